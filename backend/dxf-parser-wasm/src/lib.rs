@@ -0,0 +1,244 @@
+//! WebAssembly wrapper around `dxf-parser` for parsing drawings in the
+//! browser. Build it with `wasm-pack build --target web`.
+//!
+//! The result holds the same `Layer` and `Entity` rows the server serves,
+//! so the frontend can render a preview with the types it uses for
+//! uploaded files. Nothing is stored: `file_id` is 0 and
+//! ids are numbered from 1 in file order, as the database would assign them.
+
+use chrono::{DateTime, Utc};
+use dxf_parser::models::{
+    BoundingBox, CreateEntityInput, CreateLayerInput, ElevationRange, Entity, Layer, MODEL_LAYOUT,
+};
+use dxf_parser::{DxfParser, ParseEvent, ParseMode};
+use serde::Serialize;
+use std::collections::HashMap;
+use wasm_bindgen::prelude::*;
+
+/// Parses a DXF file and returns `{ layers, entities }`. `strict` fails on
+/// the first malformed value instead of skipping the affected entity.
+#[wasm_bindgen(js_name = parseDxf)]
+pub fn parse_dxf(data: &[u8], strict: bool) -> Result<JsValue, JsValue> {
+    let mode = if strict {
+        ParseMode::Strict
+    } else {
+        ParseMode::Lenient
+    };
+    let parsed_at = DateTime::from_timestamp_millis(js_sys::Date::now() as i64).unwrap_or_default();
+
+    let drawing = preview(data, mode, parsed_at).map_err(|e| JsError::new(&e.to_string()))?;
+    let json = serde_json::to_string(&drawing).map_err(|e| JsError::new(&e.to_string()))?;
+    js_sys::JSON::parse(&json)
+}
+
+/// A parsed drawing as the server would serve it after persisting it.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct DrawingPreview {
+    pub layers: Vec<Layer>,
+    /// Entities of all layers, ordered by draw order.
+    pub entities: Vec<Entity>,
+}
+
+fn layer(id: u64, input: CreateLayerInput, parsed_at: DateTime<Utc>) -> Layer {
+    Layer {
+        id,
+        file_id: 0,
+        name: input.name,
+        is_locked: input.is_locked,
+        is_visible: input.is_visible,
+        color: input.color,
+        line_type: input.line_type,
+        line_weight: input.line_weight,
+        min_x: None,
+        min_y: None,
+        max_x: None,
+        max_y: None,
+        min_z: None,
+        max_z: None,
+        created_at: parsed_at,
+        updated_at: parsed_at,
+    }
+}
+
+fn entity(id: u64, layer_id: u64, input: CreateEntityInput, parsed_at: DateTime<Utc>) -> Entity {
+    Entity {
+        id,
+        layer_id,
+        layout: input.layout,
+        handle: input.handle,
+        owner_handle: input.owner_handle,
+        extension_dictionary: input.extension_dictionary,
+        draw_order: input.draw_order,
+        entity_type: input.data.entity_type().to_string(),
+        data: input.data,
+        xdata: input.xdata,
+        min_x: input.min_x,
+        min_y: input.min_y,
+        max_x: input.max_x,
+        max_y: input.max_y,
+        min_z: input.min_z,
+        max_z: input.max_z,
+        created_at: parsed_at,
+        updated_at: parsed_at,
+    }
+}
+
+/// Parses `data` the way the server's parse task does, applying layout
+/// renames, draw order and layer extents once the tables arrive.
+/// `parsed_at` is used for the timestamps.
+pub fn preview(
+    data: &[u8],
+    mode: ParseMode,
+    parsed_at: DateTime<Utc>,
+) -> dxf_parser::Result<DrawingPreview> {
+    let mut parser = DxfParser::new(data, mode)?;
+    let mut drawing = DrawingPreview::default();
+    let mut layer_ids: HashMap<String, u64> = HashMap::new();
+
+    while let Some(event) = parser.next_event()? {
+        match event {
+            ParseEvent::Layer(input) => {
+                let id = drawing.layers.len() as u64 + 1;
+                if !layer_ids.contains_key(&input.name) {
+                    layer_ids.insert(input.name.clone(), id);
+                    drawing.layers.push(layer(id, input, parsed_at));
+                }
+            }
+            ParseEvent::Entity {
+                layer,
+                entity: input,
+            } => {
+                let Some(&layer_id) = layer_ids.get(&layer) else {
+                    continue;
+                };
+                let id = drawing.entities.len() as u64 + 1;
+                drawing
+                    .entities
+                    .push(entity(id, layer_id, input, parsed_at));
+            }
+            ParseEvent::Tables(tables) => {
+                let renames: HashMap<&str, &str> = tables.layout_renames().collect();
+                let draw_order: HashMap<&str, u64> = tables
+                    .draw_order
+                    .iter()
+                    .map(|(handle, order)| (handle.as_str(), *order))
+                    .collect();
+                for entity in &mut drawing.entities {
+                    if let Some(layout) = renames.get(entity.layout.as_str()) {
+                        entity.layout = layout.to_string();
+                    }
+                    if let Some(order) = entity
+                        .handle
+                        .as_deref()
+                        .and_then(|handle| draw_order.get(handle))
+                    {
+                        entity.draw_order = *order;
+                    }
+                }
+            }
+            ParseEvent::Diagnostic(_)
+            | ParseEvent::Progress(_)
+            | ParseEvent::Unsupported(_)
+            | ParseEvent::RawRecord(_) => {}
+        }
+    }
+
+    // Layer extents cover model space only, as on the server.
+    let mut extents: HashMap<u64, (BoundingBox, ElevationRange)> = HashMap::new();
+    for entity in drawing
+        .entities
+        .iter()
+        .filter(|entity| entity.layout == MODEL_LAYOUT)
+    {
+        let bbox = BoundingBox::new(entity.min_x, entity.min_y, entity.max_x, entity.max_y);
+        let z_range = ElevationRange {
+            min_z: entity.min_z,
+            max_z: entity.max_z,
+        };
+        extents
+            .entry(entity.layer_id)
+            .and_modify(|(layer_bbox, layer_z_range)| {
+                layer_bbox.merge(&bbox);
+                layer_z_range.merge(&z_range);
+            })
+            .or_insert((bbox, z_range));
+    }
+    for layer in &mut drawing.layers {
+        if let Some((bbox, z_range)) = extents.get(&layer.id) {
+            layer.min_x = Some(bbox.min_x);
+            layer.min_y = Some(bbox.min_y);
+            layer.max_x = Some(bbox.max_x);
+            layer.max_y = Some(bbox.max_y);
+            layer.min_z = Some(z_range.min_z);
+            layer.max_z = Some(z_range.max_z);
+        }
+    }
+
+    drawing
+        .entities
+        .sort_by_key(|entity| (entity.draw_order, entity.id));
+    Ok(drawing)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PARSED_AT: &str = "2024-01-01T00:00:00Z";
+
+    fn parsed_at() -> DateTime<Utc> {
+        PARSED_AT.parse().unwrap()
+    }
+
+    #[test]
+    fn test_preview_numbers_layers_and_entities_like_the_server() {
+        let content = include_bytes!("../../fixtures/sample_tables.dxf");
+        let drawing = preview(content, ParseMode::Lenient, parsed_at()).unwrap();
+
+        assert!(!drawing.layers.is_empty());
+        assert!(!drawing.entities.is_empty());
+        for (index, layer) in drawing.layers.iter().enumerate() {
+            assert_eq!(layer.id, index as u64 + 1);
+            assert_eq!(layer.file_id, 0);
+        }
+        for entity in &drawing.entities {
+            assert!(drawing
+                .layers
+                .iter()
+                .any(|layer| layer.id == entity.layer_id));
+        }
+
+        let json = serde_json::to_value(&drawing).unwrap();
+        let entity = &json["entities"][0];
+        assert_eq!(entity["entity_type"], entity["data"]["type"]);
+        assert_eq!(entity["created_at"], PARSED_AT);
+        assert!(entity["data"]["version"].is_u64());
+    }
+
+    #[test]
+    fn test_preview_computes_model_space_layer_extents() {
+        let content = b"0\nSECTION\n2\nENTITIES\n0\nLINE\n8\nWALLS\n10\n1\n20\n2\n30\n0\n11\n5\n21\n6\n31\n3\n0\nLINE\n8\nWALLS\n10\n-1\n20\n0\n30\n0\n11\n0\n21\n0\n31\n0\n0\nENDSEC\n0\nEOF\n";
+        let drawing = preview(content, ParseMode::Lenient, parsed_at()).unwrap();
+
+        let layer = drawing
+            .layers
+            .iter()
+            .find(|layer| layer.name == "WALLS")
+            .unwrap();
+        assert_eq!(layer.min_x, Some(-1.0));
+        assert_eq!(layer.min_y, Some(0.0));
+        assert_eq!(layer.max_x, Some(5.0));
+        assert_eq!(layer.max_y, Some(6.0));
+        assert_eq!(layer.max_z, Some(3.0));
+        assert_eq!(drawing.entities.len(), 2);
+        assert!(drawing.entities.iter().all(|e| e.layer_id == layer.id));
+    }
+
+    #[test]
+    fn test_preview_rejects_invalid_input_in_strict_mode() {
+        let content =
+            b"0\nSECTION\n2\nENTITIES\n0\nLINE\n8\n0\n10\nnot-a-number\n0\nENDSEC\n0\nEOF\n";
+        assert!(preview(content, ParseMode::Strict, parsed_at()).is_err());
+        assert!(preview(content, ParseMode::Lenient, parsed_at()).is_ok());
+    }
+}
@@ -0,0 +1,232 @@
+use crate::error::{Error, Result};
+use std::io::BufRead;
+
+/// Every binary DXF file starts with this 22-byte sentinel.
+pub const BINARY_SENTINEL: &[u8] = b"AutoCAD Binary DXF\r\n\x1a\0";
+
+pub fn is_binary_dxf(data: &[u8]) -> bool {
+    data.starts_with(BINARY_SENTINEL)
+}
+
+/// Decodes a binary DXF file into the alternating group code / value lines
+/// of its ASCII equivalent, so the result can be fed to the ASCII parser.
+pub fn decode_binary_dxf(data: &[u8]) -> Result<Vec<String>> {
+    let mut reader = BinaryGroupReader::new(data)?;
+
+    let mut lines = Vec::new();
+    while let Some((code, value)) = reader.next_group()? {
+        lines.push(code.to_string());
+        lines.push(value);
+    }
+
+    Ok(lines)
+}
+
+/// Reads the groups of a binary DXF file one at a time.
+///
+/// R13 and later files store group codes as 2-byte little-endian integers.
+/// R12 files use a single byte, with 255 escaping a following 2-byte code.
+pub struct BinaryGroupReader<R> {
+    reader: R,
+    pos: usize,
+    wide_codes: bool,
+    first_code: Option<i32>,
+    done: bool,
+}
+
+impl<R: BufRead> BinaryGroupReader<R> {
+    pub fn new(mut reader: R) -> Result<Self> {
+        let mut sentinel = [0u8; BINARY_SENTINEL.len()];
+        reader
+            .read_exact(&mut sentinel)
+            .map_err(|_| Error::InvalidDxf("missing binary DXF sentinel".to_string()))?;
+        if sentinel != BINARY_SENTINEL {
+            return Err(Error::InvalidDxf("missing binary DXF sentinel".to_string()));
+        }
+
+        let mut reader = Self {
+            reader,
+            pos: BINARY_SENTINEL.len(),
+            wide_codes: false,
+            first_code: None,
+            done: false,
+        };
+
+        // The first group is always (0, "SECTION"); with 1-byte codes the
+        // byte after the code is already the 'S' of the value.
+        if reader.read_u8_or_eof()?.is_some() {
+            reader.wide_codes = reader.reader.fill_buf()?.first() == Some(&0);
+            if reader.wide_codes {
+                reader.take::<1>()?;
+            }
+            reader.first_code = Some(0);
+        } else {
+            reader.done = true;
+        }
+
+        Ok(reader)
+    }
+
+    /// Returns the next group code and its value rendered as ASCII DXF
+    /// text, or `None` after the `EOF` marker or at the end of the input.
+    pub fn next_group(&mut self) -> Result<Option<(i32, String)>> {
+        if self.done {
+            return Ok(None);
+        }
+
+        let code = match self.first_code.take() {
+            Some(code) => code,
+            None => match self.read_code()? {
+                Some(code) => code,
+                None => {
+                    self.done = true;
+                    return Ok(None);
+                }
+            },
+        };
+
+        let value = self.read_value(code)?;
+        if code == 0 && value == "EOF" {
+            self.done = true;
+        }
+
+        Ok(Some((code, value)))
+    }
+
+    /// Number of bytes consumed so far, including the sentinel.
+    pub fn position(&self) -> u64 {
+        self.pos as u64
+    }
+
+    fn read_u8_or_eof(&mut self) -> Result<Option<u8>> {
+        let Some(&byte) = self.reader.fill_buf()?.first() else {
+            return Ok(None);
+        };
+        self.reader.consume(1);
+        self.pos += 1;
+        Ok(Some(byte))
+    }
+
+    fn take<const N: usize>(&mut self) -> Result<[u8; N]> {
+        let mut bytes = [0u8; N];
+        self.reader
+            .read_exact(&mut bytes)
+            .map_err(|_| Error::InvalidDxf(format!("binary DXF truncated at byte {}", self.pos)))?;
+        self.pos += N;
+        Ok(bytes)
+    }
+
+    fn read_i16(&mut self) -> Result<i16> {
+        Ok(i16::from_le_bytes(self.take()?))
+    }
+
+    fn read_code(&mut self) -> Result<Option<i32>> {
+        if self.wide_codes {
+            let Some(low) = self.read_u8_or_eof()? else {
+                return Ok(None);
+            };
+            let [high] = self.take()?;
+            return Ok(Some(u16::from_le_bytes([low, high]) as i32));
+        }
+
+        match self.read_u8_or_eof()? {
+            Some(255) => Ok(Some(self.read_i16()? as u16 as i32)),
+            Some(code) => Ok(Some(code as i32)),
+            None => Ok(None),
+        }
+    }
+
+    fn read_value(&mut self, code: i32) -> Result<String> {
+        let value = match code {
+            10..=59 | 110..=149 | 210..=239 | 460..=469 | 1010..=1059 => {
+                f64::from_le_bytes(self.take()?).to_string()
+            }
+            60..=79 | 170..=179 | 270..=289 | 370..=389 | 400..=409 | 1060..=1070 => {
+                self.read_i16()?.to_string()
+            }
+            90..=99 | 420..=429 | 440..=459 | 1071 => i32::from_le_bytes(self.take()?).to_string(),
+            160..=169 => i64::from_le_bytes(self.take()?).to_string(),
+            290..=299 => self.take::<1>()?[0].to_string(),
+            310..=319 | 1004 => {
+                let [len] = self.take()?;
+                let mut bytes = vec![0u8; len as usize];
+                self.reader.read_exact(&mut bytes).map_err(|_| {
+                    Error::InvalidDxf(format!("binary DXF truncated at byte {}", self.pos))
+                })?;
+                self.pos += bytes.len();
+                bytes.iter().map(|byte| format!("{:02X}", byte)).collect()
+            }
+            _ => self.read_string()?,
+        };
+
+        Ok(value)
+    }
+
+    fn read_string(&mut self) -> Result<String> {
+        let mut bytes = Vec::new();
+        self.reader.read_until(0, &mut bytes)?;
+        if bytes.pop() != Some(0) {
+            return Err(Error::InvalidDxf(format!(
+                "unterminated string at byte {}",
+                self.pos
+            )));
+        }
+        self.pos += bytes.len() + 1;
+        Ok(String::from_utf8_lossy(&bytes).into_owned())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn r12_group(out: &mut Vec<u8>, code: u8, value: &[u8]) {
+        out.push(code);
+        out.extend_from_slice(value);
+    }
+
+    #[test]
+    fn test_decode_r12_single_byte_codes() {
+        let mut data = BINARY_SENTINEL.to_vec();
+        r12_group(&mut data, 0, b"SECTION\0");
+        r12_group(&mut data, 2, b"ENTITIES\0");
+        r12_group(&mut data, 0, b"CIRCLE\0");
+        r12_group(&mut data, 8, b"WALLS\0");
+        r12_group(&mut data, 10, &12.5f64.to_le_bytes());
+        r12_group(&mut data, 20, &(-3.0f64).to_le_bytes());
+        r12_group(&mut data, 40, &2.0f64.to_le_bytes());
+        r12_group(&mut data, 62, &5i16.to_le_bytes());
+        data.push(255);
+        data.extend_from_slice(&1001i16.to_le_bytes());
+        data.extend_from_slice(b"ACME\0");
+        r12_group(&mut data, 0, b"ENDSEC\0");
+        r12_group(&mut data, 0, b"EOF\0");
+
+        let lines = decode_binary_dxf(&data).unwrap();
+
+        assert_eq!(
+            lines,
+            vec![
+                "0", "SECTION", "2", "ENTITIES", "0", "CIRCLE", "8", "WALLS", "10", "12.5", "20",
+                "-3", "40", "2", "62", "5", "1001", "ACME", "0", "ENDSEC", "0", "EOF",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_decode_truncated_file() {
+        let mut data = BINARY_SENTINEL.to_vec();
+        data.extend_from_slice(&0i16.to_le_bytes());
+        data.extend_from_slice(b"SECTION\0");
+        data.extend_from_slice(&10i16.to_le_bytes());
+        data.extend_from_slice(&[0, 0, 0]);
+
+        assert!(decode_binary_dxf(&data).is_err());
+    }
+
+    #[test]
+    fn test_rejects_ascii_input() {
+        assert!(!is_binary_dxf(b"0\nSECTION\n"));
+        assert!(decode_binary_dxf(b"0\nSECTION\n").is_err());
+    }
+}
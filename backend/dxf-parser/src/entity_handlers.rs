@@ -0,0 +1,1767 @@
+use crate::error::Result;
+use crate::groups::Group;
+use crate::models::geometry::{self, ClipBoundary, ClipKind, EntityProperties};
+use crate::models::{
+    BoundingBox, CreateDimStyleInput, CreateTextStyleInput, ElevationRange, Geometry, Point, Point3,
+};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::f64::consts::{PI, TAU};
+use std::str::FromStr;
+use std::sync::Arc;
+
+/// Straight segments a full turn of an elliptic hatch edge is flattened
+/// into.
+const SEGMENTS_PER_TURN: f64 = 64.0;
+
+/// Straight segments a spline hatch edge is flattened into, per control
+/// point.
+const SEGMENTS_PER_CONTROL_POINT: usize = 8;
+
+/// Reads one type of entity. The parser has already read the entity's `0`
+/// group; the handler reads the rest through the [`EntityReader`] and
+/// returns the entity's geometry and extents, or `None` to leave it out.
+///
+/// Handlers are looked up by entity name in an [`EntityHandlerRegistry`].
+/// The stored `entity_type` is that of the returned geometry, so a handler
+/// for a vendor entity stores it as the standard entity it draws like.
+pub trait EntityHandler: Send + Sync {
+    fn parse(&self, reader: &mut dyn EntityReader) -> Result<Option<ParsedEntity>>;
+}
+
+/// The groups of the entity being read, and the tables read before the
+/// entities.
+pub trait EntityReader {
+    /// The next group that is specific to the entity type. Handles, owner
+    /// references, `102` application groups and XDATA are kept by the
+    /// parser.
+    fn next_field(&mut self) -> Result<Option<Group>>;
+
+    /// Starts the next sub-entity, such as a POLYLINE's VERTEX, if one of
+    /// `kind` follows.
+    fn next_sub_entity(&mut self, kind: &str) -> Result<bool>;
+
+    /// The next group of the current sub-entity, as it is.
+    fn next_sub_entity_field(&mut self) -> Result<Option<Group>>;
+
+    /// Reports a malformed value. In lenient mode the entity is then
+    /// skipped; in strict mode the parse fails.
+    fn invalid_value(&mut self, group: &Group) -> Result<()>;
+
+    fn text_style(&self, name: &str) -> Option<&CreateTextStyleInput>;
+
+    fn dim_style(&self, name: &str) -> Option<&CreateDimStyleInput>;
+
+    /// The name of the LAYER table record with this handle.
+    fn layer_name(&self, handle: &str) -> Option<&str>;
+}
+
+impl dyn EntityReader + '_ {
+    /// Parses a numeric group value, or reports it and returns `default`.
+    pub fn number<T: FromStr>(&mut self, group: &Group, default: T) -> Result<T> {
+        match group.value().parse() {
+            Ok(parsed) => Ok(parsed),
+            Err(_) => {
+                self.invalid_value(group)?;
+                Ok(default)
+            }
+        }
+    }
+}
+
+/// An entity as read by its handler. The bounding box is in the XY plane;
+/// the elevation range includes any thickness.
+#[derive(Debug, Clone)]
+pub struct ParsedEntity {
+    pub layer: String,
+    pub geometry: Geometry,
+    pub bbox: BoundingBox,
+    pub elevation: ElevationRange,
+}
+
+/// Entity handlers by entity name. The default registry has a handler for
+/// every entity type the parser supports; entities without a handler are
+/// counted as unsupported and kept as raw records.
+#[derive(Clone)]
+pub struct EntityHandlerRegistry {
+    handlers: HashMap<String, Arc<dyn EntityHandler>>,
+}
+
+impl EntityHandlerRegistry {
+    /// A registry without any handlers.
+    pub fn empty() -> Self {
+        Self {
+            handlers: HashMap::new(),
+        }
+    }
+
+    /// Registers the handler for an entity name, replacing any handler
+    /// registered for it before. Names are matched regardless of case.
+    pub fn register(&mut self, name: &str, handler: impl EntityHandler + 'static) -> &mut Self {
+        self.handlers
+            .insert(name.to_ascii_uppercase(), Arc::new(handler));
+        self
+    }
+
+    /// The handler for an entity name, in any case.
+    pub fn get(&self, name: &str) -> Option<&dyn EntityHandler> {
+        // Entity names are upper case in files, so only others are copied.
+        self.handlers
+            .get(name)
+            .or_else(|| self.handlers.get(&name.to_ascii_uppercase()))
+            .map(|handler| handler.as_ref())
+    }
+}
+
+impl Default for EntityHandlerRegistry {
+    fn default() -> Self {
+        let mut registry = Self::empty();
+        registry
+            .register("LINE", LineHandler)
+            .register("POLYLINE", PolylineHandler)
+            .register("LWPOLYLINE", LwPolylineHandler)
+            .register("ARC", ArcHandler)
+            .register("CIRCLE", CircleHandler)
+            .register("TEXT", TextHandler)
+            .register("MTEXT", MTextHandler)
+            .register("DIMENSION", DimensionHandler)
+            .register("VIEWPORT", ViewportHandler)
+            .register("IMAGE", ImageHandler)
+            .register("WIPEOUT", WipeoutHandler)
+            .register("HATCH", HatchHandler)
+            .register("ACAD_TABLE", TableHandler)
+            .register("PDFUNDERLAY", UnderlayHandler(Geometry::PdfUnderlay))
+            .register("DWFUNDERLAY", UnderlayHandler(Geometry::DwfUnderlay))
+            .register("DGNUNDERLAY", UnderlayHandler(Geometry::DgnUnderlay));
+        registry
+    }
+}
+
+impl std::fmt::Debug for EntityHandlerRegistry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut names: Vec<&String> = self.handlers.keys().collect();
+        names.sort();
+        f.debug_struct("EntityHandlerRegistry")
+            .field("handlers", &names)
+            .finish()
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LineHandler;
+
+impl EntityHandler for LineHandler {
+    fn parse(&self, reader: &mut dyn EntityReader) -> Result<Option<ParsedEntity>> {
+        let mut layer_name = "0".to_string();
+        let mut line_type: Option<String> = None;
+        let mut line_type_scale: Option<f64> = None;
+        let mut pen = Pen::default();
+        let mut elevation = 0.0;
+        let mut thickness = 0.0;
+        let mut x1: f64 = 0.0;
+        let mut y1: f64 = 0.0;
+        let mut x2: f64 = 0.0;
+        let mut y2: f64 = 0.0;
+        let mut z1: Option<f64> = None;
+        let mut z2: Option<f64> = None;
+
+        while let Some(group) = reader.next_field()? {
+            let value = group.value();
+
+            match group.code.as_str() {
+                "8" => layer_name = value.to_string(),
+                "6" => line_type = Some(value.to_string()),
+                "48" => line_type_scale = Some(reader.number(&group, 1.0)?),
+                "62" | "370" | "420" => pen.read(reader, &group)?,
+                "38" => elevation = reader.number(&group, 0.0)?,
+                "39" => thickness = reader.number(&group, 0.0)?,
+                "10" => x1 = reader.number(&group, 0.0)?,
+                "20" => y1 = reader.number(&group, 0.0)?,
+                "11" => x2 = reader.number(&group, 0.0)?,
+                "21" => y2 = reader.number(&group, 0.0)?,
+                "30" => z1 = Some(reader.number(&group, 0.0)?),
+                "31" => z2 = Some(reader.number(&group, 0.0)?),
+                _ => {}
+            }
+        }
+
+        let z1 = z1.unwrap_or(elevation);
+        let z2 = z2.unwrap_or(elevation);
+        let bbox = BoundingBox::new(x1.min(x2), y1.min(y2), x1.max(x2), y1.max(y2));
+        let mut z_range = ElevationRange::from_z(z1);
+        z_range.expand(z2);
+        let z_range = z_range.extrude(thickness);
+
+        Ok(Some(ParsedEntity {
+            layer: layer_name,
+            geometry: Geometry::Line(geometry::Line {
+                start: Point3::new(x1, y1, z1),
+                end: Point3::new(x2, y2, z2),
+                properties: properties(line_type, line_type_scale, thickness, pen),
+            }),
+            bbox,
+            elevation: z_range,
+        }))
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PolylineHandler;
+
+impl EntityHandler for PolylineHandler {
+    fn parse(&self, reader: &mut dyn EntityReader) -> Result<Option<ParsedEntity>> {
+        let mut layer_name = "0".to_string();
+        let mut line_type: Option<String> = None;
+        let mut line_type_scale: Option<f64> = None;
+        let mut pen = Pen::default();
+        let mut elevation = 0.0;
+        let mut thickness = 0.0;
+        let mut flags: i32 = 0;
+
+        while let Some(group) = reader.next_field()? {
+            let value = group.value();
+
+            match group.code.as_str() {
+                "8" => layer_name = value.to_string(),
+                "6" => line_type = Some(value.to_string()),
+                "48" => line_type_scale = Some(reader.number(&group, 1.0)?),
+                "62" | "370" | "420" => pen.read(reader, &group)?,
+                "38" => elevation = reader.number(&group, 0.0)?,
+                "39" => thickness = reader.number(&group, 0.0)?,
+                // The POLYLINE "point" is always 0,0 in X and Y; its Z is the
+                // elevation of a 2D polyline.
+                "30" => elevation = reader.number(&group, 0.0)?,
+                "70" => flags = reader.number(&group, 0)?,
+                _ => {}
+            }
+        }
+
+        // 3D polylines and polyface/polygon meshes carry a Z per vertex.
+        let is_3d = flags & (8 | 16 | 64) != 0;
+        let mut vertices = Vec::new();
+        let mut bulges = Vec::new();
+        while reader.next_sub_entity("VERTEX")? {
+            let mut x = 0.0;
+            let mut y = 0.0;
+            let mut z = elevation;
+            let mut bulge = 0.0;
+
+            while let Some(group) = reader.next_sub_entity_field()? {
+                match group.code.as_str() {
+                    "10" => x = reader.number(&group, 0.0)?,
+                    "20" => y = reader.number(&group, 0.0)?,
+                    "30" if is_3d => z = reader.number(&group, 0.0)?,
+                    "42" => bulge = reader.number(&group, 0.0)?,
+                    _ => {}
+                }
+            }
+
+            vertices.push(Point3::new(x, y, z));
+            bulges.push(bulge);
+        }
+        Ok(polyline(
+            layer_name,
+            vertices,
+            bulges,
+            flags & 1 != 0,
+            thickness,
+            properties(line_type, line_type_scale, thickness, pen),
+        ))
+    }
+}
+
+/// An LWPOLYLINE: a 2D polyline with its vertices (10/20, each starting a
+/// new vertex) and their bulges (42) inline, read as a [`geometry::Polyline`].
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LwPolylineHandler;
+
+impl EntityHandler for LwPolylineHandler {
+    fn parse(&self, reader: &mut dyn EntityReader) -> Result<Option<ParsedEntity>> {
+        let mut layer_name = "0".to_string();
+        let mut line_type: Option<String> = None;
+        let mut line_type_scale: Option<f64> = None;
+        let mut pen = Pen::default();
+        let mut elevation = 0.0;
+        let mut thickness = 0.0;
+        let mut flags: i32 = 0;
+        let mut points: Vec<[f64; 2]> = Vec::new();
+        let mut bulges: Vec<f64> = Vec::new();
+
+        while let Some(group) = reader.next_field()? {
+            let value = group.value();
+
+            match group.code.as_str() {
+                "8" => layer_name = value.to_string(),
+                "6" => line_type = Some(value.to_string()),
+                "48" => line_type_scale = Some(reader.number(&group, 1.0)?),
+                "62" | "370" | "420" => pen.read(reader, &group)?,
+                "38" => elevation = reader.number(&group, 0.0)?,
+                "39" => thickness = reader.number(&group, 0.0)?,
+                "70" => flags = reader.number(&group, 0)?,
+                "10" => {
+                    points.push([reader.number(&group, 0.0)?, 0.0]);
+                    bulges.push(0.0);
+                }
+                "20" => {
+                    if let Some(point) = points.last_mut() {
+                        point[1] = reader.number(&group, 0.0)?;
+                    }
+                }
+                "42" => {
+                    if let Some(bulge) = bulges.last_mut() {
+                        *bulge = reader.number(&group, 0.0)?;
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        let vertices = points
+            .iter()
+            .map(|&[x, y]| Point3::new(x, y, elevation))
+            .collect();
+        Ok(polyline(
+            layer_name,
+            vertices,
+            bulges,
+            flags & 1 != 0,
+            thickness,
+            properties(line_type, line_type_scale, thickness, pen),
+        ))
+    }
+}
+
+/// A polyline entity of its vertices and the bulges of the segments
+/// starting at them; `None` without vertices.
+fn polyline(
+    layer: String,
+    vertices: Vec<Point3>,
+    mut bulges: Vec<f64>,
+    closed: bool,
+    thickness: f64,
+    properties: EntityProperties,
+) -> Option<ParsedEntity> {
+    if bulges.iter().all(|bulge| *bulge == 0.0) {
+        bulges.clear();
+    }
+
+    let first = vertices.first()?;
+    let mut bbox = BoundingBox::from_point(first.x, first.y);
+    let mut z_range = ElevationRange::from_z(first.z);
+    for vertex in &vertices {
+        bbox.expand(vertex.x, vertex.y);
+        z_range.expand(vertex.z);
+    }
+    for (index, bulge) in bulges.iter().enumerate() {
+        let next = match vertices.get(index + 1) {
+            Some(next) => next,
+            None if closed => first,
+            None => break,
+        };
+        expand_by_bulge(&mut bbox, &vertices[index], next, *bulge);
+    }
+    let z_range = z_range.extrude(thickness);
+
+    Some(ParsedEntity {
+        layer,
+        geometry: Geometry::Polyline(geometry::Polyline {
+            vertices,
+            bulges,
+            closed,
+            properties,
+        }),
+        bbox,
+        elevation: z_range,
+    })
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ArcHandler;
+
+impl EntityHandler for ArcHandler {
+    fn parse(&self, reader: &mut dyn EntityReader) -> Result<Option<ParsedEntity>> {
+        let mut layer_name = "0".to_string();
+        let mut line_type: Option<String> = None;
+        let mut line_type_scale: Option<f64> = None;
+        let mut pen = Pen::default();
+        let mut elevation = 0.0;
+        let mut thickness = 0.0;
+        let mut cx = 0.0;
+        let mut cy = 0.0;
+        let mut cz: Option<f64> = None;
+        let mut radius = 0.0;
+        let mut start_angle = 0.0;
+        let mut end_angle = 360.0;
+
+        while let Some(group) = reader.next_field()? {
+            let value = group.value();
+
+            match group.code.as_str() {
+                "8" => layer_name = value.to_string(),
+                "6" => line_type = Some(value.to_string()),
+                "48" => line_type_scale = Some(reader.number(&group, 1.0)?),
+                "62" | "370" | "420" => pen.read(reader, &group)?,
+                "38" => elevation = reader.number(&group, 0.0)?,
+                "39" => thickness = reader.number(&group, 0.0)?,
+                "10" => cx = reader.number(&group, 0.0)?,
+                "20" => cy = reader.number(&group, 0.0)?,
+                "30" => cz = Some(reader.number(&group, 0.0)?),
+                "40" => radius = reader.number(&group, 0.0)?,
+                "50" => start_angle = reader.number(&group, 0.0)?,
+                "51" => end_angle = reader.number(&group, 360.0)?,
+                _ => {}
+            }
+        }
+
+        let cz = cz.unwrap_or(elevation);
+        let bbox = BoundingBox::new(cx - radius, cy - radius, cx + radius, cy + radius);
+        let z_range = ElevationRange::from_z(cz).extrude(thickness);
+
+        Ok(Some(ParsedEntity {
+            layer: layer_name,
+            geometry: Geometry::Arc(geometry::Arc {
+                center: Point3::new(cx, cy, cz),
+                radius,
+                start_angle,
+                end_angle,
+                properties: properties(line_type, line_type_scale, thickness, pen),
+            }),
+            bbox,
+            elevation: z_range,
+        }))
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CircleHandler;
+
+impl EntityHandler for CircleHandler {
+    fn parse(&self, reader: &mut dyn EntityReader) -> Result<Option<ParsedEntity>> {
+        let mut layer_name = "0".to_string();
+        let mut line_type: Option<String> = None;
+        let mut line_type_scale: Option<f64> = None;
+        let mut pen = Pen::default();
+        let mut elevation = 0.0;
+        let mut thickness = 0.0;
+        let mut cx = 0.0;
+        let mut cy = 0.0;
+        let mut cz: Option<f64> = None;
+        let mut radius = 0.0;
+
+        while let Some(group) = reader.next_field()? {
+            let value = group.value();
+
+            match group.code.as_str() {
+                "8" => layer_name = value.to_string(),
+                "6" => line_type = Some(value.to_string()),
+                "48" => line_type_scale = Some(reader.number(&group, 1.0)?),
+                "62" | "370" | "420" => pen.read(reader, &group)?,
+                "38" => elevation = reader.number(&group, 0.0)?,
+                "39" => thickness = reader.number(&group, 0.0)?,
+                "10" => cx = reader.number(&group, 0.0)?,
+                "20" => cy = reader.number(&group, 0.0)?,
+                "30" => cz = Some(reader.number(&group, 0.0)?),
+                "40" => radius = reader.number(&group, 0.0)?,
+                _ => {}
+            }
+        }
+
+        let cz = cz.unwrap_or(elevation);
+        let bbox = BoundingBox::new(cx - radius, cy - radius, cx + radius, cy + radius);
+        let z_range = ElevationRange::from_z(cz).extrude(thickness);
+
+        Ok(Some(ParsedEntity {
+            layer: layer_name,
+            geometry: Geometry::Circle(geometry::Circle {
+                center: Point3::new(cx, cy, cz),
+                radius,
+                properties: properties(line_type, line_type_scale, thickness, pen),
+            }),
+            bbox,
+            elevation: z_range,
+        }))
+    }
+}
+
+/// A paper space window onto model space. The center, width and height
+/// are in paper units; the view center and height are in model units.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ViewportHandler;
+
+impl EntityHandler for ViewportHandler {
+    fn parse(&self, reader: &mut dyn EntityReader) -> Result<Option<ParsedEntity>> {
+        let mut layer_name = "0".to_string();
+        let mut center = [0.0; 3];
+        let mut width = 0.0;
+        let mut height = 0.0;
+        let mut status: i32 = 0;
+        let mut id: i32 = 0;
+        let mut view_center = [0.0; 2];
+        let mut view_target = [0.0; 3];
+        let mut view_direction = [0.0, 0.0, 1.0];
+        let mut view_height = 0.0;
+        let mut twist_angle = 0.0;
+        let mut frozen_layers: Vec<String> = Vec::new();
+
+        while let Some(group) = reader.next_field()? {
+            let value = group.value();
+
+            match group.code.as_str() {
+                "8" => layer_name = value.to_string(),
+                "10" => center[0] = reader.number(&group, 0.0)?,
+                "20" => center[1] = reader.number(&group, 0.0)?,
+                "30" => center[2] = reader.number(&group, 0.0)?,
+                "40" => width = reader.number(&group, 0.0)?,
+                "41" => height = reader.number(&group, 0.0)?,
+                "68" => status = reader.number(&group, 0)?,
+                "69" => id = reader.number(&group, 0)?,
+                "12" => view_center[0] = reader.number(&group, 0.0)?,
+                "22" => view_center[1] = reader.number(&group, 0.0)?,
+                "16" => view_direction[0] = reader.number(&group, 0.0)?,
+                "26" => view_direction[1] = reader.number(&group, 0.0)?,
+                "36" => view_direction[2] = reader.number(&group, 0.0)?,
+                "17" => view_target[0] = reader.number(&group, 0.0)?,
+                "27" => view_target[1] = reader.number(&group, 0.0)?,
+                "37" => view_target[2] = reader.number(&group, 0.0)?,
+                "45" => view_height = reader.number(&group, 0.0)?,
+                "51" => twist_angle = reader.number(&group, 0.0)?,
+                // Frozen layers are stored as LAYER handles; unknown
+                // handles are kept as they are.
+                "331" => {
+                    let handle = value.to_ascii_uppercase();
+                    frozen_layers.push(reader.layer_name(&handle).map_or(handle, str::to_string));
+                }
+                _ => {}
+            }
+        }
+
+        let bbox = BoundingBox::new(
+            center[0] - width / 2.0,
+            center[1] - height / 2.0,
+            center[0] + width / 2.0,
+            center[1] + height / 2.0,
+        );
+
+        Ok(Some(ParsedEntity {
+            layer: layer_name,
+            geometry: Geometry::Viewport(geometry::Viewport {
+                center: point3(center),
+                width,
+                height,
+                status,
+                id,
+                view_center: Point {
+                    x: view_center[0],
+                    y: view_center[1],
+                },
+                view_target: point3(view_target),
+                view_direction: point3(view_direction),
+                view_height,
+                twist_angle,
+                frozen_layers,
+            }),
+            bbox,
+            elevation: ElevationRange::from_z(center[2]),
+        }))
+    }
+}
+
+/// A raster image placed by its lower-left corner and the vectors of one
+/// pixel along U and V. The file path is on the IMAGEDEF object (340).
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ImageHandler;
+
+impl EntityHandler for ImageHandler {
+    fn parse(&self, reader: &mut dyn EntityReader) -> Result<Option<ParsedEntity>> {
+        let mut layer_name = "0".to_string();
+        let mut insertion = [0.0; 3];
+        let mut u_vector = [1.0, 0.0, 0.0];
+        let mut v_vector = [0.0, 1.0, 0.0];
+        let mut size = [0.0; 2];
+        let mut definition: Option<String> = None;
+        let mut display_flags: i32 = 0;
+        let mut clipping = false;
+        let mut brightness: i32 = 50;
+        let mut contrast: i32 = 50;
+        let mut fade: i32 = 0;
+        let mut clip_type: i32 = 1;
+        let mut clip_vertices: Vec<Point> = Vec::new();
+
+        while let Some(group) = reader.next_field()? {
+            let value = group.value();
+
+            match group.code.as_str() {
+                "8" => layer_name = value.to_string(),
+                "10" => insertion[0] = reader.number(&group, 0.0)?,
+                "20" => insertion[1] = reader.number(&group, 0.0)?,
+                "30" => insertion[2] = reader.number(&group, 0.0)?,
+                "11" => u_vector[0] = reader.number(&group, 0.0)?,
+                "21" => u_vector[1] = reader.number(&group, 0.0)?,
+                "31" => u_vector[2] = reader.number(&group, 0.0)?,
+                "12" => v_vector[0] = reader.number(&group, 0.0)?,
+                "22" => v_vector[1] = reader.number(&group, 0.0)?,
+                "32" => v_vector[2] = reader.number(&group, 0.0)?,
+                "13" => size[0] = reader.number(&group, 0.0)?,
+                "23" => size[1] = reader.number(&group, 0.0)?,
+                "340" => definition = Some(value.to_ascii_uppercase()),
+                "70" => display_flags = reader.number(&group, 0)?,
+                "280" => clipping = reader.number::<i32>(&group, 0)? != 0,
+                "281" => brightness = reader.number(&group, 50)?,
+                "282" => contrast = reader.number(&group, 50)?,
+                "283" => fade = reader.number(&group, 0)?,
+                "71" => clip_type = reader.number(&group, 1)?,
+                "14" => clip_vertices.push(Point {
+                    x: reader.number(&group, 0.0)?,
+                    y: 0.0,
+                }),
+                "24" => {
+                    let y = reader.number(&group, 0.0)?;
+                    if let Some(vertex) = clip_vertices.last_mut() {
+                        vertex.y = y;
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        // Corners of the image: insertion + i * U + j * V for i, j in
+        // {0, width} x {0, height}.
+        let mut bbox = BoundingBox::from_point(insertion[0], insertion[1]);
+        let mut z_range = ElevationRange::from_z(insertion[2]);
+        for (i, j) in [(size[0], 0.0), (0.0, size[1]), (size[0], size[1])] {
+            bbox.expand(
+                insertion[0] + i * u_vector[0] + j * v_vector[0],
+                insertion[1] + i * u_vector[1] + j * v_vector[1],
+            );
+            z_range.expand(insertion[2] + i * u_vector[2] + j * v_vector[2]);
+        }
+
+        Ok(Some(ParsedEntity {
+            layer: layer_name,
+            geometry: Geometry::Image(geometry::Image {
+                insertion: point3(insertion),
+                u_vector: point3(u_vector),
+                v_vector: point3(v_vector),
+                width_px: size[0],
+                height_px: size[1],
+                definition,
+                display_flags,
+                brightness,
+                contrast,
+                fade,
+                clip_boundary: clip_boundary(clipping, clip_type, clip_vertices),
+            }),
+            bbox,
+            elevation: z_range,
+        }))
+    }
+}
+
+/// A WIPEOUT: a polygon that masks the entities drawn before it. It is
+/// stored like an image of one by one pixels, with its boundary in
+/// image coordinates from -0.5 to 0.5 and Y pointing down.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct WipeoutHandler;
+
+impl EntityHandler for WipeoutHandler {
+    fn parse(&self, reader: &mut dyn EntityReader) -> Result<Option<ParsedEntity>> {
+        let mut layer_name = "0".to_string();
+        let mut insertion = [0.0; 3];
+        let mut u_vector = [1.0, 0.0, 0.0];
+        let mut v_vector = [0.0, 1.0, 0.0];
+        let mut clip_type: i32 = 1;
+        let mut clip_vertices: Vec<(f64, f64)> = Vec::new();
+
+        while let Some(group) = reader.next_field()? {
+            let value = group.value();
+
+            match group.code.as_str() {
+                "8" => layer_name = value.to_string(),
+                "10" => insertion[0] = reader.number(&group, 0.0)?,
+                "20" => insertion[1] = reader.number(&group, 0.0)?,
+                "30" => insertion[2] = reader.number(&group, 0.0)?,
+                "11" => u_vector[0] = reader.number(&group, 0.0)?,
+                "21" => u_vector[1] = reader.number(&group, 0.0)?,
+                "31" => u_vector[2] = reader.number(&group, 0.0)?,
+                "12" => v_vector[0] = reader.number(&group, 0.0)?,
+                "22" => v_vector[1] = reader.number(&group, 0.0)?,
+                "32" => v_vector[2] = reader.number(&group, 0.0)?,
+                "71" => clip_type = reader.number(&group, 1)?,
+                "14" => clip_vertices.push((reader.number(&group, 0.0)?, 0.0)),
+                "24" => {
+                    let y = reader.number(&group, 0.0)?;
+                    if let Some(vertex) = clip_vertices.last_mut() {
+                        vertex.1 = y;
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        if clip_type == 1 && clip_vertices.len() == 2 {
+            let [(x1, y1), (x2, y2)] = [clip_vertices[0], clip_vertices[1]];
+            clip_vertices = vec![(x1, y1), (x2, y1), (x2, y2), (x1, y2)];
+        }
+        if clip_vertices.is_empty() {
+            clip_vertices = vec![(-0.5, -0.5), (0.5, -0.5), (0.5, 0.5), (-0.5, 0.5)];
+        }
+        // Closing vertices repeat the first one.
+        if clip_vertices.len() > 1 && clip_vertices.first() == clip_vertices.last() {
+            clip_vertices.pop();
+        }
+
+        let boundary: Vec<Point3> = clip_vertices
+            .iter()
+            .map(|(x, y)| {
+                let (i, j) = (x + 0.5, 0.5 - y);
+                point3(
+                    [0, 1, 2].map(|axis| insertion[axis] + i * u_vector[axis] + j * v_vector[axis]),
+                )
+            })
+            .collect();
+
+        let mut bbox = BoundingBox::from_point(boundary[0].x, boundary[0].y);
+        let mut z_range = ElevationRange::from_z(boundary[0].z);
+        for point in &boundary {
+            bbox.expand(point.x, point.y);
+            z_range.expand(point.z);
+        }
+
+        Ok(Some(ParsedEntity {
+            layer: layer_name,
+            geometry: Geometry::Wipeout(geometry::Wipeout { boundary }),
+            bbox,
+            elevation: z_range,
+        }))
+    }
+}
+
+/// A HATCH. The boundary paths follow group 91, each starting with its
+/// 92 path type flags: polyline paths (flag 2) list their vertices and
+/// bulges, other paths their edges, each starting with a 72 edge type. The
+/// pattern follows from the 75 hatch style on. Gradients, seed points and
+/// the associated boundary objects are not kept.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct HatchHandler;
+
+/// The part of a HATCH being read; groups such as 10 and 72 mean
+/// different things in each.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum HatchPart {
+    Header,
+    Paths,
+    Pattern,
+}
+
+/// One boundary path as it is read.
+#[derive(Debug, Default)]
+struct HatchPath {
+    flags: i32,
+    /// The vertices and bulges of a polyline path.
+    vertices: Vec<Point>,
+    bulges: Vec<f64>,
+    edges: Vec<HatchEdge>,
+}
+
+/// One edge of a boundary path as it is read: a line (type 1) from its
+/// 10 point, a circular (2) or elliptic (3) arc around its 10 point, or a
+/// spline (4) of its 10 control points. Each edge ends where the next one
+/// starts.
+#[derive(Debug)]
+struct HatchEdge {
+    kind: i32,
+    /// Groups 10/20.
+    points: Vec<Point>,
+    /// Groups 11/21: the end of a line, the end of an ellipse's major axis
+    /// from its center, or the fit points of a spline.
+    second_points: Vec<Point>,
+    /// Group 40: the radius, the ratio of the minor axis to the major one,
+    /// or the knots of a spline.
+    values: Vec<f64>,
+    /// Group 42: the weights of a spline's control points.
+    weights: Vec<f64>,
+    start_angle: f64,
+    end_angle: f64,
+    counterclockwise: bool,
+    degree: usize,
+}
+
+impl HatchEdge {
+    fn new(kind: i32) -> Self {
+        Self {
+            kind,
+            points: Vec::new(),
+            second_points: Vec::new(),
+            values: Vec::new(),
+            weights: Vec::new(),
+            start_angle: 0.0,
+            end_angle: 360.0,
+            counterclockwise: true,
+            degree: 3,
+        }
+    }
+
+    /// Adds the edge's start and the points along it to a loop, with the
+    /// bulges of the segments starting at them.
+    fn append_to(&self, vertices: &mut Vec<Point>, bulges: &mut Vec<f64>) {
+        // Clockwise arcs have their angles measured clockwise.
+        let direction = if self.counterclockwise { 1.0 } else { -1.0 };
+
+        match self.kind {
+            1 => {
+                vertices.extend(self.points.first());
+                bulges.push(0.0);
+            }
+            2 => {
+                let (Some(center), Some(&radius)) = (self.points.first(), self.values.first())
+                else {
+                    return;
+                };
+                let start = self.start_angle.to_radians() * direction;
+                let sweep = match (self.end_angle - self.start_angle).rem_euclid(360.0) {
+                    0.0 => TAU,
+                    sweep => sweep.to_radians(),
+                } * direction;
+                // A full circle is two half turns; one bulge cannot hold it.
+                let parts = if sweep.abs() > PI { 2 } else { 1 };
+                for part in 0..parts {
+                    let (sin, cos) = (start + sweep * part as f64 / parts as f64).sin_cos();
+                    vertices.push(Point {
+                        x: center.x + radius * cos,
+                        y: center.y + radius * sin,
+                    });
+                    bulges.push((sweep / parts as f64 / 4.0).tan());
+                }
+            }
+            3 => {
+                let (Some(center), Some(major), Some(&ratio)) = (
+                    self.points.first(),
+                    self.second_points.first(),
+                    self.values.first(),
+                ) else {
+                    return;
+                };
+                // The angles are to points on the ellipse; its parameters
+                // are those of the points on the circle around it.
+                let parameter = |angle: f64| {
+                    let (sin, cos) = angle.to_radians().sin_cos();
+                    (sin / ratio).atan2(cos)
+                };
+                let start = parameter(self.start_angle);
+                let sweep = match (parameter(self.end_angle) - start).rem_euclid(TAU) {
+                    0.0 => TAU,
+                    sweep => sweep,
+                } * direction;
+                let start = start * direction;
+                let count = (sweep.abs() / TAU * SEGMENTS_PER_TURN).ceil().max(1.0) as usize;
+                for index in 0..count {
+                    let (sin, cos) = (start + sweep * index as f64 / count as f64).sin_cos();
+                    vertices.push(Point {
+                        x: center.x + major.x * cos - major.y * ratio * sin,
+                        y: center.y + major.y * cos + major.x * ratio * sin,
+                    });
+                    bulges.push(0.0);
+                }
+            }
+            4 => {
+                let points = spline_points(self.degree, &self.values, &self.points, &self.weights)
+                    .unwrap_or_else(|| {
+                        if self.second_points.is_empty() {
+                            self.points.clone()
+                        } else {
+                            self.second_points.clone()
+                        }
+                    });
+                let count = points.len().saturating_sub(1).max(1);
+                for point in points.into_iter().take(count) {
+                    vertices.push(point);
+                    bulges.push(0.0);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+impl HatchPath {
+    fn finish(self, elevation: f64) -> geometry::HatchLoop {
+        let (mut vertices, mut bulges) = (self.vertices, self.bulges);
+        for edge in &self.edges {
+            edge.append_to(&mut vertices, &mut bulges);
+        }
+        // Closing vertices repeat the first one.
+        if vertices.len() > 1 && vertices.first() == vertices.last() {
+            vertices.pop();
+            bulges.truncate(vertices.len());
+        }
+        if bulges.iter().all(|bulge| *bulge == 0.0) {
+            bulges.clear();
+        }
+        geometry::HatchLoop {
+            // Every loop is kept as a polyline.
+            flags: self.flags & !2,
+            vertices: vertices
+                .iter()
+                .map(|vertex| Point3::new(vertex.x, vertex.y, elevation))
+                .collect(),
+            bulges,
+        }
+    }
+}
+
+impl EntityHandler for HatchHandler {
+    fn parse(&self, reader: &mut dyn EntityReader) -> Result<Option<ParsedEntity>> {
+        let mut layer_name = "0".to_string();
+        let mut line_type: Option<String> = None;
+        let mut line_type_scale: Option<f64> = None;
+        let mut pen = Pen::default();
+        let mut part = HatchPart::Header;
+        let mut hatch = geometry::Hatch {
+            pattern_scale: 1.0,
+            ..Default::default()
+        };
+        let mut elevation = 0.0;
+        let mut paths: Vec<HatchPath> = Vec::new();
+
+        while let Some(group) = reader.next_field()? {
+            let value = group.value();
+
+            match (part, group.code.as_str()) {
+                (_, "8") => layer_name = value.to_string(),
+                (_, "6") => line_type = Some(value.to_string()),
+                (_, "48") => line_type_scale = Some(reader.number(&group, 1.0)?),
+                (_, "62" | "370" | "420") => pen.read(reader, &group)?,
+                (HatchPart::Header, "2") => hatch.pattern_name = value.to_string(),
+                (HatchPart::Header, "70") => hatch.solid = reader.number(&group, 0)? & 1 != 0,
+                (HatchPart::Header, "30") => elevation = reader.number(&group, 0.0)?,
+                (HatchPart::Header, "91") => part = HatchPart::Paths,
+                (HatchPart::Paths, "92") => paths.push(HatchPath {
+                    flags: reader.number(&group, 0)?,
+                    ..Default::default()
+                }),
+                (HatchPart::Paths, "75") | (HatchPart::Header, "75") => {
+                    hatch.style = reader.number(&group, 0)?;
+                    part = HatchPart::Pattern;
+                }
+                (HatchPart::Paths, code) => {
+                    let Some(path) = paths.last_mut() else {
+                        continue;
+                    };
+                    if path.flags & 2 != 0 {
+                        match code {
+                            "10" => {
+                                path.vertices.push(Point {
+                                    x: reader.number(&group, 0.0)?,
+                                    y: 0.0,
+                                });
+                                path.bulges.push(0.0);
+                            }
+                            "20" => {
+                                if let Some(vertex) = path.vertices.last_mut() {
+                                    vertex.y = reader.number(&group, 0.0)?;
+                                }
+                            }
+                            "42" => {
+                                if let Some(bulge) = path.bulges.last_mut() {
+                                    *bulge = reader.number(&group, 0.0)?;
+                                }
+                            }
+                            _ => {}
+                        }
+                        continue;
+                    }
+                    if code == "72" {
+                        path.edges.push(HatchEdge::new(reader.number(&group, 0)?));
+                        continue;
+                    }
+                    let Some(edge) = path.edges.last_mut() else {
+                        continue;
+                    };
+                    match code {
+                        "10" => edge.points.push(Point {
+                            x: reader.number(&group, 0.0)?,
+                            y: 0.0,
+                        }),
+                        "20" => {
+                            if let Some(point) = edge.points.last_mut() {
+                                point.y = reader.number(&group, 0.0)?;
+                            }
+                        }
+                        "11" => edge.second_points.push(Point {
+                            x: reader.number(&group, 0.0)?,
+                            y: 0.0,
+                        }),
+                        "21" => {
+                            if let Some(point) = edge.second_points.last_mut() {
+                                point.y = reader.number(&group, 0.0)?;
+                            }
+                        }
+                        "40" => edge.values.push(reader.number(&group, 0.0)?),
+                        "42" => edge.weights.push(reader.number(&group, 1.0)?),
+                        "50" => edge.start_angle = reader.number(&group, 0.0)?,
+                        "51" => edge.end_angle = reader.number(&group, 360.0)?,
+                        // Group 73 of a spline says whether it is rational.
+                        "73" if edge.kind != 4 => {
+                            edge.counterclockwise = reader.number(&group, 1)? != 0
+                        }
+                        "94" => edge.degree = reader.number(&group, 3)?,
+                        _ => {}
+                    }
+                }
+                (HatchPart::Pattern, "52") => hatch.pattern_angle = reader.number(&group, 0.0)?,
+                (HatchPart::Pattern, "41") => hatch.pattern_scale = reader.number(&group, 1.0)?,
+                (HatchPart::Pattern, "53") => {
+                    hatch.pattern_lines.push(geometry::HatchPatternLine {
+                        angle: reader.number(&group, 0.0)?,
+                        ..Default::default()
+                    })
+                }
+                (HatchPart::Pattern, "43" | "44" | "45" | "46" | "49") => {
+                    let number = reader.number(&group, 0.0)?;
+                    let Some(line) = hatch.pattern_lines.last_mut() else {
+                        continue;
+                    };
+                    match group.code.as_str() {
+                        "43" => line.base.x = number,
+                        "44" => line.base.y = number,
+                        "45" => line.offset.x = number,
+                        "46" => line.offset.y = number,
+                        _ => line.dashes.push(number),
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        hatch.loops = paths
+            .into_iter()
+            .map(|path| path.finish(elevation))
+            .filter(|boundary| !boundary.vertices.is_empty())
+            .collect();
+        let Some(first) = hatch.loops.first().map(|boundary| boundary.vertices[0]) else {
+            return Ok(None);
+        };
+        let mut bbox = BoundingBox::from_point(first.x, first.y);
+        for boundary in &hatch.loops {
+            let vertices = &boundary.vertices;
+            for (index, vertex) in vertices.iter().enumerate() {
+                bbox.expand(vertex.x, vertex.y);
+                if let Some(bulge) = boundary.bulges.get(index) {
+                    let next = &vertices[(index + 1) % vertices.len()];
+                    expand_by_bulge(&mut bbox, vertex, next, *bulge);
+                }
+            }
+        }
+        hatch.properties = properties(line_type, line_type_scale, 0.0, pen);
+
+        Ok(Some(ParsedEntity {
+            layer: layer_name,
+            geometry: Geometry::Hatch(hatch),
+            bbox,
+            elevation: ElevationRange::from_z(elevation),
+        }))
+    }
+}
+
+/// Points along a (rational) B-spline from its start to its end, by de
+/// Boor's algorithm, or `None` if its knots do not fit its control points.
+fn spline_points(
+    degree: usize,
+    knots: &[f64],
+    control_points: &[Point],
+    weights: &[f64],
+) -> Option<Vec<Point>> {
+    let count = control_points.len();
+    if degree == 0 || count <= degree || knots.len() != count + degree + 1 {
+        return None;
+    }
+    let weight = |index: usize| match weights.len() {
+        length if length == count => weights[index],
+        _ => 1.0,
+    };
+    let (start, end) = (knots[degree], knots[count]);
+    let steps = count * SEGMENTS_PER_CONTROL_POINT;
+    let points = (0..=steps)
+        .map(|step| {
+            let u = start + (end - start) * step as f64 / steps as f64;
+            // The knot span holding u.
+            let span = (degree..count)
+                .rev()
+                .find(|&index| knots[index] <= u)
+                .unwrap_or(degree);
+            let mut d: Vec<[f64; 3]> = (0..=degree)
+                .map(|j| {
+                    let index = j + span - degree;
+                    let (point, w) = (control_points[index], weight(index));
+                    [point.x * w, point.y * w, w]
+                })
+                .collect();
+            for r in 1..=degree {
+                for j in (r..=degree).rev() {
+                    let index = j + span - degree;
+                    let denominator = knots[index + degree + 1 - r] - knots[index];
+                    let alpha = if denominator == 0.0 {
+                        0.0
+                    } else {
+                        (u - knots[index]) / denominator
+                    };
+                    let previous = d[j - 1];
+                    d[j] =
+                        [0, 1, 2].map(|axis| (1.0 - alpha) * previous[axis] + alpha * d[j][axis]);
+                }
+            }
+            let [x, y, w] = d[degree];
+            Point { x: x / w, y: y / w }
+        })
+        .collect();
+    Some(points)
+}
+
+/// An ACAD_TABLE, stored as a TABLE entity. The insertion point is the
+/// top left corner; rows run down and columns along the direction
+/// vector. Cells follow the row heights and column widths in row-major
+/// order, each starting with a `171` cell type.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TableHandler;
+
+/// One ACAD_TABLE cell as it is read.
+#[derive(Debug)]
+struct TableCell {
+    text: String,
+    has_text: bool,
+    value_text: Option<String>,
+    row_span: usize,
+    column_span: usize,
+    text_height: Option<f64>,
+    block: Option<String>,
+}
+
+impl Default for TableCell {
+    fn default() -> Self {
+        Self {
+            text: String::new(),
+            has_text: false,
+            value_text: None,
+            row_span: 1,
+            column_span: 1,
+            text_height: None,
+            block: None,
+        }
+    }
+}
+
+impl TableCell {
+    /// The cell at `row` and `column` of a grid with `rows` by `columns`
+    /// cells. Spans are kept within the grid.
+    fn finish(self, row: usize, column: usize, rows: usize, columns: usize) -> geometry::TableCell {
+        let text = if self.has_text {
+            self.text
+        } else {
+            self.value_text.unwrap_or(self.text)
+        };
+        geometry::TableCell {
+            text,
+            row_span: self.row_span.clamp(1, (rows - row).max(1)),
+            column_span: self.column_span.clamp(1, (columns - column).max(1)),
+            text_height: self.text_height,
+            block: self.block,
+        }
+    }
+}
+
+impl EntityHandler for TableHandler {
+    fn parse(&self, reader: &mut dyn EntityReader) -> Result<Option<ParsedEntity>> {
+        let mut layer_name = "0".to_string();
+        let mut block_name: Option<String> = None;
+        let mut insertion = [0.0; 3];
+        let mut direction: [f64; 2] = [1.0, 0.0];
+        let mut row_count: usize = 0;
+        let mut column_count: usize = 0;
+        let mut row_heights: Vec<f64> = Vec::new();
+        let mut column_widths: Vec<f64> = Vec::new();
+        let mut cells: Vec<TableCell> = Vec::new();
+
+        while let Some(group) = reader.next_field()? {
+            let value = group.value();
+
+            let Some(cell) = cells.last_mut() else {
+                match group.code.as_str() {
+                    "8" => layer_name = value.to_string(),
+                    "2" => block_name = Some(value.to_string()),
+                    "10" => insertion[0] = reader.number(&group, 0.0)?,
+                    "20" => insertion[1] = reader.number(&group, 0.0)?,
+                    "30" => insertion[2] = reader.number(&group, 0.0)?,
+                    "11" => direction[0] = reader.number(&group, 1.0)?,
+                    "21" => direction[1] = reader.number(&group, 0.0)?,
+                    "91" => row_count = reader.number(&group, 0)?,
+                    "92" => column_count = reader.number(&group, 0)?,
+                    "141" => row_heights.push(reader.number(&group, 0.0)?),
+                    "142" => column_widths.push(reader.number(&group, 0.0)?),
+                    "171" => cells.push(TableCell::default()),
+                    _ => {}
+                }
+                continue;
+            };
+
+            match group.code.as_str() {
+                "171" => cells.push(TableCell::default()),
+                // Text longer than 250 characters is split into 2 (or 3)
+                // chunks, with the rest in 1.
+                "2" | "3" => cell.text.push_str(group.raw_value()),
+                "1" => {
+                    cell.text.push_str(group.raw_value());
+                    cell.has_text = true;
+                }
+                // R2007 and later also store the text as a cell value.
+                "302" if !cell.has_text => cell.value_text = Some(value.to_string()),
+                "175" => cell.column_span = reader.number(&group, 1)?,
+                "176" => cell.row_span = reader.number(&group, 1)?,
+                "140" => cell.text_height = Some(reader.number(&group, 0.0)?),
+                "340" => cell.block = Some(value.to_ascii_uppercase()),
+                _ => {}
+            }
+        }
+
+        let width: f64 = column_widths.iter().sum();
+        let height: f64 = row_heights.iter().sum();
+        let length = direction[0].hypot(direction[1]);
+        let (ux, uy) = if length > 0.0 {
+            (direction[0] / length, direction[1] / length)
+        } else {
+            (1.0, 0.0)
+        };
+        let mut bbox = BoundingBox::from_point(insertion[0], insertion[1]);
+        for (i, j) in [(width, 0.0), (0.0, height), (width, height)] {
+            bbox.expand(
+                insertion[0] + i * ux + j * uy,
+                insertion[1] + i * uy - j * ux,
+            );
+        }
+
+        let mut cells = cells.into_iter();
+        let grid: Vec<Vec<geometry::TableCell>> = (0..row_count)
+            .map(|row| {
+                (0..column_count)
+                    .map(|column| {
+                        cells.next().unwrap_or_default().finish(
+                            row,
+                            column,
+                            row_count,
+                            column_count,
+                        )
+                    })
+                    .collect()
+            })
+            .collect();
+
+        Ok(Some(ParsedEntity {
+            layer: layer_name,
+            geometry: Geometry::Table(geometry::Table {
+                block_name,
+                insertion: point3(insertion),
+                direction: Point { x: ux, y: uy },
+                rows: row_count,
+                columns: column_count,
+                row_heights,
+                column_widths,
+                cells: grid,
+            }),
+            bbox,
+            elevation: ElevationRange::from_z(insertion[2]),
+        }))
+    }
+}
+
+/// A PDF, DWF or DGN underlay. Its size is only known from the
+/// referenced file, so the extents cover the insertion point and any
+/// clip boundary.
+#[derive(Debug, Clone, Copy)]
+pub struct UnderlayHandler(pub fn(geometry::Underlay) -> Geometry);
+
+impl EntityHandler for UnderlayHandler {
+    fn parse(&self, reader: &mut dyn EntityReader) -> Result<Option<ParsedEntity>> {
+        let mut layer_name = "0".to_string();
+        let mut insertion = [0.0; 3];
+        let mut scale = [1.0; 3];
+        let mut rotation = 0.0;
+        let mut definition: Option<String> = None;
+        let mut flags: i32 = 0;
+        let mut contrast: i32 = 100;
+        let mut fade: i32 = 0;
+        let mut clip_vertices: Vec<Point> = Vec::new();
+
+        while let Some(group) = reader.next_field()? {
+            let value = group.value();
+
+            match group.code.as_str() {
+                "8" => layer_name = value.to_string(),
+                "10" => insertion[0] = reader.number(&group, 0.0)?,
+                "20" => insertion[1] = reader.number(&group, 0.0)?,
+                "30" => insertion[2] = reader.number(&group, 0.0)?,
+                "41" => scale[0] = reader.number(&group, 1.0)?,
+                "42" => scale[1] = reader.number(&group, 1.0)?,
+                "43" => scale[2] = reader.number(&group, 1.0)?,
+                "50" => rotation = reader.number(&group, 0.0)?,
+                "340" => definition = Some(value.to_ascii_uppercase()),
+                "280" => flags = reader.number(&group, 0)?,
+                "281" => contrast = reader.number(&group, 100)?,
+                "282" => fade = reader.number(&group, 0)?,
+                "11" => clip_vertices.push(Point {
+                    x: reader.number(&group, 0.0)?,
+                    y: 0.0,
+                }),
+                "21" => {
+                    let y = reader.number(&group, 0.0)?;
+                    if let Some(vertex) = clip_vertices.last_mut() {
+                        vertex.y = y;
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        // Clip vertices are in underlay units; place them like the
+        // underlay itself.
+        let (sin, cos) = f64::to_radians(rotation).sin_cos();
+        let mut bbox = BoundingBox::from_point(insertion[0], insertion[1]);
+        for vertex in &clip_vertices {
+            let x = vertex.x * scale[0];
+            let y = vertex.y * scale[1];
+            bbox.expand(
+                insertion[0] + x * cos - y * sin,
+                insertion[1] + x * sin + y * cos,
+            );
+        }
+
+        let clipping = flags & 2 != 0;
+        let underlay = geometry::Underlay {
+            insertion: point3(insertion),
+            scale: point3(scale),
+            rotation,
+            definition,
+            flags,
+            contrast,
+            fade,
+            clip_boundary: clip_boundary(clipping, 2, clip_vertices),
+        };
+        Ok(Some(ParsedEntity {
+            layer: layer_name,
+            geometry: (self.0)(underlay),
+            bbox,
+            elevation: ElevationRange::from_z(insertion[2]),
+        }))
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TextHandler;
+
+impl EntityHandler for TextHandler {
+    fn parse(&self, reader: &mut dyn EntityReader) -> Result<Option<ParsedEntity>> {
+        let mut layer_name = "0".to_string();
+        let mut line_type: Option<String> = None;
+        let mut line_type_scale: Option<f64> = None;
+        let mut pen = Pen::default();
+        let mut elevation = 0.0;
+        let mut thickness = 0.0;
+        let mut x = 0.0;
+        let mut y = 0.0;
+        let mut z: Option<f64> = None;
+        let mut height = 0.0;
+        let mut text = String::new();
+        let mut style_name = "STANDARD".to_string();
+        let mut width_factor: Option<f64> = None;
+        let mut oblique_angle: Option<f64> = None;
+        let mut rotation = 0.0;
+
+        while let Some(group) = reader.next_field()? {
+            let value = group.value();
+
+            match group.code.as_str() {
+                "8" => layer_name = value.to_string(),
+                "6" => line_type = Some(value.to_string()),
+                "48" => line_type_scale = Some(reader.number(&group, 1.0)?),
+                "62" | "370" | "420" => pen.read(reader, &group)?,
+                "38" => elevation = reader.number(&group, 0.0)?,
+                "39" => thickness = reader.number(&group, 0.0)?,
+                "10" => x = reader.number(&group, 0.0)?,
+                "20" => y = reader.number(&group, 0.0)?,
+                "30" => z = Some(reader.number(&group, 0.0)?),
+                "40" => height = reader.number(&group, 0.0)?,
+                "1" => text = value.to_string(),
+                "7" => style_name = value.to_string(),
+                "41" => width_factor = Some(reader.number(&group, 1.0)?),
+                "51" => oblique_angle = Some(reader.number(&group, 0.0)?),
+                "50" => rotation = reader.number(&group, 0.0)?,
+                _ => {}
+            }
+        }
+
+        // A style with a fixed height overrides the entity height; the
+        // entity's own width factor and oblique angle override the style's.
+        let style = reader.text_style(&style_name);
+        if let Some(style) = style.filter(|style| style.fixed_height > 0.0) {
+            height = style.fixed_height;
+        }
+        let width_factor = width_factor
+            .or(style.map(|style| style.width_factor))
+            .unwrap_or(1.0);
+        let oblique_angle = oblique_angle
+            .or(style.map(|style| style.oblique_angle))
+            .unwrap_or(0.0);
+
+        let z = z.unwrap_or(elevation);
+        let z_range = ElevationRange::from_z(z).extrude(thickness);
+        let bbox = BoundingBox::new(
+            x,
+            y,
+            x + text.chars().count() as f64 * height * 0.6 * width_factor,
+            y + height,
+        );
+
+        Ok(Some(ParsedEntity {
+            layer: layer_name,
+            geometry: Geometry::Text(geometry::Text {
+                position: Point3::new(x, y, z),
+                text,
+                height,
+                style: style_name,
+                width_factor,
+                oblique_angle,
+                rotation,
+                properties: properties(line_type, line_type_scale, thickness, pen),
+            }),
+            bbox,
+            elevation: z_range,
+        }))
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MTextHandler;
+
+impl EntityHandler for MTextHandler {
+    fn parse(&self, reader: &mut dyn EntityReader) -> Result<Option<ParsedEntity>> {
+        let mut layer_name = "0".to_string();
+        let mut line_type: Option<String> = None;
+        let mut line_type_scale: Option<f64> = None;
+        let mut pen = Pen::default();
+        let mut elevation = 0.0;
+        let mut thickness = 0.0;
+        let mut x = 0.0;
+        let mut y = 0.0;
+        let mut z: Option<f64> = None;
+        let mut height = 0.0;
+        let mut width = 0.0;
+        let mut text = String::new();
+        let mut tail = String::new();
+        let mut style_name = "STANDARD".to_string();
+        let mut attachment_point: i32 = 1;
+        let mut rotation = 0.0;
+
+        while let Some(group) = reader.next_field()? {
+            // MTEXT chunks keep their leading and trailing spaces.
+            let raw_value = group.raw_value();
+            let value = group.value();
+
+            match group.code.as_str() {
+                "8" => layer_name = value.to_string(),
+                "6" => line_type = Some(value.to_string()),
+                "48" => line_type_scale = Some(reader.number(&group, 1.0)?),
+                "62" | "370" | "420" => pen.read(reader, &group)?,
+                "38" => elevation = reader.number(&group, 0.0)?,
+                "39" => thickness = reader.number(&group, 0.0)?,
+                "10" => x = reader.number(&group, 0.0)?,
+                "20" => y = reader.number(&group, 0.0)?,
+                "30" => z = Some(reader.number(&group, 0.0)?),
+                "40" => height = reader.number(&group, 0.0)?,
+                "41" => width = reader.number(&group, 0.0)?,
+                "3" => text.push_str(raw_value),
+                "1" => tail = raw_value.to_string(),
+                "7" => style_name = value.to_string(),
+                "71" => attachment_point = reader.number(&group, 1)?,
+                "50" => rotation = reader.number(&group, 0.0)?,
+                _ => {}
+            }
+        }
+
+        text.push_str(&tail);
+
+        let style = reader.text_style(&style_name);
+        if let Some(style) = style.filter(|style| style.fixed_height > 0.0) {
+            height = style.fixed_height;
+        }
+        let width_factor = style.map_or(1.0, |style| style.width_factor);
+
+        let paragraphs: Vec<&str> = text.split("\\P").collect();
+        let longest = paragraphs
+            .iter()
+            .map(|p| p.chars().count())
+            .max()
+            .unwrap_or(0);
+        let extent_x = if width > 0.0 {
+            width
+        } else {
+            longest as f64 * height * 0.6 * width_factor
+        };
+        let extent_y = paragraphs.len() as f64 * height * 1.25;
+
+        // Attachment points 1-9 run left-to-right, top-to-bottom.
+        let column = (attachment_point - 1).rem_euclid(3) as f64;
+        let row = ((attachment_point - 1) / 3).clamp(0, 2) as f64;
+        let min_x = x - extent_x * column / 2.0;
+        let max_y = y + extent_y * row / 2.0;
+        let bbox = BoundingBox::new(min_x, max_y - extent_y, min_x + extent_x, max_y);
+        let z = z.unwrap_or(elevation);
+        let z_range = ElevationRange::from_z(z).extrude(thickness);
+
+        Ok(Some(ParsedEntity {
+            layer: layer_name,
+            geometry: Geometry::MText(geometry::MText {
+                position: Point3::new(x, y, z),
+                text,
+                height,
+                width,
+                style: style_name,
+                width_factor,
+                attachment_point,
+                rotation,
+                properties: properties(line_type, line_type_scale, thickness, pen),
+            }),
+            bbox,
+            elevation: z_range,
+        }))
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DimensionHandler;
+
+impl EntityHandler for DimensionHandler {
+    fn parse(&self, reader: &mut dyn EntityReader) -> Result<Option<ParsedEntity>> {
+        let mut layer_name = "0".to_string();
+        let mut line_type: Option<String> = None;
+        let mut line_type_scale: Option<f64> = None;
+        let mut pen = Pen::default();
+        let mut elevation = 0.0;
+        let mut thickness = 0.0;
+        let mut block_name: Option<String> = None;
+        let mut dim_style_name = "STANDARD".to_string();
+        let mut dimension_type = 0;
+        let mut text: Option<String> = None;
+        let mut measurement: Option<f64> = None;
+        let mut points: [Option<(f64, f64, f64)>; 6] = [None; 6];
+
+        while let Some(group) = reader.next_field()? {
+            let value = group.value();
+
+            match group.code.as_str() {
+                "8" => layer_name = value.to_string(),
+                "6" => line_type = Some(value.to_string()),
+                "48" => line_type_scale = Some(reader.number(&group, 1.0)?),
+                "62" | "370" | "420" => pen.read(reader, &group)?,
+                "38" => elevation = reader.number(&group, 0.0)?,
+                "39" => thickness = reader.number(&group, 0.0)?,
+                "2" => block_name = Some(value.to_string()),
+                "3" => dim_style_name = value.to_string(),
+                "70" => dimension_type = reader.number(&group, 0)?,
+                "1" if !value.is_empty() => text = Some(value.to_string()),
+                "42" => measurement = Some(reader.number(&group, 0.0)?),
+                "10" | "11" | "13" | "14" | "15" | "16" => {
+                    let slot = dimension_point_slot(&group.code);
+                    let point = points[slot].get_or_insert((0.0, 0.0, elevation));
+                    point.0 = reader.number(&group, 0.0)?;
+                }
+                "20" | "21" | "23" | "24" | "25" | "26" => {
+                    let slot = dimension_point_slot(&group.code);
+                    let point = points[slot].get_or_insert((0.0, 0.0, elevation));
+                    point.1 = reader.number(&group, 0.0)?;
+                }
+                "30" | "31" | "33" | "34" | "35" | "36" => {
+                    let slot = dimension_point_slot(&group.code);
+                    let point = points[slot].get_or_insert((0.0, 0.0, elevation));
+                    point.2 = reader.number(&group, 0.0)?;
+                }
+                _ => {}
+            }
+        }
+
+        let mut bbox: Option<BoundingBox> = None;
+        let mut z_range: Option<ElevationRange> = None;
+        for (px, py, pz) in points.iter().flatten() {
+            match bbox.as_mut() {
+                Some(bbox) => bbox.expand(*px, *py),
+                None => bbox = Some(BoundingBox::from_point(*px, *py)),
+            }
+            match z_range.as_mut() {
+                Some(z_range) => z_range.expand(*pz),
+                None => z_range = Some(ElevationRange::from_z(*pz)),
+            }
+        }
+        let [definition_point, text_position, point_13, point_14, point_15, point_16] =
+            points.map(|point| point.map(|(x, y, z)| Point3::new(x, y, z)));
+        let mut dimension = geometry::Dimension {
+            dimension_type: dimension_type & 0x0f,
+            dim_style: dim_style_name,
+            definition_point,
+            text_position,
+            point_13,
+            point_14,
+            point_15,
+            point_16,
+            block_name,
+            text,
+            measurement,
+            text_height: None,
+            text_style: None,
+            properties: properties(line_type, line_type_scale, thickness, pen),
+        };
+        if let Some(dim_style) = reader.dim_style(&dimension.dim_style) {
+            let text_height = dim_style
+                .properties
+                .get("DIMTXT")
+                .and_then(Value::as_f64)
+                .unwrap_or(0.18);
+            let scale = dim_style
+                .properties
+                .get("DIMSCALE")
+                .and_then(Value::as_f64)
+                .filter(|scale| *scale > 0.0)
+                .unwrap_or(1.0);
+            dimension.text_height = Some(text_height * scale);
+            dimension.text_style = dim_style.text_style.clone();
+        }
+
+        let bbox = bbox.unwrap_or_else(|| BoundingBox::from_point(0.0, 0.0));
+        let z_range = z_range
+            .unwrap_or_else(|| ElevationRange::from_z(elevation))
+            .extrude(thickness);
+
+        Ok(Some(ParsedEntity {
+            layer: layer_name,
+            geometry: Geometry::Dimension(Box::new(dimension)),
+            bbox,
+            elevation: z_range,
+        }))
+    }
+}
+
+/// The color and lineweight groups every entity may carry (62, 420, 370).
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Pen {
+    pub color: Option<i32>,
+    pub true_color: Option<u32>,
+    pub line_weight: Option<i32>,
+}
+
+impl Pen {
+    /// Reads group 62, 420 or 370; other groups are ignored.
+    pub fn read(&mut self, reader: &mut dyn EntityReader, group: &Group) -> Result<()> {
+        match group.code.as_str() {
+            "62" => self.color = Some(reader.number(group, 256)?),
+            // Some writers set the high byte; only the RGB part is kept.
+            "420" => self.true_color = Some((reader.number::<i64>(group, 0)? & 0xFF_FFFF) as u32),
+            "370" => self.line_weight = Some(reader.number(group, -1)?),
+            _ => {}
+        }
+        Ok(())
+    }
+}
+
+/// The entity-level linetype, color and lineweight overrides and the
+/// extrusion thickness (group 39). Entities without a linetype, color or
+/// lineweight draw with their layer's (BYLAYER).
+pub fn properties(
+    line_type: Option<String>,
+    line_type_scale: Option<f64>,
+    thickness: f64,
+    pen: Pen,
+) -> EntityProperties {
+    EntityProperties {
+        line_type,
+        line_type_scale,
+        thickness: (thickness != 0.0).then_some(thickness),
+        color: pen.color,
+        true_color: pen.true_color,
+        line_weight: pen.line_weight,
+    }
+}
+
+/// Grows a bounding box by the arc of a bulged polyline segment. An arc of
+/// at most a half circle lies within its chord swept out to the arc's
+/// midpoint; larger ones within their circle.
+fn expand_by_bulge(bbox: &mut BoundingBox, from: &Point3, to: &Point3, bulge: f64) {
+    if bulge == 0.0 {
+        return;
+    }
+    let (dx, dy) = (to.x - from.x, to.y - from.y);
+    if bulge.abs() <= 1.0 {
+        // The arc's midpoint lies the sagitta away from the chord, to its
+        // right for counterclockwise arcs.
+        let (offset_x, offset_y) = (dy * bulge / 2.0, -dx * bulge / 2.0);
+        bbox.expand(from.x + offset_x, from.y + offset_y);
+        bbox.expand(to.x + offset_x, to.y + offset_y);
+    } else {
+        let chord = dx.hypot(dy);
+        let angle = 4.0 * bulge.atan();
+        let radius = chord / (2.0 * (angle / 2.0).sin().abs());
+        // The center lies on the chord's bisector, on the arc's far side.
+        let apothem = radius - bulge.abs() * chord / 2.0;
+        let (center_x, center_y) = (
+            (from.x + to.x) / 2.0 - dy / chord * apothem * bulge.signum(),
+            (from.y + to.y) / 2.0 + dx / chord * apothem * bulge.signum(),
+        );
+        bbox.expand(center_x - radius, center_y - radius);
+        bbox.expand(center_x + radius, center_y + radius);
+    }
+}
+
+fn point3([x, y, z]: [f64; 3]) -> Point3 {
+    Point3::new(x, y, z)
+}
+
+/// A clip boundary, or `None` when clipping is off. Rectangular
+/// boundaries (type 1) give two opposite corners.
+fn clip_boundary(clipping: bool, clip_type: i32, vertices: Vec<Point>) -> Option<ClipBoundary> {
+    if !clipping || vertices.is_empty() {
+        return None;
+    }
+    Some(ClipBoundary {
+        kind: if clip_type == 1 {
+            ClipKind::Rectangular
+        } else {
+            ClipKind::Polygonal
+        },
+        vertices,
+    })
+}
+
+fn dimension_point_slot(code: &str) -> usize {
+    match code {
+        "10" | "20" | "30" => 0,
+        "11" | "21" | "31" => 1,
+        "13" | "23" | "33" => 2,
+        "14" | "24" | "34" => 3,
+        "15" | "25" | "35" => 4,
+        _ => 5,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_registry_matches_names_regardless_of_case() {
+        let mut registry = EntityHandlerRegistry::empty();
+        registry.register("acme_pipe", LineHandler);
+
+        assert!(registry.get("ACME_PIPE").is_some());
+        assert!(registry.get("Acme_Pipe").is_some());
+        assert!(registry.get("acme_pipe").is_some());
+        assert!(registry.get("ACME_VALVE").is_none());
+
+        let registry = EntityHandlerRegistry::default();
+        assert!(registry.get("lwpolyline").is_some());
+    }
+}
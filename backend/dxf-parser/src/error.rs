@@ -0,0 +1,12 @@
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Invalid DXF: {0}")]
+    InvalidDxf(String),
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
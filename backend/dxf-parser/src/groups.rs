@@ -0,0 +1,215 @@
+use crate::binary::{self, BinaryGroupReader};
+use crate::error::{Error, Result};
+use std::io::{BufRead, Chain, Cursor, Read};
+
+/// One group code / value pair. `line` is the 1-based line of the value in
+/// the ASCII file, or in the decoded code/value stream for binary files.
+#[derive(Debug, Clone)]
+pub struct Group {
+    pub line: usize,
+    pub code: String,
+    raw: String,
+}
+
+impl Group {
+    pub fn value(&self) -> &str {
+        self.raw.trim()
+    }
+
+    /// The value with its leading and trailing spaces kept.
+    pub fn raw_value(&self) -> &str {
+        &self.raw
+    }
+
+    pub fn is(&self, code: &str, value: &str) -> bool {
+        self.code == code && self.value() == value
+    }
+}
+
+/// Appends a group to raw record content, as ASCII code and value lines.
+pub(crate) fn push_group(content: &mut String, group: &Group) {
+    content.push_str(&group.code);
+    content.push('\n');
+    content.push_str(group.raw_value());
+    content.push('\n');
+}
+
+/// The code / value pairs of raw record content, codes trimmed.
+pub(crate) fn raw_groups(content: &str) -> impl Iterator<Item = (&str, &str)> {
+    let mut lines = content.lines();
+    std::iter::from_fn(move || Some((lines.next()?.trim(), lines.next()?)))
+}
+
+type Sniffed<R> = Chain<Cursor<Vec<u8>>, R>;
+
+enum Source<R> {
+    Ascii { reader: R, buf: Vec<u8> },
+    Binary(BinaryGroupReader<R>),
+}
+
+/// Reads groups one at a time from an ASCII or binary DXF stream, with one
+/// group of lookahead. Only the current group is held in memory.
+pub struct GroupReader<R> {
+    source: Source<Sniffed<R>>,
+    line: usize,
+    ascii_bytes: u64,
+    peeked: Option<Group>,
+}
+
+impl<R: BufRead> GroupReader<R> {
+    pub fn new(mut reader: R) -> Result<Self> {
+        let mut head = Vec::with_capacity(binary::BINARY_SENTINEL.len());
+        reader
+            .by_ref()
+            .take(binary::BINARY_SENTINEL.len() as u64)
+            .read_to_end(&mut head)?;
+        let is_binary = binary::is_binary_dxf(&head);
+        let reader = Cursor::new(head).chain(reader);
+
+        let source = if is_binary {
+            Source::Binary(BinaryGroupReader::new(reader)?)
+        } else {
+            Source::Ascii {
+                reader,
+                buf: Vec::new(),
+            }
+        };
+
+        Ok(Self {
+            source,
+            line: 0,
+            ascii_bytes: 0,
+            peeked: None,
+        })
+    }
+
+    /// Number of input bytes consumed so far, including any lookahead.
+    pub fn bytes_read(&self) -> u64 {
+        match &self.source {
+            Source::Binary(reader) => reader.position(),
+            Source::Ascii { .. } => self.ascii_bytes,
+        }
+    }
+
+    pub fn next_group(&mut self) -> Result<Option<Group>> {
+        if let Some(group) = self.peeked.take() {
+            return Ok(Some(group));
+        }
+        self.read_group()
+    }
+
+    pub fn peek(&mut self) -> Result<Option<&Group>> {
+        if self.peeked.is_none() {
+            self.peeked = self.read_group()?;
+        }
+        Ok(self.peeked.as_ref())
+    }
+
+    /// Returns the next group of the current record, stopping before the
+    /// code 0 group that starts the next one.
+    pub fn next_field(&mut self) -> Result<Option<Group>> {
+        match self.peek()? {
+            Some(group) if group.code != "0" => self.next_group(),
+            _ => Ok(None),
+        }
+    }
+
+    /// Consumes groups up to and including the `(0, marker)` group.
+    pub fn skip_past(&mut self, marker: &str) -> Result<()> {
+        while let Some(group) = self.next_group()? {
+            if group.is("0", marker) {
+                break;
+            }
+        }
+        Ok(())
+    }
+
+    fn read_group(&mut self) -> Result<Option<Group>> {
+        match &mut self.source {
+            Source::Binary(reader) => {
+                let Some((code, raw)) = reader.next_group()? else {
+                    return Ok(None);
+                };
+                self.line += 2;
+                Ok(Some(Group {
+                    line: self.line,
+                    code: code.to_string(),
+                    raw,
+                }))
+            }
+            Source::Ascii { .. } => {
+                let Some(code) = self.read_line()? else {
+                    return Ok(None);
+                };
+                let Some(raw) = self.read_line()? else {
+                    return Ok(None);
+                };
+                Ok(Some(Group {
+                    line: self.line,
+                    code: code.trim().to_string(),
+                    raw,
+                }))
+            }
+        }
+    }
+
+    fn read_line(&mut self) -> Result<Option<String>> {
+        let Source::Ascii { reader, buf } = &mut self.source else {
+            return Ok(None);
+        };
+
+        buf.clear();
+        let len = reader.read_until(b'\n', buf)?;
+        if len == 0 {
+            return Ok(None);
+        }
+        self.line += 1;
+        self.ascii_bytes += len as u64;
+
+        if buf.last() == Some(&b'\n') {
+            buf.pop();
+        }
+        if buf.last() == Some(&b'\r') {
+            buf.pop();
+        }
+
+        let line = std::str::from_utf8(buf).map_err(|_| {
+            Error::InvalidDxf(format!("line {} is not valid UTF-8 text", self.line))
+        })?;
+        Ok(Some(line.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reads_groups_with_lookahead() {
+        let mut reader =
+            GroupReader::new(&b"0\r\nLINE\r\n8\r\n WALLS \r\n0\r\nEOF\r\n"[..]).unwrap();
+
+        assert!(reader.next_group().unwrap().unwrap().is("0", "LINE"));
+        assert!(reader.peek().unwrap().unwrap().is("8", "WALLS"));
+
+        let field = reader.next_field().unwrap().unwrap();
+        assert_eq!(field.line, 4);
+        assert_eq!(field.raw_value(), " WALLS ");
+
+        assert!(reader.next_field().unwrap().is_none());
+        assert!(reader.next_group().unwrap().unwrap().is("0", "EOF"));
+        assert!(reader.next_group().unwrap().is_none());
+    }
+
+    #[test]
+    fn test_rejects_invalid_utf8_with_line_number() {
+        let mut reader = GroupReader::new(&b"0\nTEXT\n1\n\xff\xfe\n"[..]).unwrap();
+
+        reader.next_group().unwrap();
+        let err = reader.next_group().unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Invalid DXF: line 4 is not valid UTF-8 text"
+        );
+    }
+}
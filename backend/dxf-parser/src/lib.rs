@@ -0,0 +1,22 @@
+//! Streaming reader for ASCII and binary DXF files, and a writer for ASCII
+//! DXF. It has no web or database dependencies, so batch tools can link it
+//! directly; the server persists the [`ParseEvent`]s it produces. The
+//! `sqlx` feature lets the server read its stored layer and entity rows
+//! into the same [`models::Layer`] and [`models::Entity`] types the
+//! browser preview returns.
+
+pub mod binary;
+pub mod entity_handlers;
+pub mod error;
+pub mod groups;
+pub mod models;
+pub mod parser;
+pub mod writer;
+
+pub use entity_handlers::{EntityHandler, EntityHandlerRegistry, EntityReader, ParsedEntity};
+pub use error::{Error, Result};
+pub use parser::{
+    parse_dxf, parse_dxf_bytes, DrawingTables, DxfParser, ParseEvent, ParseMode, ParseProgress,
+    ParsedDrawing, ParsedLayer,
+};
+pub use writer::{write_dxf, DxfVersion};
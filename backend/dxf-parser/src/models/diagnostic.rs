@@ -0,0 +1,46 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DiagnosticSeverity {
+    Error,
+    Warning,
+}
+
+impl DiagnosticSeverity {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DiagnosticSeverity::Error => "error",
+            DiagnosticSeverity::Warning => "warning",
+        }
+    }
+}
+
+/// A problem found while parsing. `line` is 1-based; for binary DXF files it
+/// counts lines of the decoded code/value stream rather than bytes.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateDiagnosticInput {
+    pub line: u64,
+    pub group_code: Option<i32>,
+    pub entity_type: Option<String>,
+    pub entity_handle: Option<String>,
+    pub severity: DiagnosticSeverity,
+    pub message: String,
+}
+
+impl fmt::Display for CreateDiagnosticInput {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)?;
+        if let Some(code) = self.group_code {
+            write!(f, " (group code {})", code)?;
+        }
+        if let Some(entity_type) = &self.entity_type {
+            write!(f, " in {}", entity_type)?;
+            if let Some(handle) = &self.entity_handle {
+                write!(f, " {}", handle)?;
+            }
+        }
+        Ok(())
+    }
+}
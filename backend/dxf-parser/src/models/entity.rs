@@ -0,0 +1,156 @@
+use crate::models::Geometry;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CreateEntityInput {
+    pub data: Geometry,
+    pub min_x: f64,
+    pub min_y: f64,
+    pub max_x: f64,
+    pub max_y: f64,
+    pub min_z: f64,
+    pub max_z: f64,
+    /// Name of the layout the entity is drawn in, e.g. `Model`.
+    pub layout: String,
+    /// Handle (group 5), unique within the file.
+    pub handle: Option<String>,
+    /// Handle of the owning block record (group 330).
+    pub owner_handle: Option<String>,
+    /// Handle of the `ACAD_XDICTIONARY` extension dictionary.
+    pub extension_dictionary: Option<String>,
+    /// XDATA values keyed by registered application name.
+    pub xdata: Option<Value>,
+    /// Position in the draw order; entities of a layout are drawn from the
+    /// lowest value up.
+    pub draw_order: u64,
+}
+
+impl CreateEntityInput {
+    pub fn entity_type(&self) -> &'static str {
+        self.data.entity_type()
+    }
+}
+
+/// A stored entity, as the server serves it and the browser preview
+/// returns it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "sqlx", derive(sqlx::FromRow))]
+pub struct Entity {
+    pub id: u64,
+    pub layer_id: u64,
+    pub layout: String,
+    pub handle: Option<String>,
+    pub owner_handle: Option<String>,
+    pub extension_dictionary: Option<String>,
+    pub draw_order: u64,
+    pub entity_type: String,
+    #[cfg_attr(feature = "sqlx", sqlx(json))]
+    pub data: Geometry,
+    pub xdata: Option<Value>,
+    pub min_x: f64,
+    pub min_y: f64,
+    pub max_x: f64,
+    pub max_y: f64,
+    pub min_z: f64,
+    pub max_z: f64,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<Entity> for CreateEntityInput {
+    fn from(entity: Entity) -> Self {
+        Self {
+            data: entity.data,
+            min_x: entity.min_x,
+            min_y: entity.min_y,
+            max_x: entity.max_x,
+            max_y: entity.max_y,
+            min_z: entity.min_z,
+            max_z: entity.max_z,
+            layout: entity.layout,
+            handle: entity.handle,
+            owner_handle: entity.owner_handle,
+            extension_dictionary: entity.extension_dictionary,
+            xdata: entity.xdata,
+            draw_order: entity.draw_order,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BoundingBox {
+    pub min_x: f64,
+    pub min_y: f64,
+    pub max_x: f64,
+    pub max_y: f64,
+}
+
+impl BoundingBox {
+    pub fn new(min_x: f64, min_y: f64, max_x: f64, max_y: f64) -> Self {
+        Self {
+            min_x,
+            min_y,
+            max_x,
+            max_y,
+        }
+    }
+
+    pub fn from_point(x: f64, y: f64) -> Self {
+        Self {
+            min_x: x,
+            min_y: y,
+            max_x: x,
+            max_y: y,
+        }
+    }
+
+    pub fn expand(&mut self, x: f64, y: f64) {
+        self.min_x = self.min_x.min(x);
+        self.min_y = self.min_y.min(y);
+        self.max_x = self.max_x.max(x);
+        self.max_y = self.max_y.max(y);
+    }
+
+    pub fn merge(&mut self, other: &BoundingBox) {
+        self.min_x = self.min_x.min(other.min_x);
+        self.min_y = self.min_y.min(other.min_y);
+        self.max_x = self.max_x.max(other.max_x);
+        self.max_y = self.max_y.max(other.max_y);
+    }
+}
+
+/// Z extent of an entity or layer, including any extrusion by thickness.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ElevationRange {
+    pub min_z: f64,
+    pub max_z: f64,
+}
+
+impl ElevationRange {
+    pub fn from_z(z: f64) -> Self {
+        Self { min_z: z, max_z: z }
+    }
+
+    pub fn expand(&mut self, z: f64) {
+        self.min_z = self.min_z.min(z);
+        self.max_z = self.max_z.max(z);
+    }
+
+    pub fn merge(&mut self, other: &ElevationRange) {
+        self.min_z = self.min_z.min(other.min_z);
+        self.max_z = self.max_z.max(other.max_z);
+    }
+
+    /// Extends the range by a thickness along Z; negative thickness extrudes
+    /// downwards.
+    pub fn extrude(mut self, thickness: f64) -> Self {
+        if thickness < 0.0 {
+            self.min_z += thickness;
+        } else {
+            self.max_z += thickness;
+        }
+        self
+    }
+}
@@ -0,0 +1,464 @@
+use serde::de::Error as _;
+use serde::ser::Error as _;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::Value;
+
+/// Version of the entity data schema written by this build. Data without a
+/// `version` key predates versioning and is read as version 0.
+pub const GEOMETRY_VERSION: u64 = 1;
+
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub struct Point {
+    pub x: f64,
+    pub y: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+pub struct Point3 {
+    pub x: f64,
+    pub y: f64,
+    #[serde(default)]
+    pub z: f64,
+}
+
+impl Point3 {
+    pub fn new(x: f64, y: f64, z: f64) -> Self {
+        Self { x, y, z }
+    }
+}
+
+/// The geometry of an entity, one variant per entity type. Serialized as an
+/// object tagged with `type` and `version`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(remote = "Self", tag = "type", rename_all = "UPPERCASE")]
+pub enum Geometry {
+    Line(Line),
+    Polyline(Polyline),
+    Arc(Arc),
+    Circle(Circle),
+    Text(Text),
+    #[serde(rename = "MTEXT")]
+    MText(MText),
+    Dimension(Box<Dimension>),
+    Viewport(Viewport),
+    Image(Image),
+    Wipeout(Wipeout),
+    Hatch(Hatch),
+    Table(Table),
+    PdfUnderlay(Underlay),
+    DwfUnderlay(Underlay),
+    DgnUnderlay(Underlay),
+    Insert(Insert),
+}
+
+impl Geometry {
+    /// The DXF entity type, as stored in `entities.entity_type`.
+    pub fn entity_type(&self) -> &'static str {
+        match self {
+            Geometry::Line(_) => "LINE",
+            Geometry::Polyline(_) => "POLYLINE",
+            Geometry::Arc(_) => "ARC",
+            Geometry::Circle(_) => "CIRCLE",
+            Geometry::Text(_) => "TEXT",
+            Geometry::MText(_) => "MTEXT",
+            Geometry::Dimension(_) => "DIMENSION",
+            Geometry::Viewport(_) => "VIEWPORT",
+            Geometry::Image(_) => "IMAGE",
+            Geometry::Wipeout(_) => "WIPEOUT",
+            Geometry::Hatch(_) => "HATCH",
+            Geometry::Table(_) => "TABLE",
+            Geometry::PdfUnderlay(_) => "PDFUNDERLAY",
+            Geometry::DwfUnderlay(_) => "DWFUNDERLAY",
+            Geometry::DgnUnderlay(_) => "DGNUNDERLAY",
+            Geometry::Insert(_) => "INSERT",
+        }
+    }
+}
+
+impl Default for Geometry {
+    fn default() -> Self {
+        Geometry::Line(Line::default())
+    }
+}
+
+impl Serialize for Geometry {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut value =
+            Geometry::serialize(self, serde_json::value::Serializer).map_err(S::Error::custom)?;
+        if let Value::Object(fields) = &mut value {
+            fields.insert("version".to_string(), GEOMETRY_VERSION.into());
+        }
+        value.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Geometry {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let mut value = Value::deserialize(deserializer)?;
+        upgrade(&mut value).map_err(D::Error::custom)?;
+        Geometry::deserialize(value).map_err(D::Error::custom)
+    }
+}
+
+/// Brings data written by an older schema version up to
+/// [`GEOMETRY_VERSION`], one version at a time.
+fn upgrade(value: &mut Value) -> Result<(), String> {
+    let Value::Object(fields) = value else {
+        return Err("entity data must be an object".to_string());
+    };
+    let version = fields
+        .remove("version")
+        .map_or(Some(0), |version| version.as_u64());
+    let Some(mut version) = version else {
+        return Err("entity data version must be a number".to_string());
+    };
+    if version > GEOMETRY_VERSION {
+        return Err(format!("unsupported entity data version {version}"));
+    }
+
+    while version < GEOMETRY_VERSION {
+        if version == 0 {
+            // Version 0 is the seed data schema: polyline `points`, text
+            // `content` and insert scales without Z.
+            if let Some(points) = fields.remove("points") {
+                fields.entry("vertices").or_insert(points);
+            }
+            if let Some(content) = fields.remove("content") {
+                fields.entry("text").or_insert(content);
+            }
+            if fields.get("type").and_then(Value::as_str) == Some("INSERT") {
+                if let Some(Value::Object(scale)) = fields.get_mut("scale") {
+                    scale.entry("z").or_insert(1.0.into());
+                }
+            }
+        }
+        version += 1;
+    }
+    Ok(())
+}
+
+/// Entity-level overrides of the layer's linetype, color and lineweight,
+/// and the extrusion thickness (group 39). Absent values are not written.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct EntityProperties {
+    /// Linetype override; entities without one draw BYLAYER.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub line_type: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub line_type_scale: Option<f64>,
+    /// Only set when non-zero.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub thickness: Option<f64>,
+    /// AutoCAD Color Index (group 62): 0 is BYBLOCK, 256 BYLAYER.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub color: Option<i32>,
+    /// 24-bit `0xRRGGBB` color (group 420); takes precedence over `color`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub true_color: Option<u32>,
+    /// Lineweight in hundredths of a millimetre (group 370); -1 is BYLAYER,
+    /// -2 BYBLOCK and -3 the default lineweight.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub line_weight: Option<i32>,
+}
+
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct Line {
+    pub start: Point3,
+    pub end: Point3,
+    #[serde(flatten)]
+    pub properties: EntityProperties,
+}
+
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct Polyline {
+    pub vertices: Vec<Point3>,
+    /// Bulge of the segment starting at each vertex (group 42): the tangent
+    /// of a quarter of its arc angle, positive counterclockwise. Empty when
+    /// all segments are straight.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub bulges: Vec<f64>,
+    /// Whether a segment joins the last vertex to the first.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub closed: bool,
+    #[serde(flatten)]
+    pub properties: EntityProperties,
+}
+
+/// Angles are in degrees, counterclockwise from the X axis.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct Arc {
+    pub center: Point3,
+    pub radius: f64,
+    pub start_angle: f64,
+    pub end_angle: f64,
+    #[serde(flatten)]
+    pub properties: EntityProperties,
+}
+
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct Circle {
+    pub center: Point3,
+    pub radius: f64,
+    #[serde(flatten)]
+    pub properties: EntityProperties,
+}
+
+/// Single-line text. The height, width factor and oblique angle already
+/// include the text style's.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Text {
+    pub position: Point3,
+    pub text: String,
+    pub height: f64,
+    #[serde(default = "standard_style")]
+    pub style: String,
+    #[serde(default = "unit")]
+    pub width_factor: f64,
+    #[serde(default)]
+    pub oblique_angle: f64,
+    #[serde(default)]
+    pub rotation: f64,
+    #[serde(flatten)]
+    pub properties: EntityProperties,
+}
+
+/// Multiline text, with its MTEXT formatting codes. A width of 0 means no
+/// wrapping.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MText {
+    pub position: Point3,
+    pub text: String,
+    pub height: f64,
+    #[serde(default)]
+    pub width: f64,
+    #[serde(default = "standard_style")]
+    pub style: String,
+    #[serde(default = "unit")]
+    pub width_factor: f64,
+    /// 1-9, left-to-right and top-to-bottom.
+    #[serde(default = "top_left")]
+    pub attachment_point: i32,
+    #[serde(default)]
+    pub rotation: f64,
+    #[serde(flatten)]
+    pub properties: EntityProperties,
+}
+
+/// A dimension with its definition points (groups 10-16) as present in the
+/// file. The text height is the dimension style's, times its DIMSCALE.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Dimension {
+    pub dimension_type: i32,
+    #[serde(default = "standard_style")]
+    pub dim_style: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub definition_point: Option<Point3>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub text_position: Option<Point3>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub point_13: Option<Point3>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub point_14: Option<Point3>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub point_15: Option<Point3>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub point_16: Option<Point3>,
+    /// The anonymous block holding the dimension's graphics.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub block_name: Option<String>,
+    /// Text override; `<>` stands for the measurement.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub measurement: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub text_height: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub text_style: Option<String>,
+    #[serde(flatten)]
+    pub properties: EntityProperties,
+}
+
+/// A paper space window onto model space. The center, width and height
+/// are in paper units; the view center and height are in model units.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct Viewport {
+    pub center: Point3,
+    pub width: f64,
+    pub height: f64,
+    /// 0 when the viewport is off.
+    pub status: i32,
+    /// 1 for the viewport showing the sheet itself.
+    pub id: i32,
+    pub view_center: Point,
+    pub view_target: Point3,
+    pub view_direction: Point3,
+    pub view_height: f64,
+    pub twist_angle: f64,
+    #[serde(default)]
+    pub frozen_layers: Vec<String>,
+}
+
+/// A raster image placed by its lower-left corner and the vectors of one
+/// pixel along U and V. The definition is the handle of its IMAGEDEF.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Image {
+    pub insertion: Point3,
+    pub u_vector: Point3,
+    pub v_vector: Point3,
+    pub width_px: f64,
+    pub height_px: f64,
+    pub definition: Option<String>,
+    #[serde(default)]
+    pub display_flags: i32,
+    #[serde(default = "half")]
+    pub brightness: i32,
+    #[serde(default = "half")]
+    pub contrast: i32,
+    #[serde(default)]
+    pub fade: i32,
+    pub clip_boundary: Option<ClipBoundary>,
+}
+
+/// A PDF, DWF or DGN underlay. The definition is the handle of its
+/// PDFDEFINITION, DWFDEFINITION or DGNDEFINITION.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Underlay {
+    pub insertion: Point3,
+    pub scale: Point3,
+    pub rotation: f64,
+    pub definition: Option<String>,
+    #[serde(default)]
+    pub flags: i32,
+    #[serde(default = "full")]
+    pub contrast: i32,
+    #[serde(default)]
+    pub fade: i32,
+    pub clip_boundary: Option<ClipBoundary>,
+}
+
+/// The part of an image or underlay that is shown, in its own units.
+/// Rectangular boundaries give two opposite corners.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ClipBoundary {
+    #[serde(rename = "type")]
+    pub kind: ClipKind,
+    pub vertices: Vec<Point>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ClipKind {
+    Rectangular,
+    Polygonal,
+}
+
+/// A polygon masking the entities drawn before it, in world coordinates.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct Wipeout {
+    pub boundary: Vec<Point3>,
+}
+
+/// An area bounded by closed loops, filled solid or with a pattern. With
+/// the normal style (0), loops nested in an odd number of others are
+/// holes.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct Hatch {
+    pub pattern_name: String,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub solid: bool,
+    /// Island detection (group 75): 0 normal, 1 outermost, 2 ignore.
+    #[serde(default)]
+    pub style: i32,
+    /// Pattern angle in degrees and scale; the pattern lines already have
+    /// them applied.
+    #[serde(default)]
+    pub pattern_angle: f64,
+    #[serde(default = "unit")]
+    pub pattern_scale: f64,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub pattern_lines: Vec<HatchPatternLine>,
+    pub loops: Vec<HatchLoop>,
+    #[serde(flatten)]
+    pub properties: EntityProperties,
+}
+
+/// A closed boundary loop, as the vertices and bulges of a closed
+/// polyline at the hatch's elevation. Line and arc edges keep their
+/// shape; elliptic and spline edges are flattened.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct HatchLoop {
+    /// Boundary path type flags (group 92) but the polyline one: 1
+    /// external, 16 outermost.
+    #[serde(default)]
+    pub flags: i32,
+    pub vertices: Vec<Point3>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub bulges: Vec<f64>,
+}
+
+/// One line family of a hatch pattern: lines through `base` at `angle`
+/// degrees, repeated by `offset`, with dashes as in a linetype.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct HatchPatternLine {
+    pub angle: f64,
+    pub base: Point,
+    pub offset: Point,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub dashes: Vec<f64>,
+}
+
+/// An ACAD_TABLE. The insertion point is its top left corner; rows run
+/// down and columns along the direction.
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct Table {
+    pub block_name: Option<String>,
+    pub insertion: Point3,
+    pub direction: Point,
+    pub rows: usize,
+    pub columns: usize,
+    pub row_heights: Vec<f64>,
+    pub column_widths: Vec<f64>,
+    /// Cells by row, `rows` by `columns`.
+    pub cells: Vec<Vec<TableCell>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TableCell {
+    pub text: String,
+    pub row_span: usize,
+    pub column_span: usize,
+    pub text_height: Option<f64>,
+    /// Handle of the block record shown in a block cell.
+    pub block: Option<String>,
+}
+
+/// A block reference.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Insert {
+    pub block_name: String,
+    pub position: Point3,
+    pub scale: Point3,
+    #[serde(default)]
+    pub rotation: f64,
+}
+
+fn standard_style() -> String {
+    "STANDARD".to_string()
+}
+
+fn unit() -> f64 {
+    1.0
+}
+
+fn top_left() -> i32 {
+    1
+}
+
+fn half() -> i32 {
+    50
+}
+
+fn full() -> i32 {
+    100
+}
@@ -0,0 +1,48 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateLayerInput {
+    pub name: String,
+    pub is_locked: bool,
+    pub is_visible: bool,
+    pub color: Option<String>,
+    pub line_type: Option<String>,
+    pub line_weight: Option<String>,
+}
+
+/// A stored layer, as the server serves it and the browser preview
+/// returns it. The extents cover its model space entities.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "sqlx", derive(sqlx::FromRow))]
+pub struct Layer {
+    pub id: u64,
+    pub file_id: u64,
+    pub name: String,
+    pub is_locked: bool,
+    pub is_visible: bool,
+    pub color: Option<String>,
+    pub line_type: Option<String>,
+    pub line_weight: Option<String>,
+    pub min_x: Option<f64>,
+    pub min_y: Option<f64>,
+    pub max_x: Option<f64>,
+    pub max_y: Option<f64>,
+    pub min_z: Option<f64>,
+    pub max_z: Option<f64>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<Layer> for CreateLayerInput {
+    fn from(layer: Layer) -> Self {
+        Self {
+            name: layer.name,
+            is_locked: layer.is_locked,
+            is_visible: layer.is_visible,
+            color: layer.color,
+            line_type: layer.line_type,
+            line_weight: layer.line_weight,
+        }
+    }
+}
@@ -0,0 +1,33 @@
+use serde::{Deserialize, Serialize};
+
+/// Name of the model space layout, which every drawing has.
+pub const MODEL_LAYOUT: &str = "Model";
+
+/// A LAYOUT object. `block_name` is the `*Model_Space` or `*Paper_Space…`
+/// block holding the layout's entities; the bounds are its limits.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateLayoutInput {
+    pub name: String,
+    pub block_name: Option<String>,
+    pub tab_order: i32,
+    pub min_x: f64,
+    pub min_y: f64,
+    pub max_x: f64,
+    pub max_y: f64,
+}
+
+impl CreateLayoutInput {
+    /// A layout for a layout block that no LAYOUT object claims, as in R12
+    /// files, named after the block.
+    pub fn for_block(name: &str, tab_order: i32) -> Self {
+        Self {
+            name: name.to_string(),
+            block_name: Some(name.to_string()),
+            tab_order,
+            min_x: 0.0,
+            min_y: 0.0,
+            max_x: 0.0,
+            max_y: 0.0,
+        }
+    }
+}
@@ -0,0 +1,56 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateLinetypeInput {
+    pub name: String,
+    pub description: Option<String>,
+    pub pattern_length: f64,
+    pub elements: Vec<LinetypeElement>,
+}
+
+/// One entry of a linetype pattern. Lengths are in drawing units before any
+/// `$LTSCALE` or per-entity linetype scale is applied.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LinetypeElement {
+    pub kind: LinetypeElementKind,
+    pub length: f64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub embedded: Option<EmbeddedElement>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LinetypeElementKind {
+    Dash,
+    Gap,
+    Dot,
+}
+
+impl LinetypeElementKind {
+    pub fn from_length(length: f64) -> Self {
+        if length > 0.0 {
+            LinetypeElementKind::Dash
+        } else if length < 0.0 {
+            LinetypeElementKind::Gap
+        } else {
+            LinetypeElementKind::Dot
+        }
+    }
+}
+
+/// Text or shape drawn inside a complex linetype element.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct EmbeddedElement {
+    pub kind: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub shape_number: Option<i32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub style_handle: Option<String>,
+    pub scale: f64,
+    pub rotation: f64,
+    pub absolute_rotation: bool,
+    pub offset_x: f64,
+    pub offset_y: f64,
+}
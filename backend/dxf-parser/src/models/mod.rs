@@ -0,0 +1,23 @@
+pub mod diagnostic;
+pub mod entity;
+pub mod geometry;
+pub mod layer;
+pub mod layout;
+pub mod linetype;
+pub mod object;
+pub mod raw_record;
+pub mod reference;
+pub mod style;
+pub mod unsupported;
+
+pub use diagnostic::{CreateDiagnosticInput, DiagnosticSeverity};
+pub use entity::{BoundingBox, CreateEntityInput, ElevationRange, Entity};
+pub use geometry::{Geometry, Point, Point3, GEOMETRY_VERSION};
+pub use layer::{CreateLayerInput, Layer};
+pub use layout::{CreateLayoutInput, MODEL_LAYOUT};
+pub use linetype::{CreateLinetypeInput, EmbeddedElement, LinetypeElement, LinetypeElementKind};
+pub use object::{CreateEntityGroupInput, CreateNamedObjectInput};
+pub use raw_record::{CreateRawRecordInput, RawSection};
+pub use reference::{CreateExternalReferenceInput, ReferenceKind};
+pub use style::{CreateDimStyleInput, CreateTextStyleInput};
+pub use unsupported::{CreateUnsupportedItemInput, UnsupportedKind};
@@ -0,0 +1,17 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateNamedObjectInput {
+    pub path: String,
+    pub handle: String,
+    pub object_type: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateEntityGroupInput {
+    pub name: String,
+    pub handle: String,
+    pub description: Option<String>,
+    pub is_selectable: bool,
+    pub members: Vec<String>,
+}
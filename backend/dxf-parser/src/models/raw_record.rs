@@ -0,0 +1,36 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RawSection {
+    Entities,
+    Objects,
+    /// A block definition, from its BLOCK to its ENDBLK.
+    Blocks,
+}
+
+impl RawSection {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RawSection::Entities => "entities",
+            RawSection::Objects => "objects",
+            RawSection::Blocks => "blocks",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CreateRawRecordInput {
+    pub section: RawSection,
+    pub record_type: String,
+    pub handle: Option<String>,
+    pub owner_handle: Option<String>,
+    /// Layout of an entity; `None` for objects and blocks.
+    pub layout: Option<String>,
+    /// Where the record goes back: for entities, the number of parsed
+    /// entities before it in the file; for objects, the number of objects
+    /// before it in the OBJECTS section; for blocks, the number of blocks
+    /// kept before it.
+    pub position: u64,
+    pub content: String,
+}
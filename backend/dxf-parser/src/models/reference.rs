@@ -0,0 +1,31 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReferenceKind {
+    Image,
+    Pdf,
+    Dwf,
+    Dgn,
+}
+
+impl ReferenceKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ReferenceKind::Image => "image",
+            ReferenceKind::Pdf => "pdf",
+            ReferenceKind::Dwf => "dwf",
+            ReferenceKind::Dgn => "dgn",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CreateExternalReferenceInput {
+    pub handle: String,
+    pub kind: ReferenceKind,
+    pub path: String,
+    pub name: Option<String>,
+    pub width_px: Option<f64>,
+    pub height_px: Option<f64>,
+}
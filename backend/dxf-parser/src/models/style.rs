@@ -0,0 +1,37 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateTextStyleInput {
+    pub name: String,
+    pub font_file: Option<String>,
+    pub bigfont_file: Option<String>,
+    /// Zero means the height is taken from each entity.
+    pub fixed_height: f64,
+    pub width_factor: f64,
+    /// Degrees.
+    pub oblique_angle: f64,
+    pub flags: i32,
+}
+
+impl Default for CreateTextStyleInput {
+    fn default() -> Self {
+        Self {
+            name: String::new(),
+            font_file: None,
+            bigfont_file: None,
+            fixed_height: 0.0,
+            width_factor: 1.0,
+            oblique_angle: 0.0,
+            flags: 0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CreateDimStyleInput {
+    pub name: String,
+    pub text_style: Option<String>,
+    /// Dimension variables keyed by their system variable name, e.g. `DIMTXT`.
+    pub properties: Map<String, Value>,
+}
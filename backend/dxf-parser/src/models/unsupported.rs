@@ -0,0 +1,26 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum UnsupportedKind {
+    Entity,
+    Section,
+    Table,
+}
+
+impl UnsupportedKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            UnsupportedKind::Entity => "entity",
+            UnsupportedKind::Section => "section",
+            UnsupportedKind::Table => "table",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CreateUnsupportedItemInput {
+    pub kind: UnsupportedKind,
+    pub name: String,
+    pub count: u64,
+}
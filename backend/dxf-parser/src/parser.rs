@@ -0,0 +1,2793 @@
+use crate::entity_handlers::{EntityHandlerRegistry, EntityReader, ParsedEntity};
+use crate::error::{Error, Result};
+use crate::groups::{push_group, raw_groups, Group, GroupReader};
+use crate::models::{
+    BoundingBox, CreateDiagnosticInput, CreateDimStyleInput, CreateEntityGroupInput,
+    CreateEntityInput, CreateExternalReferenceInput, CreateLayerInput, CreateLayoutInput,
+    CreateLinetypeInput, CreateNamedObjectInput, CreateRawRecordInput, CreateTextStyleInput,
+    CreateUnsupportedItemInput, DiagnosticSeverity, EmbeddedElement, LinetypeElement,
+    LinetypeElementKind, RawSection, ReferenceKind, UnsupportedKind, MODEL_LAYOUT,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::io::BufRead;
+use std::str::FromStr;
+use std::sync::Arc;
+
+#[derive(Debug, Clone)]
+pub struct ParsedLayer {
+    pub input: CreateLayerInput,
+    pub entities: Vec<CreateEntityInput>,
+}
+
+/// A whole drawing collected in memory. Large files should be consumed as a
+/// stream of [`ParseEvent`]s from [`DxfParser`] instead.
+#[derive(Debug, Clone, Default)]
+pub struct ParsedDrawing {
+    pub layers: HashMap<String, ParsedLayer>,
+    pub linetypes: Vec<CreateLinetypeInput>,
+    pub text_styles: Vec<CreateTextStyleInput>,
+    pub dim_styles: Vec<CreateDimStyleInput>,
+    pub layouts: Vec<CreateLayoutInput>,
+    pub named_objects: Vec<CreateNamedObjectInput>,
+    pub groups: Vec<CreateEntityGroupInput>,
+    pub references: Vec<CreateExternalReferenceInput>,
+    pub raw_records: Vec<CreateRawRecordInput>,
+    /// Global linetype scale from the `$LTSCALE` header variable.
+    pub linetype_scale: Option<f64>,
+    pub diagnostics: Vec<CreateDiagnosticInput>,
+    pub unsupported: Vec<CreateUnsupportedItemInput>,
+}
+
+/// Table data, layouts and header variables, which are small enough to keep
+/// in memory for the whole parse.
+#[derive(Debug, Clone, Default)]
+pub struct DrawingTables {
+    pub linetypes: Vec<CreateLinetypeInput>,
+    pub text_styles: Vec<CreateTextStyleInput>,
+    pub dim_styles: Vec<CreateDimStyleInput>,
+    /// Layouts ordered by tab, starting with `Model`. Paper space entities
+    /// are streamed tagged with their layout's block name, because LAYOUT
+    /// objects come last in the file; see [`DrawingTables::layout_renames`].
+    pub layouts: Vec<CreateLayoutInput>,
+    /// Objects reachable from the root dictionary, ordered by path.
+    pub named_objects: Vec<CreateNamedObjectInput>,
+    /// GROUP objects named by their ACAD_GROUP dictionary entries.
+    pub groups: Vec<CreateEntityGroupInput>,
+    /// Image and underlay definitions, referenced by IMAGE and underlay
+    /// entities through their `definition` handle.
+    pub references: Vec<CreateExternalReferenceInput>,
+    /// Entities whose draw order differs from file order because of a
+    /// SORTENTSTABLE, as (handle, draw order) pairs. Entities are streamed
+    /// in file order since the tables are stored in OBJECTS, at the end.
+    pub draw_order: Vec<(String, u64)>,
+    pub linetype_scale: Option<f64>,
+}
+
+impl DrawingTables {
+    /// Pairs of (block name, layout name) for layouts whose entities were
+    /// tagged with a block name that differs from the layout name.
+    pub fn layout_renames(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.layouts.iter().filter_map(|layout| {
+            layout
+                .block_name
+                .as_deref()
+                .filter(|block| *block != layout.name)
+                .map(|block| (block, layout.name.as_str()))
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum ParseEvent {
+    /// A layer, emitted before the first entity that references it.
+    Layer(CreateLayerInput),
+    Entity {
+        layer: String,
+        entity: CreateEntityInput,
+    },
+    Diagnostic(CreateDiagnosticInput),
+    /// Emitted after roughly every [`PROGRESS_INTERVAL_BYTES`] of input and
+    /// once more at the end.
+    Progress(ParseProgress),
+    /// Skipped entity types, sections and tables, most frequent first.
+    /// Emitted once, after the last entity.
+    Unsupported(Vec<CreateUnsupportedItemInput>),
+    /// An entity or object the parser does not understand, kept verbatim.
+    RawRecord(CreateRawRecordInput),
+    /// Emitted once, after the last entity.
+    Tables(DrawingTables),
+}
+
+/// Input consumed between two [`ParseEvent::Progress`] events.
+pub const PROGRESS_INTERVAL_BYTES: u64 = 1024 * 1024;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ParseProgress {
+    pub bytes_read: u64,
+    pub entities_parsed: u64,
+    /// Name of the section being read, e.g. `ENTITIES`.
+    pub section: Option<String>,
+}
+
+/// How the parser reacts to malformed values.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ParseMode {
+    /// Record a diagnostic and skip the affected entity. Malformed values in
+    /// table records fall back to their defaults.
+    #[default]
+    Lenient,
+    /// Fail the whole parse on the first error.
+    Strict,
+}
+
+impl ParseMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ParseMode::Lenient => "lenient",
+            ParseMode::Strict => "strict",
+        }
+    }
+}
+
+/// The table record or entity currently being read. The handle is used in
+/// diagnostics; owner, extension dictionary and XDATA are stored with the
+/// entity.
+#[derive(Debug, Default)]
+struct RecordContext {
+    kind: Option<String>,
+    handle: Option<String>,
+    line: usize,
+    failed: bool,
+    /// Group 67 is 1: the entity is in the active paper space layout.
+    paper_space: bool,
+    owner: Option<String>,
+    extension_dictionary: Option<String>,
+    /// Name of the open `102` application group, e.g. `ACAD_REACTORS`.
+    app_group: Option<String>,
+    /// XDATA values keyed by the application name that registered them.
+    xdata: Map<String, Value>,
+    xdata_app: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Section {
+    Other,
+    Blocks,
+    Entities,
+    Done,
+}
+
+pub(crate) const MODEL_SPACE_BLOCK: &str = "*Model_Space";
+
+/// Block of the active paper space layout; other paper space layouts use
+/// `*Paper_Space0`, `*Paper_Space1` and so on.
+pub(crate) const PAPER_SPACE_BLOCK: &str = "*Paper_Space";
+
+pub(crate) fn is_paper_space_block(name: &str) -> bool {
+    name.get(..PAPER_SPACE_BLOCK.len())
+        .is_some_and(|prefix| prefix.eq_ignore_ascii_case(PAPER_SPACE_BLOCK))
+}
+
+/// Streaming DXF parser. Header and table sections are read up front;
+/// entities are then yielded one at a time as [`ParseEvent`]s, so memory use
+/// does not grow with the number of entities.
+pub struct DxfParser<R> {
+    groups: GroupReader<R>,
+    mode: ParseMode,
+    handlers: Arc<EntityHandlerRegistry>,
+    section: Section,
+    section_name: Option<String>,
+    entities_parsed: u64,
+    next_progress_at: u64,
+    pending: VecDeque<ParseEvent>,
+    record: RecordContext,
+    /// LAYER table records by lowercase name, since layer names are
+    /// case-insensitive; the first spelling seen is the one kept.
+    layers: HashMap<String, CreateLayerInput>,
+    /// Lowercase names of the layers already emitted.
+    emitted_layers: HashSet<String>,
+    /// Draw order (file order) of each entity handle seen so far; handles
+    /// are kept unique per file.
+    entity_handles: HashMap<String, u64>,
+    unsupported: HashMap<(UnsupportedKind, String), u64>,
+    linetypes: Vec<CreateLinetypeInput>,
+    text_styles: Vec<CreateTextStyleInput>,
+    dim_styles: Vec<CreateDimStyleInput>,
+    /// STYLE table record handles, used to resolve DIMSTYLE text styles.
+    style_handles: HashMap<String, String>,
+    /// LAYER names by handle, used to resolve viewport frozen layers.
+    layer_handles: HashMap<String, String>,
+    /// BLOCK_RECORD names by handle, used to find an entity's layout.
+    block_records: HashMap<String, String>,
+    /// The paper space block being read in the BLOCKS section.
+    layout_block: Option<String>,
+    /// Block definitions kept as raw records so far.
+    raw_blocks: u64,
+    /// Paper space blocks that entities were tagged with.
+    used_layout_blocks: HashSet<String>,
+    layouts: Vec<CreateLayoutInput>,
+    objects: ObjectIndex,
+    named_objects: Vec<CreateNamedObjectInput>,
+    entity_groups: Vec<CreateEntityGroupInput>,
+    references: Vec<CreateExternalReferenceInput>,
+    /// Sort handles from SORTENTSTABLE objects, by entity handle.
+    sort_handles: HashMap<String, String>,
+    linetype_scale: Option<f64>,
+}
+
+/// The OBJECTS section as read so far, before the dictionary tree is
+/// resolved.
+#[derive(Debug, Default)]
+struct ObjectIndex {
+    /// Object type by handle, for every object with a handle.
+    types: HashMap<String, String>,
+    /// The first DICTIONARY in the section, which is the root.
+    root: Option<String>,
+    /// Entry names and handles of each DICTIONARY, in file order.
+    dictionaries: HashMap<String, Vec<(String, String)>>,
+    /// GROUP objects by handle, named once their dictionary entry is found.
+    groups: HashMap<String, CreateEntityGroupInput>,
+    /// Objects read so far, with or without a handle.
+    count: u64,
+}
+
+/// DIMSTYLE group codes and the dimension variables they carry.
+pub(crate) const DIMSTYLE_VARIABLES: &[(&str, &str)] = &[
+    ("3", "DIMPOST"),
+    ("4", "DIMAPOST"),
+    ("40", "DIMSCALE"),
+    ("41", "DIMASZ"),
+    ("42", "DIMEXO"),
+    ("43", "DIMDLI"),
+    ("44", "DIMEXE"),
+    ("45", "DIMRND"),
+    ("46", "DIMDLE"),
+    ("47", "DIMTP"),
+    ("48", "DIMTM"),
+    ("71", "DIMTOL"),
+    ("72", "DIMLIM"),
+    ("73", "DIMTIH"),
+    ("74", "DIMTOH"),
+    ("75", "DIMSE1"),
+    ("76", "DIMSE2"),
+    ("77", "DIMTAD"),
+    ("78", "DIMZIN"),
+    ("140", "DIMTXT"),
+    ("141", "DIMCEN"),
+    ("142", "DIMTSZ"),
+    ("143", "DIMALTF"),
+    ("144", "DIMLFAC"),
+    ("145", "DIMTVP"),
+    ("146", "DIMTFAC"),
+    ("147", "DIMGAP"),
+    ("170", "DIMALT"),
+    ("171", "DIMALTD"),
+    ("172", "DIMTOFL"),
+    ("173", "DIMSAH"),
+    ("174", "DIMTIX"),
+    ("175", "DIMSOXD"),
+    ("176", "DIMCLRD"),
+    ("177", "DIMCLRE"),
+    ("178", "DIMCLRT"),
+    ("271", "DIMDEC"),
+    ("340", "DIMTXSTY"),
+];
+
+impl<R: BufRead> DxfParser<R> {
+    pub fn new(reader: R, mode: ParseMode) -> Result<Self> {
+        Self::with_handlers(reader, mode, Arc::new(EntityHandlerRegistry::default()))
+    }
+
+    /// A parser that reads entities with the given handlers instead of the
+    /// built-in ones.
+    pub fn with_handlers(
+        reader: R,
+        mode: ParseMode,
+        handlers: Arc<EntityHandlerRegistry>,
+    ) -> Result<Self> {
+        Ok(Self {
+            groups: GroupReader::new(reader)?,
+            mode,
+            handlers,
+            section: Section::Other,
+            section_name: None,
+            entities_parsed: 0,
+            next_progress_at: PROGRESS_INTERVAL_BYTES,
+            pending: VecDeque::new(),
+            record: RecordContext::default(),
+            layers: HashMap::new(),
+            emitted_layers: HashSet::new(),
+            entity_handles: HashMap::new(),
+            unsupported: HashMap::new(),
+            linetypes: Vec::new(),
+            text_styles: Vec::new(),
+            dim_styles: Vec::new(),
+            style_handles: HashMap::new(),
+            layer_handles: HashMap::new(),
+            block_records: HashMap::new(),
+            layout_block: None,
+            raw_blocks: 0,
+            used_layout_blocks: HashSet::new(),
+            layouts: Vec::new(),
+            objects: ObjectIndex::default(),
+            named_objects: Vec::new(),
+            entity_groups: Vec::new(),
+            references: Vec::new(),
+            sort_handles: HashMap::new(),
+            linetype_scale: None,
+        })
+    }
+
+    /// Returns the next event, or `None` once the drawing has been read.
+    pub fn next_event(&mut self) -> Result<Option<ParseEvent>> {
+        loop {
+            if let Some(event) = self.pending.pop_front() {
+                return Ok(Some(event));
+            }
+            if self.section == Section::Done {
+                return Ok(None);
+            }
+            if let Err(e) = self.step() {
+                self.section = Section::Done;
+                return Err(e);
+            }
+            if self.groups.bytes_read() >= self.next_progress_at {
+                self.next_progress_at = self.groups.bytes_read() + PROGRESS_INTERVAL_BYTES;
+                self.pending
+                    .push_back(ParseEvent::Progress(self.progress()));
+            }
+        }
+    }
+
+    pub fn progress(&self) -> ParseProgress {
+        ParseProgress {
+            bytes_read: self.groups.bytes_read(),
+            entities_parsed: self.entities_parsed,
+            section: self.section_name.clone(),
+        }
+    }
+
+    /// Reads the whole drawing into memory.
+    pub fn collect_drawing(mut self) -> Result<ParsedDrawing> {
+        let mut drawing = ParsedDrawing::default();
+
+        while let Some(event) = self.next_event()? {
+            match event {
+                ParseEvent::Layer(input) => {
+                    drawing.layers.insert(
+                        input.name.clone(),
+                        ParsedLayer {
+                            input,
+                            entities: Vec::new(),
+                        },
+                    );
+                }
+                ParseEvent::Entity { layer, entity } => {
+                    if let Some(layer) = drawing.layers.get_mut(&layer) {
+                        layer.entities.push(entity);
+                    }
+                }
+                ParseEvent::Diagnostic(diagnostic) => drawing.diagnostics.push(diagnostic),
+                ParseEvent::Progress(_) => {}
+                ParseEvent::Unsupported(items) => drawing.unsupported = items,
+                ParseEvent::RawRecord(record) => drawing.raw_records.push(record),
+                ParseEvent::Tables(tables) => {
+                    for (block, layout) in tables.layout_renames() {
+                        for record in drawing
+                            .raw_records
+                            .iter_mut()
+                            .filter(|record| record.layout.as_deref() == Some(block))
+                        {
+                            record.layout = Some(layout.to_string());
+                        }
+                        for entity in drawing
+                            .layers
+                            .values_mut()
+                            .flat_map(|layer| layer.entities.iter_mut())
+                            .filter(|entity| entity.layout == block)
+                        {
+                            entity.layout = layout.to_string();
+                        }
+                    }
+                    let draw_order: HashMap<&str, u64> = tables
+                        .draw_order
+                        .iter()
+                        .map(|(handle, order)| (handle.as_str(), *order))
+                        .collect();
+                    for entity in drawing
+                        .layers
+                        .values_mut()
+                        .flat_map(|layer| layer.entities.iter_mut())
+                    {
+                        if let Some(order) = entity
+                            .handle
+                            .as_deref()
+                            .and_then(|handle| draw_order.get(handle))
+                        {
+                            entity.draw_order = *order;
+                        }
+                    }
+                    drawing.layouts = tables.layouts;
+                    drawing.named_objects = tables.named_objects;
+                    drawing.groups = tables.groups;
+                    drawing.references = tables.references;
+                    drawing.linetypes = tables.linetypes;
+                    drawing.text_styles = tables.text_styles;
+                    drawing.dim_styles = tables.dim_styles;
+                    drawing.linetype_scale = tables.linetype_scale;
+                }
+            }
+        }
+
+        Ok(drawing)
+    }
+
+    /// Reads one top-level group: a section start, or one entity inside
+    /// the ENTITIES section.
+    fn step(&mut self) -> Result<()> {
+        let Some(group) = self.groups.next_group()? else {
+            self.finish();
+            return Ok(());
+        };
+
+        if group.code != "0" {
+            return Ok(());
+        }
+
+        if self.section == Section::Entities {
+            return match group.value() {
+                "ENDSEC" => {
+                    self.section = Section::Other;
+                    Ok(())
+                }
+                _ => self.parse_entity(&group),
+            };
+        }
+
+        if self.section == Section::Blocks {
+            return match group.value() {
+                "ENDSEC" => {
+                    self.section = Section::Other;
+                    Ok(())
+                }
+                "BLOCK" => self.begin_block(group.line),
+                "ENDBLK" => {
+                    self.layout_block = None;
+                    Ok(())
+                }
+                _ if self.layout_block.is_some() => self.parse_entity(&group),
+                _ => Ok(()),
+            };
+        }
+
+        match group.value() {
+            "SECTION" => {
+                let Some(name) = self.groups.next_field()? else {
+                    return Ok(());
+                };
+                if name.code != "2" {
+                    return Ok(());
+                }
+                self.section_name = Some(name.value().to_string());
+                match name.value() {
+                    "HEADER" => self.parse_header_section()?,
+                    "TABLES" => self.parse_tables_section()?,
+                    "ENTITIES" => self.section = Section::Entities,
+                    "BLOCKS" => self.section = Section::Blocks,
+                    "OBJECTS" => self.parse_objects_section()?,
+                    other => {
+                        self.count_unsupported(UnsupportedKind::Section, other);
+                        self.groups.skip_past("ENDSEC")?;
+                    }
+                }
+            }
+            "EOF" => self.finish(),
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    fn finish(&mut self) {
+        self.emit_layer("0");
+
+        let mut unsupported: Vec<CreateUnsupportedItemInput> = self
+            .unsupported
+            .drain()
+            .map(|((kind, name), count)| CreateUnsupportedItemInput { kind, name, count })
+            .collect();
+        unsupported.sort_by(|a, b| {
+            a.kind
+                .cmp(&b.kind)
+                .then(b.count.cmp(&a.count))
+                .then_with(|| a.name.cmp(&b.name))
+        });
+        self.pending.push_back(ParseEvent::Unsupported(unsupported));
+
+        self.pending
+            .push_back(ParseEvent::Progress(self.progress()));
+        let layouts = self.finish_layouts();
+        let draw_order = self.finish_draw_order();
+        self.pending.push_back(ParseEvent::Tables(DrawingTables {
+            linetypes: std::mem::take(&mut self.linetypes),
+            text_styles: std::mem::take(&mut self.text_styles),
+            dim_styles: std::mem::take(&mut self.dim_styles),
+            layouts,
+            named_objects: std::mem::take(&mut self.named_objects),
+            groups: std::mem::take(&mut self.entity_groups),
+            references: std::mem::take(&mut self.references),
+            draw_order,
+            linetype_scale: self.linetype_scale,
+        }));
+        self.section = Section::Done;
+    }
+
+    /// Applies SORTENTSTABLE sort handles. Entities are drawn in order of
+    /// their sort handle, or their own handle if they have none; the file
+    /// order slots of the handled entities are handed out again in that
+    /// order, so entities without a handle keep their place.
+    fn finish_draw_order(&mut self) -> Vec<(String, u64)> {
+        let sort_handles = std::mem::take(&mut self.sort_handles);
+        if sort_handles.is_empty() {
+            return Vec::new();
+        }
+
+        let sort_key = |handle: &str| u64::from_str_radix(handle, 16).unwrap_or(u64::MAX);
+        let mut sorted: Vec<(u64, u64, &String)> = self
+            .entity_handles
+            .iter()
+            .map(|(handle, order)| {
+                let key = sort_handles.get(handle).unwrap_or(handle);
+                (sort_key(key), *order, handle)
+            })
+            .collect();
+        let mut slots: Vec<u64> = sorted.iter().map(|(_, order, _)| *order).collect();
+        slots.sort_unstable();
+        sorted.sort_unstable();
+
+        sorted
+            .into_iter()
+            .zip(slots)
+            .filter(|((_, order, _), slot)| order != slot)
+            .map(|((_, _, handle), slot)| (handle.clone(), slot))
+            .collect()
+    }
+
+    /// Adds the model layout and any paper space block without a LAYOUT
+    /// object, and orders the layouts by tab.
+    fn finish_layouts(&mut self) -> Vec<CreateLayoutInput> {
+        let mut layouts = std::mem::take(&mut self.layouts);
+        if !layouts.iter().any(|layout| layout.name == MODEL_LAYOUT) {
+            let mut model = CreateLayoutInput::for_block(MODEL_SPACE_BLOCK, 0);
+            model.name = MODEL_LAYOUT.to_string();
+            layouts.push(model);
+        }
+
+        let mut unclaimed: Vec<&String> = self
+            .used_layout_blocks
+            .iter()
+            .filter(|block| {
+                !layouts
+                    .iter()
+                    .any(|layout| layout.block_name.as_ref() == Some(*block))
+            })
+            .collect();
+        unclaimed.sort();
+        let next_tab = layouts
+            .iter()
+            .map(|layout| layout.tab_order)
+            .max()
+            .unwrap_or(0)
+            + 1;
+        for (tab_order, block) in (next_tab..).zip(unclaimed) {
+            layouts.push(CreateLayoutInput::for_block(block, tab_order));
+        }
+
+        layouts.sort_by(|a, b| {
+            a.tab_order
+                .cmp(&b.tab_order)
+                .then_with(|| a.name.cmp(&b.name))
+        });
+        layouts
+    }
+
+    fn default_layer(name: &str) -> CreateLayerInput {
+        CreateLayerInput {
+            name: name.to_string(),
+            is_locked: false,
+            is_visible: true,
+            color: Some("7".to_string()),
+            line_type: Some("CONTINUOUS".to_string()),
+            line_weight: None,
+        }
+    }
+
+    /// Emits the layer with the given name, matched case-insensitively, if
+    /// it has not been emitted yet, and returns the name it is stored under.
+    fn emit_layer(&mut self, name: &str) -> String {
+        let key = name.to_lowercase();
+        let input = self
+            .layers
+            .entry(key.clone())
+            .or_insert_with(|| Self::default_layer(name));
+        let stored = input.name.clone();
+        if self.emitted_layers.insert(key) {
+            self.pending.push_back(ParseEvent::Layer(input.clone()));
+        }
+        stored
+    }
+
+    fn parse_header_section(&mut self) -> Result<()> {
+        while let Some(group) = self.groups.next_group()? {
+            if group.is("0", "ENDSEC") {
+                break;
+            }
+
+            if group.is("9", "$LTSCALE") {
+                self.begin_record("HEADER", group.line);
+                if let Some(value) = self.groups.next_field()? {
+                    if value.code == "40" {
+                        self.linetype_scale = Some(self.number(&value, 1.0)?);
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn parse_tables_section(&mut self) -> Result<()> {
+        while let Some(group) = self.groups.next_group()? {
+            if group.is("0", "ENDSEC") {
+                break;
+            }
+
+            if !group.is("0", "TABLE") {
+                continue;
+            }
+
+            match self.groups.next_field()? {
+                Some(name) if name.code == "2" => match name.value() {
+                    "LAYER" => self.parse_layer_table()?,
+                    "LTYPE" => self.parse_ltype_table()?,
+                    "STYLE" => self.parse_style_table()?,
+                    "DIMSTYLE" => self.parse_dimstyle_table()?,
+                    "BLOCK_RECORD" => self.parse_block_record_table()?,
+                    other => {
+                        self.count_unsupported(UnsupportedKind::Table, other);
+                        self.groups.skip_past("ENDTAB")?;
+                    }
+                },
+                _ => {}
+            }
+        }
+
+        self.resolve_dim_style_text_styles();
+
+        // Layer 0 always exists, whether or not the table lists it.
+        self.layers
+            .entry("0".to_string())
+            .or_insert_with(|| Self::default_layer("0"));
+        let mut names: Vec<String> = self
+            .layers
+            .values()
+            .map(|layer| layer.name.clone())
+            .collect();
+        names.sort();
+        for name in names {
+            self.emit_layer(&name);
+        }
+
+        Ok(())
+    }
+
+    /// Reads table records of the given type until ENDTAB, calling `record`
+    /// for each one after its `(0, kind)` group has been consumed.
+    fn parse_table(
+        &mut self,
+        kind: &str,
+        mut record: impl FnMut(&mut Self) -> Result<()>,
+    ) -> Result<()> {
+        while let Some(group) = self.groups.next_group()? {
+            if group.is("0", "ENDTAB") {
+                break;
+            }
+
+            if group.is("0", kind) {
+                self.begin_record(kind, group.line);
+                record(self)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn parse_block_record_table(&mut self) -> Result<()> {
+        self.parse_table("BLOCK_RECORD", |parser| {
+            let mut name: Option<String> = None;
+
+            while let Some(group) = parser.groups.next_field()? {
+                match group.code.as_str() {
+                    "2" => name = Some(group.value().to_string()),
+                    "5" => parser.record.handle = Some(group.value().to_ascii_uppercase()),
+                    _ => {}
+                }
+            }
+
+            if let (Some(handle), Some(name)) = (parser.record.handle.clone(), name) {
+                parser.block_records.insert(handle, name);
+            }
+            Ok(())
+        })
+    }
+
+    /// Reads a BLOCK header. Entities of paper space blocks are parsed up
+    /// to ENDBLK; the model space block is skipped, as its entities are in
+    /// the ENTITIES section. Other blocks are kept whole, through their
+    /// ENDBLK, as a raw record.
+    fn begin_block(&mut self, line: usize) -> Result<()> {
+        self.begin_record("BLOCK", line);
+        let mut content = self.read_raw_record("BLOCK")?;
+        let name = raw_groups(&content)
+            .find(|(code, _)| *code == "2")
+            .map(|(_, name)| name.trim().to_string())
+            .unwrap_or_default();
+
+        if is_paper_space_block(&name) {
+            self.layout_block = Some(name);
+            return Ok(());
+        }
+        self.layout_block = None;
+        if name.eq_ignore_ascii_case(MODEL_SPACE_BLOCK) {
+            return self.groups.skip_past("ENDBLK");
+        }
+
+        while self
+            .groups
+            .peek()?
+            .is_some_and(|group| !group.is("0", "ENDSEC"))
+        {
+            let Some(group) = self.groups.next_group()? else {
+                break;
+            };
+            let end = group.is("0", "ENDBLK");
+            push_group(&mut content, &group);
+            if end {
+                while let Some(group) = self.groups.next_field()? {
+                    push_group(&mut content, &group);
+                }
+                break;
+            }
+        }
+
+        self.pending
+            .push_back(ParseEvent::RawRecord(CreateRawRecordInput {
+                section: RawSection::Blocks,
+                record_type: "BLOCK".to_string(),
+                handle: self.record.handle.take(),
+                owner_handle: self.record.owner.take(),
+                layout: None,
+                position: self.raw_blocks,
+                content,
+            }));
+        self.raw_blocks += 1;
+        Ok(())
+    }
+
+    /// Reads the OBJECTS section. Every object's handle and type is
+    /// indexed; DICTIONARY, GROUP and LAYOUT objects are read in full. The
+    /// dictionary tree is resolved once the section ends.
+    fn parse_objects_section(&mut self) -> Result<()> {
+        while let Some(group) = self.groups.next_group()? {
+            if group.is("0", "ENDSEC") {
+                break;
+            }
+            if group.code != "0" {
+                continue;
+            }
+
+            let kind = group.value().to_string();
+            self.begin_record(&kind, group.line);
+            match kind.as_str() {
+                "DICTIONARY" => self.parse_dictionary()?,
+                "GROUP" => self.parse_group()?,
+                "LAYOUT" => self.parse_layout()?,
+                "IMAGEDEF" | "PDFDEFINITION" | "DWFDEFINITION" | "DGNDEFINITION" => {
+                    self.parse_reference_definition(&kind)?
+                }
+                "SORTENTSTABLE" => self.parse_sort_table()?,
+                _ => self.preserve_raw_object(&kind)?,
+            }
+
+            if let Some(handle) = self.record.handle.clone() {
+                self.objects.types.insert(handle, kind);
+            }
+            self.objects.count += 1;
+        }
+
+        self.resolve_named_objects();
+        Ok(())
+    }
+
+    /// Returns the next group of the current object, skipping `102`
+    /// application groups and recording the handle.
+    fn next_object_field(&mut self) -> Result<Option<Group>> {
+        while let Some(group) = self.groups.next_field()? {
+            match group.code.as_str() {
+                "102" => {
+                    self.record.app_group = group.value().strip_prefix('{').map(str::to_string);
+                }
+                _ if self.record.app_group.is_some() => {}
+                "5" => self.record.handle = Some(group.value().to_ascii_uppercase()),
+                _ => return Ok(Some(group)),
+            }
+        }
+        Ok(None)
+    }
+
+    /// Keeps an object that is not otherwise read, e.g. an XRECORD or a
+    /// third-party object, as a raw record.
+    fn preserve_raw_object(&mut self, kind: &str) -> Result<()> {
+        let content = self.read_raw_record(kind)?;
+        self.pending
+            .push_back(ParseEvent::RawRecord(CreateRawRecordInput {
+                section: RawSection::Objects,
+                record_type: kind.to_string(),
+                handle: self.record.handle.clone(),
+                owner_handle: self.record.owner.take(),
+                layout: None,
+                position: self.objects.count,
+                content,
+            }));
+        Ok(())
+    }
+
+    /// Keeps an entity the parser does not understand, e.g. an
+    /// ACAD_PROXY_ENTITY, as a raw record.
+    fn preserve_raw_entity(&mut self, kind: &str, line: usize) -> Result<()> {
+        self.begin_record(kind, line);
+        let content = self.read_raw_record(kind)?;
+
+        let layout = self.entity_layout();
+        if layout != MODEL_LAYOUT {
+            self.used_layout_blocks.insert(layout.clone());
+        }
+        self.pending
+            .push_back(ParseEvent::RawRecord(CreateRawRecordInput {
+                section: RawSection::Entities,
+                record_type: kind.to_string(),
+                handle: self.record.handle.take(),
+                owner_handle: self.record.owner.take(),
+                layout: Some(layout),
+                position: self.entities_parsed,
+                content,
+            }));
+        Ok(())
+    }
+
+    /// Reads the rest of the current record verbatim, as ASCII DXF group
+    /// code / value lines starting with its `0` group. Only the handle,
+    /// owner and paper space flag are picked out.
+    fn read_raw_record(&mut self, kind: &str) -> Result<String> {
+        let mut content = format!("0\n{}\n", kind);
+
+        while let Some(group) = self.groups.next_field()? {
+            match group.code.as_str() {
+                "102" => {
+                    self.record.app_group = group.value().strip_prefix('{').map(str::to_string);
+                }
+                _ if self.record.app_group.is_some() => {}
+                "5" if self.record.handle.is_none() => {
+                    self.record.handle = Some(group.value().to_ascii_uppercase());
+                }
+                "330" if self.record.owner.is_none() => {
+                    self.record.owner = Some(group.value().to_ascii_uppercase());
+                }
+                "67" => self.record.paper_space = group.value() == "1",
+                _ => {}
+            }
+            push_group(&mut content, &group);
+        }
+        Ok(content)
+    }
+
+    /// Reads a SORTENTSTABLE: pairs of an entity handle (331) and the sort
+    /// handle (5) that replaces it in the draw order. The object's own
+    /// handle is the `5` before the first pair.
+    fn parse_sort_table(&mut self) -> Result<()> {
+        let mut entity: Option<String> = None;
+
+        while let Some(group) = self.groups.next_field()? {
+            match group.code.as_str() {
+                "102" => {
+                    self.record.app_group = group.value().strip_prefix('{').map(str::to_string);
+                }
+                _ if self.record.app_group.is_some() => {}
+                "331" => entity = Some(group.value().to_ascii_uppercase()),
+                "5" => {
+                    let handle = group.value().to_ascii_uppercase();
+                    match entity.take() {
+                        Some(entity) => {
+                            self.sort_handles.insert(entity, handle);
+                        }
+                        None => self.record.handle = Some(handle),
+                    }
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    /// Reads an IMAGEDEF or underlay definition: the referenced file path,
+    /// and the image size or underlay sheet name.
+    fn parse_reference_definition(&mut self, kind: &str) -> Result<()> {
+        let mut path = String::new();
+        let mut name: Option<String> = None;
+        let mut width_px: Option<f64> = None;
+        let mut height_px: Option<f64> = None;
+
+        while let Some(group) = self.next_object_field()? {
+            match group.code.as_str() {
+                "1" => path = group.value().to_string(),
+                "2" => name = Some(group.value().to_string()),
+                "10" if kind == "IMAGEDEF" => width_px = Some(self.number(&group, 0.0)?),
+                "20" if kind == "IMAGEDEF" => height_px = Some(self.number(&group, 0.0)?),
+                _ => {}
+            }
+        }
+
+        let reference_kind = match kind {
+            "IMAGEDEF" => ReferenceKind::Image,
+            "PDFDEFINITION" => ReferenceKind::Pdf,
+            "DWFDEFINITION" => ReferenceKind::Dwf,
+            _ => ReferenceKind::Dgn,
+        };
+        if let Some(handle) = self.record.handle.clone() {
+            self.references.push(CreateExternalReferenceInput {
+                handle,
+                kind: reference_kind,
+                path,
+                name: name.filter(|name| !name.is_empty()),
+                width_px,
+                height_px,
+            });
+        }
+        Ok(())
+    }
+
+    fn parse_dictionary(&mut self) -> Result<()> {
+        let mut entries: Vec<(String, String)> = Vec::new();
+        let mut name: Option<String> = None;
+
+        while let Some(group) = self.next_object_field()? {
+            match group.code.as_str() {
+                "3" => name = Some(group.value().to_string()),
+                "350" | "360" => {
+                    if let Some(name) = name.take() {
+                        entries.push((name, group.value().to_ascii_uppercase()));
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        if let Some(handle) = self.record.handle.clone() {
+            self.objects.root.get_or_insert_with(|| handle.clone());
+            self.objects.dictionaries.insert(handle, entries);
+        }
+        Ok(())
+    }
+
+    fn parse_group(&mut self) -> Result<()> {
+        let mut description: Option<String> = None;
+        let mut is_selectable = true;
+        let mut members: Vec<String> = Vec::new();
+
+        while let Some(group) = self.next_object_field()? {
+            match group.code.as_str() {
+                "300" => description = Some(group.value().to_string()),
+                "71" => is_selectable = self.number::<i32>(&group, 1)? != 0,
+                "340" => members.push(group.value().to_ascii_uppercase()),
+                _ => {}
+            }
+        }
+
+        if let Some(handle) = self.record.handle.clone() {
+            self.objects.groups.insert(
+                handle.clone(),
+                CreateEntityGroupInput {
+                    name: String::new(),
+                    handle,
+                    description: description.filter(|description| !description.is_empty()),
+                    is_selectable,
+                    members,
+                },
+            );
+        }
+        Ok(())
+    }
+
+    /// Walks the dictionary tree from the root, naming every object by its
+    /// entry path. GROUP objects take the name of their entry.
+    fn resolve_named_objects(&mut self) {
+        let objects = std::mem::take(&mut self.objects);
+        let Some(root) = objects.root.clone() else {
+            return;
+        };
+        let mut groups = objects.groups;
+
+        let mut visited = HashSet::new();
+        let mut stack = vec![(root, String::new())];
+        while let Some((handle, path)) = stack.pop() {
+            if !visited.insert(handle.clone()) {
+                continue;
+            }
+            let Some(entries) = objects.dictionaries.get(&handle) else {
+                continue;
+            };
+
+            for (name, child) in entries {
+                let Some(object_type) = objects.types.get(child) else {
+                    continue;
+                };
+                let child_path = if path.is_empty() {
+                    name.clone()
+                } else {
+                    format!("{}/{}", path, name)
+                };
+
+                if let Some(mut group) = groups.remove(child) {
+                    group.name = name.clone();
+                    self.entity_groups.push(group);
+                }
+                self.named_objects.push(CreateNamedObjectInput {
+                    path: child_path.clone(),
+                    handle: child.clone(),
+                    object_type: object_type.clone(),
+                });
+                stack.push((child.clone(), child_path));
+            }
+        }
+
+        self.named_objects.sort_by(|a, b| a.path.cmp(&b.path));
+        self.entity_groups.sort_by(|a, b| a.name.cmp(&b.name));
+    }
+
+    fn parse_layout(&mut self) -> Result<()> {
+        // The plot settings before the AcDbLayout subclass marker reuse
+        // codes 1 and 330 for other values.
+        let mut in_layout = false;
+        let mut name = String::new();
+        let mut block_handle: Option<String> = None;
+        let mut tab_order = 0;
+        let mut limits = BoundingBox::from_point(0.0, 0.0);
+
+        while let Some(group) = self.next_object_field()? {
+            let value = group.value();
+
+            match group.code.as_str() {
+                "100" => in_layout = value == "AcDbLayout",
+                _ if !in_layout => {}
+                "1" => name = value.to_string(),
+                "71" => tab_order = self.number(&group, 0)?,
+                "10" => limits.min_x = self.number(&group, 0.0)?,
+                "20" => limits.min_y = self.number(&group, 0.0)?,
+                "11" => limits.max_x = self.number(&group, 0.0)?,
+                "21" => limits.max_y = self.number(&group, 0.0)?,
+                "330" => block_handle = Some(value.to_ascii_uppercase()),
+                _ => {}
+            }
+        }
+
+        if !name.is_empty() {
+            self.layouts.push(CreateLayoutInput {
+                name,
+                block_name: block_handle
+                    .and_then(|handle| self.block_records.get(&handle).cloned()),
+                tab_order,
+                min_x: limits.min_x,
+                min_y: limits.min_y,
+                max_x: limits.max_x,
+                max_y: limits.max_y,
+            });
+        }
+        Ok(())
+    }
+
+    fn parse_layer_table(&mut self) -> Result<()> {
+        self.parse_table("LAYER", |parser| {
+            let mut layer_name = "0".to_string();
+            let mut is_locked = false;
+            let mut color: Option<String> = None;
+            let mut line_type: Option<String> = None;
+            let mut line_weight: Option<String> = None;
+
+            while let Some(group) = parser.groups.next_field()? {
+                let value = group.value();
+
+                match group.code.as_str() {
+                    "2" => layer_name = value.to_string(),
+                    "5" => parser.record.handle = Some(value.to_string()),
+                    "70" => is_locked = parser.number::<i32>(&group, 0)? & 4 != 0,
+                    "62" => color = Some(value.to_string()),
+                    "6" => line_type = Some(value.to_string()),
+                    "370" => line_weight = Some(value.to_string()),
+                    _ => {}
+                }
+            }
+
+            if let Some(handle) = &parser.record.handle {
+                parser
+                    .layer_handles
+                    .insert(handle.to_ascii_uppercase(), layer_name.clone());
+            }
+            match parser.layers.entry(layer_name.to_lowercase()) {
+                Entry::Vacant(entry) => {
+                    entry.insert(CreateLayerInput {
+                        name: layer_name,
+                        is_locked,
+                        is_visible: true,
+                        color,
+                        line_type,
+                        line_weight,
+                    });
+                }
+                Entry::Occupied(entry) => {
+                    let message = format!(
+                        "layer {} differs from layer {} only in case; it is merged into it",
+                        layer_name,
+                        entry.get().name
+                    );
+                    parser.report(
+                        parser.record.line,
+                        Some(2),
+                        DiagnosticSeverity::Warning,
+                        message,
+                    )?;
+                }
+            }
+            Ok(())
+        })
+    }
+
+    fn parse_ltype_table(&mut self) -> Result<()> {
+        self.parse_table("LTYPE", |parser| {
+            let mut name = String::new();
+            let mut description: Option<String> = None;
+            let mut pattern_length = 0.0;
+            let mut elements: Vec<LinetypeElement> = Vec::new();
+
+            while let Some(group) = parser.groups.next_field()? {
+                let value = group.value();
+
+                match group.code.as_str() {
+                    "2" => name = value.to_string(),
+                    "5" => parser.record.handle = Some(value.to_string()),
+                    "3" if !value.is_empty() => description = Some(value.to_string()),
+                    "40" => pattern_length = parser.number(&group, 0.0)?,
+                    "49" => {
+                        let length = parser.number(&group, 0.0)?;
+                        elements.push(LinetypeElement {
+                            kind: LinetypeElementKind::from_length(length),
+                            length,
+                            embedded: None,
+                        });
+                    }
+                    "74" | "75" | "340" | "46" | "50" | "44" | "45" | "9" => {
+                        if let Some(element) = elements.last_mut() {
+                            parser.apply_embedded_code(element, &group)?;
+                        }
+                    }
+                    _ => {}
+                }
+            }
+
+            if !name.is_empty() {
+                parser.linetypes.push(CreateLinetypeInput {
+                    name,
+                    description,
+                    pattern_length,
+                    elements,
+                });
+            }
+            Ok(())
+        })
+    }
+
+    /// Applies one of the complex-element group codes (74, 75, 340, 46, 50,
+    /// 44, 45, 9) to the element most recently opened by a 49 code.
+    fn apply_embedded_code(&mut self, element: &mut LinetypeElement, group: &Group) -> Result<()> {
+        if group.code == "74" {
+            let flags = self.number::<i32>(group, 0)?;
+            if flags & 6 == 0 {
+                return Ok(());
+            }
+            let embedded = element.embedded.get_or_insert_with(|| EmbeddedElement {
+                scale: 1.0,
+                ..EmbeddedElement::default()
+            });
+            embedded.kind = if flags & 2 != 0 { "text" } else { "shape" }.to_string();
+            embedded.absolute_rotation = flags & 1 != 0;
+            return Ok(());
+        }
+
+        let Some(embedded) = element.embedded.as_mut() else {
+            return Ok(());
+        };
+
+        match group.code.as_str() {
+            "75" => embedded.shape_number = Some(self.number(group, 0)?),
+            "340" => embedded.style_handle = Some(group.value().to_string()),
+            "46" => embedded.scale = self.number(group, 1.0)?,
+            "50" => embedded.rotation = self.number(group, 0.0)?,
+            "44" => embedded.offset_x = self.number(group, 0.0)?,
+            "45" => embedded.offset_y = self.number(group, 0.0)?,
+            "9" => embedded.text = Some(group.value().to_string()),
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    fn parse_style_table(&mut self) -> Result<()> {
+        self.parse_table("STYLE", |parser| {
+            let mut style = CreateTextStyleInput::default();
+            let mut handle: Option<String> = None;
+
+            while let Some(group) = parser.groups.next_field()? {
+                let value = group.value();
+
+                match group.code.as_str() {
+                    "2" => style.name = value.to_string(),
+                    "5" => {
+                        handle = Some(value.to_string());
+                        parser.record.handle = handle.clone();
+                    }
+                    "70" => style.flags = parser.number(&group, 0)?,
+                    "40" => style.fixed_height = parser.number(&group, 0.0)?,
+                    "41" => style.width_factor = parser.number(&group, 1.0)?,
+                    "50" => style.oblique_angle = parser.number(&group, 0.0)?,
+                    "3" if !value.is_empty() => style.font_file = Some(value.to_string()),
+                    "4" if !value.is_empty() => style.bigfont_file = Some(value.to_string()),
+                    _ => {}
+                }
+            }
+
+            // Shape file entries (flag 1) have no name and are not text styles.
+            if !style.name.is_empty() {
+                if let Some(handle) = handle {
+                    parser.style_handles.insert(handle, style.name.clone());
+                }
+                parser.text_styles.push(style);
+            }
+            Ok(())
+        })
+    }
+
+    fn parse_dimstyle_table(&mut self) -> Result<()> {
+        self.parse_table("DIMSTYLE", |parser| {
+            let mut name = String::new();
+            let mut properties = Map::new();
+
+            while let Some(group) = parser.groups.next_field()? {
+                let code = group.code.as_str();
+
+                if code == "2" {
+                    name = group.value().to_string();
+                } else if code == "105" {
+                    parser.record.handle = Some(group.value().to_string());
+                } else if let Some((_, variable)) =
+                    DIMSTYLE_VARIABLES.iter().find(|(c, _)| *c == code)
+                {
+                    let value = parser.dimvar_value(&group)?;
+                    properties.insert(variable.to_string(), value);
+                }
+            }
+
+            if !name.is_empty() {
+                parser.dim_styles.push(CreateDimStyleInput {
+                    name,
+                    text_style: None,
+                    properties,
+                });
+            }
+            Ok(())
+        })
+    }
+
+    /// Converts a DIMSTYLE value to JSON by group code range: 1-9 and 300+
+    /// are strings or handles, 40-59 and 140-149 are reals, the rest integers.
+    fn dimvar_value(&mut self, group: &Group) -> Result<Value> {
+        let value = match group.code.parse::<i32>().unwrap_or(0) {
+            40..=59 | 140..=149 => json!(self.number::<f64>(group, 0.0)?),
+            60..=99 | 170..=179 | 270..=289 => json!(self.number::<i64>(group, 0)?),
+            _ => json!(group.value()),
+        };
+        Ok(value)
+    }
+
+    fn resolve_dim_style_text_styles(&mut self) {
+        for dim_style in &mut self.dim_styles {
+            dim_style.text_style = dim_style
+                .properties
+                .get("DIMTXSTY")
+                .and_then(Value::as_str)
+                .and_then(|handle| self.style_handles.get(handle))
+                .cloned();
+        }
+    }
+
+    fn find_text_style(&self, name: &str) -> Option<&CreateTextStyleInput> {
+        self.text_styles
+            .iter()
+            .find(|style| style.name.eq_ignore_ascii_case(name))
+    }
+
+    fn find_dim_style(&self, name: &str) -> Option<&CreateDimStyleInput> {
+        self.dim_styles
+            .iter()
+            .find(|style| style.name.eq_ignore_ascii_case(name))
+    }
+
+    fn parse_entity(&mut self, group: &Group) -> Result<()> {
+        let kind = group.value();
+        let handlers = Arc::clone(&self.handlers);
+        if let Some(handler) = handlers.get(kind) {
+            self.begin_record(kind, group.line);
+            if let Some(parsed) = handler.parse(self)? {
+                self.add_entity_to_layer(parsed);
+            }
+            return Ok(());
+        }
+
+        // SEQEND terminates the sub-entities of an INSERT; it is not an
+        // entity itself, but kept so that the preserved INSERT can be
+        // written back.
+        if kind != "SEQEND" {
+            self.count_unsupported(UnsupportedKind::Entity, kind);
+        }
+        self.preserve_raw_entity(kind, group.line)
+    }
+
+    fn count_unsupported(&mut self, kind: UnsupportedKind, name: &str) {
+        *self
+            .unsupported
+            .entry((kind, name.to_string()))
+            .or_insert(0) += 1;
+    }
+
+    fn begin_record(&mut self, kind: &str, line: usize) {
+        self.record = RecordContext {
+            kind: Some(kind.to_string()),
+            line,
+            ..RecordContext::default()
+        };
+    }
+
+    /// Returns the next group of the current entity that is specific to its
+    /// type. Handles, owner references, `102` application groups and XDATA
+    /// are collected into the record context instead.
+    fn next_entity_field(&mut self) -> Result<Option<Group>> {
+        while let Some(group) = self.groups.next_field()? {
+            if !self.read_common_group(&group)? {
+                return Ok(Some(group));
+            }
+        }
+        Ok(None)
+    }
+
+    fn read_common_group(&mut self, group: &Group) -> Result<bool> {
+        if matches!(group.code.parse::<i32>(), Ok(1000..=1071)) {
+            self.read_xdata(group)?;
+            return Ok(true);
+        }
+
+        let value = group.value();
+        match group.code.as_str() {
+            "5" => self.record.handle = Some(value.to_ascii_uppercase()),
+            "102" => {
+                self.record.app_group = value.strip_prefix('{').map(str::to_string);
+            }
+            "360" if self.record.app_group.as_deref() == Some("ACAD_XDICTIONARY") => {
+                self.record.extension_dictionary = Some(value.to_ascii_uppercase());
+            }
+            // Reactors and other application group contents are not kept.
+            _ if self.record.app_group.is_some() => {}
+            "330" => self.record.owner = Some(value.to_ascii_uppercase()),
+            "67" => self.record.paper_space = value == "1",
+            _ => return Ok(false),
+        }
+        Ok(true)
+    }
+
+    /// Appends one XDATA group to the values of the current application.
+    /// Points (1010-1013 with their 102x/103x companions) are stored as
+    /// `[x, y, z]` arrays; everything else as `{"code", "value"}` pairs.
+    fn read_xdata(&mut self, group: &Group) -> Result<()> {
+        let code: i32 = group.code.parse().unwrap_or(0);
+        if code == 1001 {
+            let app = group.value().to_string();
+            self.record
+                .xdata
+                .entry(app.clone())
+                .or_insert_with(|| json!([]));
+            self.record.xdata_app = Some(app);
+            return Ok(());
+        }
+
+        let value = match code {
+            1010..=1033 | 1040..=1042 => json!(self.number::<f64>(group, 0.0)?),
+            1070 | 1071 => json!(self.number::<i64>(group, 0)?),
+            _ => json!(group.value()),
+        };
+
+        let Some(values) = self
+            .record
+            .xdata_app
+            .as_ref()
+            .and_then(|app| self.record.xdata.get_mut(app))
+            .and_then(Value::as_array_mut)
+        else {
+            // XDATA must start with a 1001 application name.
+            return Ok(());
+        };
+
+        match code {
+            1010..=1013 => values.push(json!({"code": code, "value": [value, 0.0, 0.0]})),
+            1020..=1023 | 1030..=1033 => {
+                let base = 1010 + code % 10;
+                let axis = (code / 10 - 101) as usize;
+                if let Some(point) = values
+                    .last_mut()
+                    .filter(|last| last["code"] == base)
+                    .and_then(|last| last["value"].as_array_mut())
+                {
+                    point[axis] = value;
+                }
+            }
+            _ => values.push(json!({"code": code, "value": value})),
+        }
+        Ok(())
+    }
+
+    /// Parses a numeric group value. A malformed value is reported and, in
+    /// lenient mode, replaced with `default` while the current record is
+    /// marked as failed; in strict mode it aborts the parse.
+    fn number<T: FromStr>(&mut self, group: &Group, default: T) -> Result<T> {
+        match group.value().parse() {
+            Ok(parsed) => Ok(parsed),
+            Err(_) => {
+                self.invalid_value(group)?;
+                Ok(default)
+            }
+        }
+    }
+
+    fn report(
+        &mut self,
+        line: usize,
+        group_code: Option<i32>,
+        severity: DiagnosticSeverity,
+        message: String,
+    ) -> Result<()> {
+        let diagnostic = CreateDiagnosticInput {
+            line: line as u64,
+            group_code,
+            entity_type: self.record.kind.clone(),
+            entity_handle: self.record.handle.clone(),
+            severity,
+            message,
+        };
+
+        if self.mode == ParseMode::Strict && severity == DiagnosticSeverity::Error {
+            return Err(Error::InvalidDxf(diagnostic.to_string()));
+        }
+
+        self.pending.push_back(ParseEvent::Diagnostic(diagnostic));
+        Ok(())
+    }
+
+    /// Layout of the current entity, as its block name for paper space. The
+    /// block being read wins, then the owner block record, then group 67.
+    fn entity_layout(&self) -> String {
+        if let Some(block) = &self.layout_block {
+            return block.clone();
+        }
+        let owner_block = self
+            .record
+            .owner
+            .as_ref()
+            .and_then(|owner| self.block_records.get(owner));
+        match owner_block {
+            Some(block) if is_paper_space_block(block) => block.clone(),
+            _ if self.record.paper_space => PAPER_SPACE_BLOCK.to_string(),
+            _ => MODEL_LAYOUT.to_string(),
+        }
+    }
+
+    fn add_entity_to_layer(&mut self, parsed: ParsedEntity) {
+        let ParsedEntity {
+            layer,
+            geometry,
+            bbox,
+            elevation,
+        } = parsed;
+        let mut entity = CreateEntityInput {
+            data: geometry,
+            min_x: bbox.min_x,
+            min_y: bbox.min_y,
+            max_x: bbox.max_x,
+            max_y: bbox.max_y,
+            min_z: elevation.min_z,
+            max_z: elevation.max_z,
+            ..Default::default()
+        };
+        if self.record.failed {
+            let message = format!("{} skipped because of invalid values", entity.entity_type());
+            // Only errors can abort a strict parse, so this cannot fail.
+            let _ = self.report(self.record.line, None, DiagnosticSeverity::Warning, message);
+            return;
+        }
+
+        entity.layout = self.entity_layout();
+        if entity.layout != MODEL_LAYOUT {
+            self.used_layout_blocks.insert(entity.layout.clone());
+        }
+        entity.draw_order = self.entities_parsed;
+        entity.handle = self.record.handle.clone().filter(|handle| {
+            if let Entry::Vacant(entry) = self.entity_handles.entry(handle.clone()) {
+                entry.insert(entity.draw_order);
+                return true;
+            }
+            let message = format!(
+                "duplicate handle {}; the entity is stored without it",
+                handle
+            );
+            let _ = self.report(
+                self.record.line,
+                Some(5),
+                DiagnosticSeverity::Warning,
+                message,
+            );
+            false
+        });
+        entity.owner_handle = self.record.owner.take();
+        entity.extension_dictionary = self.record.extension_dictionary.take();
+        if !self.record.xdata.is_empty() {
+            entity.xdata = Some(Value::Object(std::mem::take(&mut self.record.xdata)));
+        }
+
+        self.entities_parsed += 1;
+        let layer = self.emit_layer(&layer);
+        self.pending.push_back(ParseEvent::Entity { layer, entity });
+    }
+}
+
+impl<R: BufRead> Iterator for DxfParser<R> {
+    type Item = Result<ParseEvent>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_event().transpose()
+    }
+}
+
+impl<R: BufRead> EntityReader for DxfParser<R> {
+    fn next_field(&mut self) -> Result<Option<Group>> {
+        self.next_entity_field()
+    }
+
+    fn next_sub_entity(&mut self, kind: &str) -> Result<bool> {
+        if !matches!(self.groups.peek()?, Some(group) if group.is("0", kind)) {
+            return Ok(false);
+        }
+        self.groups.next_group()?;
+        Ok(true)
+    }
+
+    fn next_sub_entity_field(&mut self) -> Result<Option<Group>> {
+        self.groups.next_field()
+    }
+
+    fn invalid_value(&mut self, group: &Group) -> Result<()> {
+        self.record.failed = true;
+        self.report(
+            group.line,
+            group.code.parse().ok(),
+            DiagnosticSeverity::Error,
+            format!("invalid numeric value '{}'", group.value()),
+        )
+    }
+
+    fn text_style(&self, name: &str) -> Option<&CreateTextStyleInput> {
+        self.find_text_style(name)
+    }
+
+    fn dim_style(&self, name: &str) -> Option<&CreateDimStyleInput> {
+        self.find_dim_style(name)
+    }
+
+    fn layer_name(&self, handle: &str) -> Option<&str> {
+        self.layer_handles.get(handle).map(String::as_str)
+    }
+}
+
+pub fn parse_dxf(content: &str) -> Result<ParsedDrawing> {
+    parse_dxf_bytes(content.as_bytes(), ParseMode::default())
+}
+
+/// Parses raw file contents held in memory. ASCII and binary DXF are both
+/// accepted.
+pub fn parse_dxf_bytes(data: &[u8], mode: ParseMode) -> Result<ParsedDrawing> {
+    DxfParser::new(data, mode)?.collect_drawing()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entity_handlers::EntityHandler;
+    use crate::models::geometry;
+    use crate::models::{ElevationRange, Geometry, Point, Point3, GEOMETRY_VERSION};
+
+    /// Entity data as it is stored and served.
+    fn data(entity: &CreateEntityInput) -> Value {
+        serde_json::to_value(&entity.data).unwrap()
+    }
+
+    #[test]
+    fn test_parse_simple_dxf() {
+        let content = r#"0
+SECTION
+2
+HEADER
+0
+ENDSEC
+0
+SECTION
+2
+TABLES
+0
+TABLE
+2
+LAYER
+0
+LAYER
+2
+0
+70
+0
+62
+7
+6
+CONTINUOUS
+0
+ENDTAB
+0
+ENDSEC
+0
+SECTION
+2
+ENTITIES
+0
+LINE
+8
+0
+10
+0.0
+20
+0.0
+11
+100.0
+21
+100.0
+0
+ENDSEC
+0
+EOF
+"#;
+
+        let result = parse_dxf(content);
+        assert!(result.is_ok());
+
+        let layers = result.unwrap().layers;
+        assert!(layers.contains_key("0"));
+
+        let layer = &layers["0"];
+        assert_eq!(layer.entities.len(), 1);
+        assert_eq!(layer.entities[0].entity_type(), "LINE");
+    }
+
+    #[test]
+    fn test_parse_text() {
+        let content = r#"0
+SECTION
+2
+ENTITIES
+0
+TEXT
+8
+0
+10
+10.0
+20
+10.0
+40
+5.0
+1
+Sample Text
+0
+ENDSEC
+0
+EOF
+"#;
+
+        let result = parse_dxf(content);
+        assert!(result.is_ok());
+
+        let layers = result.unwrap().layers;
+        assert!(layers.contains_key("0"));
+
+        let layer = &layers["0"];
+        assert_eq!(layer.entities.len(), 1);
+        assert_eq!(layer.entities[0].entity_type(), "TEXT");
+        assert_eq!(data(&layer.entities[0])["text"], "Sample Text");
+    }
+
+    #[test]
+    fn test_parse_ltype_table() {
+        let content = r#"0
+SECTION
+2
+HEADER
+9
+$LTSCALE
+40
+2.5
+0
+ENDSEC
+0
+SECTION
+2
+TABLES
+0
+TABLE
+2
+LTYPE
+0
+LTYPE
+2
+DASHDOT
+70
+0
+3
+Dash dot __ . __ . __
+72
+65
+73
+4
+40
+1.0
+49
+0.5
+74
+0
+49
+-0.25
+74
+0
+49
+0.0
+74
+0
+49
+-0.25
+74
+0
+0
+LTYPE
+2
+GAS_LINE
+70
+0
+3
+Gas line ----GAS----
+72
+65
+73
+3
+40
+0.8
+49
+0.5
+74
+0
+49
+-0.2
+74
+2
+75
+0
+340
+11
+46
+0.1
+50
+0.0
+44
+-0.1
+45
+-0.05
+9
+GAS
+49
+-0.1
+74
+0
+0
+ENDTAB
+0
+ENDSEC
+0
+SECTION
+2
+ENTITIES
+0
+LINE
+8
+0
+6
+DASHDOT
+48
+0.5
+10
+0.0
+20
+0.0
+11
+10.0
+21
+0.0
+0
+ENDSEC
+0
+EOF
+"#;
+
+        let drawing = parse_dxf(content).unwrap();
+        assert_eq!(drawing.linetype_scale, Some(2.5));
+        assert_eq!(drawing.linetypes.len(), 2);
+
+        let dashdot = &drawing.linetypes[0];
+        assert_eq!(dashdot.name, "DASHDOT");
+        assert_eq!(dashdot.pattern_length, 1.0);
+        let kinds: Vec<_> = dashdot.elements.iter().map(|e| e.kind).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                LinetypeElementKind::Dash,
+                LinetypeElementKind::Gap,
+                LinetypeElementKind::Dot,
+                LinetypeElementKind::Gap,
+            ]
+        );
+
+        let gas = &drawing.linetypes[1];
+        assert_eq!(gas.elements.len(), 3);
+        let embedded = gas.elements[1].embedded.as_ref().unwrap();
+        assert_eq!(embedded.kind, "text");
+        assert_eq!(embedded.text.as_deref(), Some("GAS"));
+        assert_eq!(embedded.style_handle.as_deref(), Some("11"));
+        assert_eq!(embedded.scale, 0.1);
+        assert_eq!(embedded.offset_x, -0.1);
+        assert!(gas.elements[2].embedded.is_none());
+
+        let line = &drawing.layers["0"].entities[0];
+        assert_eq!(data(line)["line_type"], "DASHDOT");
+        assert_eq!(data(line)["line_type_scale"], 0.5);
+    }
+
+    #[test]
+    fn test_parse_styles_and_link_entities() {
+        let content = include_str!("../../fixtures/sample_tables.dxf");
+
+        let drawing = parse_dxf(content).unwrap();
+
+        let names: Vec<&str> = drawing
+            .text_styles
+            .iter()
+            .map(|style| style.name.as_str())
+            .collect();
+        assert_eq!(names, vec!["STANDARD", "TITLE"]);
+        let title = &drawing.text_styles[1];
+        assert_eq!(title.fixed_height, 5.0);
+        assert_eq!(title.width_factor, 0.8);
+        assert_eq!(title.font_file.as_deref(), Some("romans.shx"));
+        assert_eq!(title.bigfont_file.as_deref(), Some("gbcbig.shx"));
+        assert!(drawing.text_styles[0].bigfont_file.is_none());
+
+        assert_eq!(drawing.dim_styles.len(), 1);
+        let arch = &drawing.dim_styles[0];
+        assert_eq!(arch.name, "ARCH");
+        assert_eq!(arch.text_style.as_deref(), Some("TITLE"));
+        assert_eq!(arch.properties["DIMTXT"], 2.5);
+        assert_eq!(arch.properties["DIMTAD"], 1);
+
+        let entities = &drawing.layers["0"].entities;
+
+        let site_plan = entities
+            .iter()
+            .find(|e| e.entity_type() == "TEXT" && data(e)["text"] == "Site Plan")
+            .unwrap();
+        assert_eq!(data(site_plan)["style"], "TITLE");
+        assert_eq!(data(site_plan)["height"], 5.0);
+        assert_eq!(data(site_plan)["width_factor"], 0.8);
+        assert_eq!(data(site_plan)["oblique_angle"], 15.0);
+        assert_eq!(site_plan.max_y, 55.0);
+
+        let mtext = entities
+            .iter()
+            .find(|e| e.entity_type() == "MTEXT")
+            .unwrap();
+        assert_eq!(data(mtext)["text"], "General notes\\PSee sheet A-101");
+        assert_eq!(mtext.min_x, 60.0);
+        assert_eq!(mtext.max_x, 90.0);
+        assert_eq!(mtext.max_y, 80.0);
+
+        let dimension = entities
+            .iter()
+            .find(|e| e.entity_type() == "DIMENSION")
+            .unwrap();
+        assert_eq!(data(dimension)["dim_style"], "ARCH");
+        assert_eq!(data(dimension)["text_style"], "TITLE");
+        assert_eq!(data(dimension)["text_height"], 250.0);
+        assert_eq!(data(dimension)["dimension_type"], 0);
+        assert_eq!(dimension.min_x, 0.0);
+        assert_eq!(dimension.max_x, 100.0);
+    }
+
+    #[test]
+    fn test_binary_dxf_matches_ascii() {
+        let ascii = parse_dxf_bytes(
+            include_bytes!("../../fixtures/sample_tables.dxf"),
+            ParseMode::Lenient,
+        )
+        .unwrap();
+        let binary = parse_dxf_bytes(
+            include_bytes!("../../fixtures/sample_binary.dxf"),
+            ParseMode::Lenient,
+        )
+        .unwrap();
+
+        let mut names: Vec<&String> = ascii.layers.keys().collect();
+        names.sort();
+        let mut binary_names: Vec<&String> = binary.layers.keys().collect();
+        binary_names.sort();
+        assert_eq!(names, binary_names);
+
+        for name in names {
+            let expected = serde_json::to_value(&ascii.layers[name].entities).unwrap();
+            let actual = serde_json::to_value(&binary.layers[name].entities).unwrap();
+            assert_eq!(expected, actual, "entities differ on layer {}", name);
+        }
+
+        assert_eq!(
+            serde_json::to_value(&ascii.linetypes).unwrap(),
+            serde_json::to_value(&binary.linetypes).unwrap()
+        );
+        assert_eq!(
+            serde_json::to_value(&ascii.text_styles).unwrap(),
+            serde_json::to_value(&binary.text_styles).unwrap()
+        );
+        assert_eq!(
+            serde_json::to_value(&ascii.dim_styles).unwrap(),
+            serde_json::to_value(&binary.dim_styles).unwrap()
+        );
+        assert_eq!(binary.linetype_scale, Some(2.0));
+    }
+
+    const MALFORMED_ENTITIES: &str = "0\nSECTION\n2\nENTITIES\n0\nLINE\n5\n1A\n8\nWALLS\n10\n0.0\n20\nabc\n11\n10.0\n21\n10.0\n0\nCIRCLE\n5\n1B\n8\nWALLS\n10\n5.0\n20\n5.0\n40\n2.0\n0\nENDSEC\n0\nEOF\n";
+
+    #[test]
+    fn test_lenient_mode_skips_malformed_entity() {
+        let drawing = parse_dxf_bytes(MALFORMED_ENTITIES.as_bytes(), ParseMode::Lenient).unwrap();
+
+        let entities = &drawing.layers["WALLS"].entities;
+        assert_eq!(entities.len(), 1);
+        assert_eq!(entities[0].entity_type(), "CIRCLE");
+
+        assert_eq!(drawing.diagnostics.len(), 2);
+        let error = &drawing.diagnostics[0];
+        assert_eq!(error.severity, DiagnosticSeverity::Error);
+        assert_eq!(error.line, 14);
+        assert_eq!(error.group_code, Some(20));
+        assert_eq!(error.entity_type.as_deref(), Some("LINE"));
+        assert_eq!(error.entity_handle.as_deref(), Some("1A"));
+
+        let warning = &drawing.diagnostics[1];
+        assert_eq!(warning.severity, DiagnosticSeverity::Warning);
+        assert_eq!(warning.line, 6);
+    }
+
+    #[test]
+    fn test_strict_mode_fails_on_first_error() {
+        let err = parse_dxf_bytes(MALFORMED_ENTITIES.as_bytes(), ParseMode::Strict).unwrap_err();
+
+        assert_eq!(
+            err.to_string(),
+            "Invalid DXF: line 14: invalid numeric value 'abc' (group code 20) in LINE 1A"
+        );
+    }
+
+    #[test]
+    fn test_yields_entities_before_end_of_input() {
+        struct FailAfter(&'static [u8]);
+
+        impl std::io::Read for FailAfter {
+            fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+                if self.0.is_empty() {
+                    return Err(std::io::Error::other("connection reset"));
+                }
+                let len = buf.len().min(self.0.len());
+                buf[..len].copy_from_slice(&self.0[..len]);
+                self.0 = &self.0[len..];
+                Ok(len)
+            }
+        }
+
+        let content = b"0\nSECTION\n2\nENTITIES\n0\nCIRCLE\n8\nWALLS\n10\n5.0\n20\n5.0\n40\n2.0\n0\nLINE\n8\nWALLS\n";
+        let mut parser = DxfParser::new(
+            std::io::BufReader::new(FailAfter(content)),
+            ParseMode::Strict,
+        )
+        .unwrap();
+
+        match parser.next().unwrap().unwrap() {
+            ParseEvent::Layer(layer) => assert_eq!(layer.name, "WALLS"),
+            other => panic!("expected layer, got {:?}", other),
+        }
+        match parser.next().unwrap().unwrap() {
+            ParseEvent::Entity { layer, entity } => {
+                assert_eq!(layer, "WALLS");
+                assert_eq!(entity.entity_type(), "CIRCLE");
+            }
+            other => panic!("expected entity, got {:?}", other),
+        }
+        assert!(parser.next().unwrap().is_err());
+        assert!(parser.next().is_none());
+    }
+
+    #[test]
+    fn test_reports_progress() {
+        let mut content = String::from("0\nSECTION\n2\nENTITIES\n");
+        while content.len() < 3 * PROGRESS_INTERVAL_BYTES as usize {
+            content.push_str("0\nLINE\n8\n0\n10\n0.0\n20\n0.0\n11\n1.0\n21\n1.0\n");
+        }
+        content.push_str("0\nENDSEC\n0\nEOF\n");
+
+        let parser = DxfParser::new(content.as_bytes(), ParseMode::Lenient).unwrap();
+        let progress: Vec<ParseProgress> = parser
+            .filter_map(|event| match event.unwrap() {
+                ParseEvent::Progress(progress) => Some(progress),
+                _ => None,
+            })
+            .collect();
+
+        assert!(progress.len() >= 3);
+        assert!(progress
+            .windows(2)
+            .all(|pair| pair[0].bytes_read < pair[1].bytes_read
+                && pair[0].entities_parsed <= pair[1].entities_parsed));
+        assert_eq!(progress[0].section.as_deref(), Some("ENTITIES"));
+
+        let last = progress.last().unwrap();
+        assert_eq!(last.bytes_read, content.len() as u64);
+        assert_eq!(
+            last.entities_parsed,
+            content.matches("LINE\n").count() as u64
+        );
+    }
+
+    #[test]
+    fn test_counts_unsupported_entities_sections_and_tables() {
+        let content = "0\nSECTION\n2\nCLASSES\n0\nCLASS\n1\nACDBPLACEHOLDER\n0\nENDSEC\n\
+0\nSECTION\n2\nTABLES\n0\nTABLE\n2\nVPORT\n0\nVPORT\n2\n*ACTIVE\n0\nENDTAB\n0\nENDSEC\n\
+0\nSECTION\n2\nENTITIES\n\
+0\nSOLID\n8\n0\n0\nACAD_PROXY_ENTITY\n8\n0\n0\nACAD_PROXY_ENTITY\n8\n0\n\
+0\nINSERT\n8\n0\n66\n1\n0\nATTRIB\n8\n0\n0\nSEQEND\n8\n0\n\
+0\nPOLYLINE\n8\n0\n0\nVERTEX\n10\n0.0\n20\n0.0\n0\nVERTEX\n10\n1.0\n20\n1.0\n0\nSEQEND\n8\n0\n\
+0\nENDSEC\n0\nEOF\n";
+
+        let drawing = parse_dxf(content).unwrap();
+
+        let inventory: Vec<(UnsupportedKind, &str, u64)> = drawing
+            .unsupported
+            .iter()
+            .map(|item| (item.kind, item.name.as_str(), item.count))
+            .collect();
+        assert_eq!(
+            inventory,
+            vec![
+                (UnsupportedKind::Entity, "ACAD_PROXY_ENTITY", 2),
+                (UnsupportedKind::Entity, "ATTRIB", 1),
+                (UnsupportedKind::Entity, "INSERT", 1),
+                (UnsupportedKind::Entity, "SOLID", 1),
+                (UnsupportedKind::Section, "CLASSES", 1),
+                (UnsupportedKind::Table, "VPORT", 1),
+            ]
+        );
+        assert_eq!(drawing.layers["0"].entities.len(), 1);
+    }
+
+    #[test]
+    fn test_keeps_z_and_elevation() {
+        let content = "0\nSECTION\n2\nENTITIES\n\
+0\nLINE\n8\nSPOT\n10\n0.0\n20\n0.0\n30\n12.5\n11\n4.0\n21\n3.0\n31\n14.0\n39\n2.0\n\
+0\nPOLYLINE\n8\nCONTOUR\n30\n105.0\n70\n0\n\
+0\nVERTEX\n10\n0.0\n20\n0.0\n30\n0.0\n0\nVERTEX\n10\n5.0\n20\n1.0\n30\n0.0\n0\nSEQEND\n\
+0\nENDSEC\n0\nEOF\n";
+
+        let drawing = parse_dxf(content).unwrap();
+
+        let line = &drawing.layers["SPOT"].entities[0];
+        assert_eq!(data(line)["start"]["z"], 12.5);
+        assert_eq!(data(line)["end"]["z"], 14.0);
+        assert_eq!((line.min_z, line.max_z), (12.5, 16.0));
+
+        let contour = &drawing.layers["CONTOUR"].entities[0];
+        assert_eq!(data(contour)["vertices"][1]["z"], 105.0);
+        assert_eq!((contour.min_z, contour.max_z), (105.0, 105.0));
+    }
+
+    #[test]
+    fn test_reads_colors_line_weights_and_bulges() {
+        let content = "0\nSECTION\n2\nTABLES\n0\nTABLE\n2\nLAYER\n\
+0\nLAYER\n2\nWALLS\n70\n0\n62\n1\n370\n35\n0\nENDTAB\n0\nENDSEC\n\
+0\nSECTION\n2\nENTITIES\n\
+0\nLINE\n8\nWALLS\n62\n3\n370\n50\n420\n-1040121857\n10\n0.0\n20\n0.0\n11\n4.0\n21\n0.0\n\
+0\nPOLYLINE\n8\nWALLS\n70\n1\n\
+0\nVERTEX\n10\n0.0\n20\n0.0\n42\n1.0\n0\nVERTEX\n10\n4.0\n20\n0.0\n0\nSEQEND\n\
+0\nENDSEC\n0\nEOF\n";
+
+        let drawing = parse_dxf(content).unwrap();
+
+        assert_eq!(
+            drawing.layers["WALLS"].input.line_weight.as_deref(),
+            Some("35")
+        );
+        let line = &drawing.layers["WALLS"].entities[0];
+        assert_eq!(data(line)["color"], 3);
+        assert_eq!(data(line)["line_weight"], 50);
+        assert_eq!(data(line)["true_color"], 0x00_FF_FF);
+
+        // A half circle below the chord from (0, 0) to (4, 0).
+        let polyline = &drawing.layers["WALLS"].entities[1];
+        assert_eq!(data(polyline)["bulges"], json!([1.0, 0.0]));
+        assert_eq!(data(polyline)["closed"], true);
+        assert_eq!(
+            (
+                polyline.min_x,
+                polyline.min_y,
+                polyline.max_x,
+                polyline.max_y
+            ),
+            (0.0, -2.0, 4.0, 0.0)
+        );
+    }
+
+    #[test]
+    fn test_merges_layers_differing_only_in_case() {
+        let content = "0\nSECTION\n2\nTABLES\n0\nTABLE\n2\nLAYER\n\
+0\nLAYER\n2\nWalls\n70\n0\n62\n1\n0\nLAYER\n2\nWALLS\n70\n0\n62\n5\n0\nENDTAB\n0\nENDSEC\n\
+0\nSECTION\n2\nENTITIES\n\
+0\nLINE\n8\nwalls\n10\n0.0\n20\n0.0\n11\n1.0\n21\n0.0\n\
+0\nLINE\n8\nWALLS\n10\n0.0\n20\n0.0\n11\n2.0\n21\n0.0\n\
+0\nLINE\n8\nnotes\n10\n0.0\n20\n0.0\n11\n3.0\n21\n0.0\n\
+0\nLINE\n8\nNotes\n10\n0.0\n20\n0.0\n11\n4.0\n21\n0.0\n\
+0\nENDSEC\n0\nEOF\n";
+
+        let drawing = parse_dxf(content).unwrap();
+
+        let mut names: Vec<&str> = drawing.layers.keys().map(String::as_str).collect();
+        names.sort();
+        assert_eq!(names, ["0", "Walls", "notes"]);
+        assert_eq!(drawing.layers["Walls"].input.color.as_deref(), Some("1"));
+        assert_eq!(drawing.layers["Walls"].entities.len(), 2);
+        assert_eq!(drawing.layers["notes"].entities.len(), 2);
+        assert_eq!(drawing.diagnostics.len(), 1);
+        assert_eq!(drawing.diagnostics[0].line, 18);
+    }
+
+    #[test]
+    fn test_reads_lwpolylines_as_polylines() {
+        let content = "0\nSECTION\n2\nENTITIES\n\
+0\nLWPOLYLINE\n8\nWALLS\n100\nAcDbPolyline\n90\n3\n70\n1\n38\n2.5\n\
+10\n0.0\n20\n0.0\n42\n1.0\n10\n4.0\n20\n0.0\n10\n4.0\n20\n3.0\n\
+0\nENDSEC\n0\nEOF\n";
+
+        let drawing = parse_dxf(content).unwrap();
+
+        assert!(drawing.unsupported.is_empty());
+        let polyline = &drawing.layers["WALLS"].entities[0];
+        assert_eq!(polyline.entity_type(), "POLYLINE");
+        assert_eq!(
+            data(polyline)["vertices"],
+            json!([
+                {"x": 0.0, "y": 0.0, "z": 2.5},
+                {"x": 4.0, "y": 0.0, "z": 2.5},
+                {"x": 4.0, "y": 3.0, "z": 2.5},
+            ])
+        );
+        assert_eq!(data(polyline)["bulges"], json!([1.0, 0.0, 0.0]));
+        assert_eq!(data(polyline)["closed"], true);
+        assert_eq!(
+            (
+                polyline.min_x,
+                polyline.min_y,
+                polyline.max_x,
+                polyline.max_y
+            ),
+            (0.0, -2.0, 4.0, 3.0)
+        );
+        assert_eq!((polyline.min_z, polyline.max_z), (2.5, 2.5));
+    }
+
+    #[test]
+    fn test_reads_hatch_boundary_loops() {
+        // A square with a clockwise arc for its right side, a round hole,
+        // a full ellipse, a parabola closed by a line and a seed point
+        // after the pattern.
+        let content = "0\nSECTION\n2\nENTITIES\n\
+0\nHATCH\n8\nAREAS\n100\nAcDbHatch\n10\n0.0\n20\n0.0\n30\n3.0\n2\nANSI31\n70\n0\n91\n4\n\
+92\n17\n93\n4\n72\n1\n10\n0.0\n20\n0.0\n11\n10.0\n21\n0.0\n\
+72\n2\n10\n10.0\n20\n5.0\n40\n5.0\n50\n90.0\n51\n270.0\n73\n0\n\
+72\n1\n10\n10.0\n20\n10.0\n11\n0.0\n21\n10.0\n72\n1\n10\n0.0\n20\n10.0\n11\n0.0\n21\n0.0\n97\n0\n\
+92\n2\n72\n1\n73\n1\n93\n3\n10\n4.0\n20\n5.0\n42\n1.0\n10\n6.0\n20\n5.0\n42\n1.0\n10\n4.0\n20\n5.0\n97\n0\n\
+92\n1\n93\n1\n72\n3\n10\n20.0\n20\n0.0\n11\n4.0\n21\n0.0\n40\n0.5\n50\n0.0\n51\n360.0\n73\n1\n97\n0\n\
+92\n1\n93\n2\n72\n4\n94\n2\n73\n0\n74\n0\n95\n6\n96\n3\n\
+40\n0.0\n40\n0.0\n40\n0.0\n40\n1.0\n40\n1.0\n40\n1.0\n\
+10\n0.0\n20\n20.0\n10\n5.0\n20\n30.0\n10\n10.0\n20\n20.0\n\
+72\n1\n10\n10.0\n20\n20.0\n11\n0.0\n21\n20.0\n97\n0\n\
+75\n0\n76\n1\n52\n0.0\n41\n1.0\n77\n0\n78\n1\n53\n45.0\n43\n0.0\n44\n0.0\n45\n-2.25\n46\n2.25\n79\n0\n\
+98\n1\n10\n2.0\n20\n2.0\n\
+0\nENDSEC\n0\nEOF\n";
+
+        let drawing = parse_dxf(content).unwrap();
+
+        assert!(drawing.unsupported.is_empty());
+        let entity = &drawing.layers["AREAS"].entities[0];
+        let Geometry::Hatch(hatch) = &entity.data else {
+            panic!("expected a hatch, got {:?}", entity.data);
+        };
+        assert_eq!(hatch.pattern_name, "ANSI31");
+        assert!(!hatch.solid);
+        assert_eq!(hatch.pattern_lines.len(), 1);
+        assert_eq!(hatch.pattern_lines[0].angle, 45.0);
+        assert_eq!(hatch.pattern_lines[0].offset, Point { x: -2.25, y: 2.25 });
+        assert_eq!(hatch.loops.len(), 4);
+
+        let square = &hatch.loops[0];
+        assert_eq!(square.flags, 17);
+        let corners: Vec<(f64, f64, f64)> = square
+            .vertices
+            .iter()
+            .map(|vertex| (vertex.x, vertex.y, vertex.z))
+            .collect();
+        assert_eq!(
+            corners,
+            [
+                (0.0, 0.0, 3.0),
+                (10.0, 0.0, 3.0),
+                (10.0, 10.0, 3.0),
+                (0.0, 10.0, 3.0)
+            ]
+        );
+        assert_eq!(square.bulges[0], 0.0);
+        assert!((square.bulges[1] + 1.0).abs() < 1e-9);
+
+        // The closing vertex of the polyline loop is dropped.
+        let hole = &hatch.loops[1];
+        assert_eq!(hole.flags, 0);
+        assert_eq!(hole.vertices.len(), 2);
+        assert_eq!(hole.bulges, [1.0, 1.0]);
+
+        let ellipse = &hatch.loops[2];
+        assert_eq!(ellipse.vertices.len(), 64);
+        assert!(ellipse.bulges.is_empty());
+        let quarter = ellipse.vertices[16];
+        assert!((quarter.x - 20.0).abs() < 1e-9 && (quarter.y - 2.0).abs() < 1e-9);
+
+        // The spline is flattened up to its end, where the line starts.
+        let parabola = &hatch.loops[3];
+        assert_eq!(parabola.vertices.len(), 25);
+        let top = parabola.vertices[12];
+        assert!((top.x - 5.0).abs() < 1e-9 && (top.y - 25.0).abs() < 1e-9);
+        assert_eq!(
+            (parabola.vertices[24].x, parabola.vertices[24].y),
+            (10.0, 20.0)
+        );
+
+        assert_eq!(
+            (entity.min_x, entity.min_y, entity.max_x, entity.max_y),
+            (0.0, -2.0, 24.0, 25.0)
+        );
+        assert_eq!((entity.min_z, entity.max_z), (3.0, 3.0));
+    }
+
+    #[test]
+    fn test_tags_entities_with_layouts() {
+        let content = "0\nSECTION\n2\nTABLES\n0\nTABLE\n2\nBLOCK_RECORD\n\
+0\nBLOCK_RECORD\n5\n1F\n2\n*Model_Space\n0\nBLOCK_RECORD\n5\n1B\n2\n*Paper_Space\n\
+0\nBLOCK_RECORD\n5\n1c\n2\n*Paper_Space0\n0\nENDTAB\n0\nENDSEC\n\
+0\nSECTION\n2\nBLOCKS\n\
+0\nBLOCK\n2\nDOOR\n0\nLINE\n8\n0\n0\nENDBLK\n\
+0\nBLOCK\n330\n1C\n2\n*Paper_Space0\n0\nCIRCLE\n330\n1C\n67\n1\n8\nTITLE\n40\n1.0\n0\nENDBLK\n\
+0\nENDSEC\n\
+0\nSECTION\n2\nENTITIES\n\
+0\nLINE\n330\n1F\n8\nPLAN\n11\n1.0\n\
+0\nLINE\n67\n1\n8\nTITLE\n11\n2.0\n\
+0\nENDSEC\n\
+0\nSECTION\n2\nOBJECTS\n\
+0\nLAYOUT\n5\n22\n330\n1A\n100\nAcDbPlotSettings\n1\nA3 setup\n100\nAcDbLayout\n\
+1\nSheet A\n71\n1\n10\n0.0\n20\n0.0\n11\n420.0\n21\n297.0\n330\n1B\n\
+0\nLAYOUT\n100\nAcDbLayout\n1\nSheet B\n71\n2\n330\n1C\n\
+0\nLAYOUT\n100\nAcDbLayout\n1\nModel\n71\n0\n330\n1F\n\
+0\nENDSEC\n0\nEOF\n";
+
+        let drawing = parse_dxf(content).unwrap();
+
+        let layouts: Vec<(&str, Option<&str>, i32)> = drawing
+            .layouts
+            .iter()
+            .map(|layout| {
+                (
+                    layout.name.as_str(),
+                    layout.block_name.as_deref(),
+                    layout.tab_order,
+                )
+            })
+            .collect();
+        assert_eq!(
+            layouts,
+            vec![
+                ("Model", Some("*Model_Space"), 0),
+                ("Sheet A", Some("*Paper_Space"), 1),
+                ("Sheet B", Some("*Paper_Space0"), 2),
+            ]
+        );
+        assert_eq!(drawing.layouts[1].max_x, 420.0);
+
+        assert_eq!(drawing.layers["PLAN"].entities[0].layout, "Model");
+        let title: Vec<&str> = drawing.layers["TITLE"]
+            .entities
+            .iter()
+            .map(|entity| entity.layout.as_str())
+            .collect();
+        assert_eq!(title, vec!["Sheet B", "Sheet A"]);
+        assert_eq!(drawing.layers["0"].entities.len(), 0);
+    }
+
+    #[test]
+    fn test_parse_viewport() {
+        let content = "0\nSECTION\n2\nTABLES\n0\nTABLE\n2\nLAYER\n\
+0\nLAYER\n5\n2f\n2\nFURNITURE\n0\nENDTAB\n0\nENDSEC\n\
+0\nSECTION\n2\nENTITIES\n\
+0\nVIEWPORT\n67\n1\n8\nVPORTS\n10\n150.0\n20\n100.0\n30\n0.0\n40\n200.0\n41\n100.0\n\
+68\n2\n69\n2\n12\n500.0\n22\n250.0\n45\n1000.0\n51\n30.0\n331\n2F\n331\n99\n\
+0\nENDSEC\n0\nEOF\n";
+
+        let drawing = parse_dxf(content).unwrap();
+
+        let viewport = &drawing.layers["VPORTS"].entities[0];
+        assert_eq!(viewport.entity_type(), "VIEWPORT");
+        assert_eq!(viewport.layout, PAPER_SPACE_BLOCK);
+        assert_eq!(
+            (
+                viewport.min_x,
+                viewport.min_y,
+                viewport.max_x,
+                viewport.max_y
+            ),
+            (50.0, 50.0, 250.0, 150.0)
+        );
+        assert_eq!(data(viewport)["view_center"]["x"], 500.0);
+        assert_eq!(data(viewport)["view_height"], 1000.0);
+        assert_eq!(data(viewport)["twist_angle"], 30.0);
+        assert_eq!(data(viewport)["frozen_layers"], json!(["FURNITURE", "99"]));
+    }
+
+    #[test]
+    fn test_resolves_dictionary_tree_and_groups() {
+        let content = "0\nSECTION\n2\nOBJECTS\n\
+0\nDICTIONARY\n5\nC\n330\n0\n3\nACAD_GROUP\n350\nD\n3\nACAD_LAYERSTATES\n350\n1E\n\
+0\nDICTIONARY\n5\nD\n102\n{ACAD_REACTORS\n330\nC\n102\n}\n330\nC\n3\nDOORS\n350\n2a\n\
+0\nGROUP\n5\n2A\n102\n{ACAD_REACTORS\n330\nD\n102\n}\n330\nD\n300\nAll doors\n70\n0\n71\n1\n\
+340\n1a0\n340\n1A1\n\
+0\nGROUP\n5\n2B\n330\nD\n340\n1A2\n\
+0\nDICTIONARY\n5\n1E\n330\nC\n3\nBEFORE_RENO\n350\n1F\n\
+0\nXRECORD\n5\n1F\n330\n1E\n1\nstate\n\
+0\nENDSEC\n0\nEOF\n";
+
+        let drawing = parse_dxf(content).unwrap();
+
+        let paths: Vec<(&str, &str)> = drawing
+            .named_objects
+            .iter()
+            .map(|object| (object.path.as_str(), object.object_type.as_str()))
+            .collect();
+        assert_eq!(
+            paths,
+            vec![
+                ("ACAD_GROUP", "DICTIONARY"),
+                ("ACAD_GROUP/DOORS", "GROUP"),
+                ("ACAD_LAYERSTATES", "DICTIONARY"),
+                ("ACAD_LAYERSTATES/BEFORE_RENO", "XRECORD"),
+            ]
+        );
+
+        assert_eq!(drawing.groups.len(), 1);
+        let group = &drawing.groups[0];
+        assert_eq!(group.name, "DOORS");
+        assert_eq!(group.handle, "2A");
+        assert_eq!(group.description.as_deref(), Some("All doors"));
+        assert_eq!(group.members, vec!["1A0", "1A1"]);
+        assert!(drawing.unsupported.is_empty());
+    }
+
+    #[test]
+    fn test_keeps_handles_and_xdata() {
+        let content = "0\nSECTION\n2\nENTITIES\n\
+0\nLINE\n5\n1a0\n330\n1F\n102\n{ACAD_REACTORS\n330\n99\n102\n}\n\
+102\n{ACAD_XDICTIONARY\n360\n1A1\n102\n}\n8\n0\n10\n0.0\n20\n0.0\n11\n1.0\n21\n1.0\n\
+1001\nACME\n1000\nASSET-7\n1010\n1.0\n1020\n2.0\n1030\n3.0\n1040\n0.5\n1001\nOTHER\n1071\n12\n\
+0\nCIRCLE\n5\n1A0\n8\n0\n10\n0.0\n20\n0.0\n40\n1.0\n\
+0\nENDSEC\n0\nEOF\n";
+
+        let drawing = parse_dxf(content).unwrap();
+        let entities = &drawing.layers["0"].entities;
+
+        let line = &entities[0];
+        assert_eq!(line.handle.as_deref(), Some("1A0"));
+        assert_eq!(line.owner_handle.as_deref(), Some("1F"));
+        assert_eq!(line.extension_dictionary.as_deref(), Some("1A1"));
+        assert_eq!(
+            line.xdata,
+            Some(json!({
+                "ACME": [
+                    {"code": 1000, "value": "ASSET-7"},
+                    {"code": 1010, "value": [1.0, 2.0, 3.0]},
+                    {"code": 1040, "value": 0.5}
+                ],
+                "OTHER": [{"code": 1071, "value": 12}]
+            }))
+        );
+
+        let circle = &entities[1];
+        assert_eq!(circle.handle, None);
+        assert_eq!(circle.xdata, None);
+        assert_eq!(drawing.diagnostics.len(), 1);
+        assert_eq!(drawing.diagnostics[0].group_code, Some(5));
+    }
+
+    #[test]
+    fn test_parses_images_and_underlays() {
+        let content = "0\nSECTION\n2\nENTITIES\n\
+0\nIMAGE\n5\n30\n8\nSITE\n10\n100.0\n20\n50.0\n30\n0.0\n11\n0.5\n21\n0.0\n31\n0.0\n\
+12\n0.0\n22\n0.5\n32\n0.0\n13\n400.0\n23\n200.0\n340\n3a\n70\n7\n280\n1\n281\n60\n\
+71\n1\n91\n2\n14\n-0.5\n24\n-0.5\n14\n199.5\n24\n99.5\n\
+0\nPDFUNDERLAY\n5\n31\n8\nSITE\n340\n3B\n10\n10.0\n20\n20.0\n41\n2.0\n42\n2.0\n50\n90.0\n\
+280\n2\n11\n0.0\n21\n0.0\n11\n10.0\n21\n5.0\n\
+0\nENDSEC\n0\nSECTION\n2\nOBJECTS\n\
+0\nIMAGEDEF\n5\n3A\n102\n{ACAD_REACTORS\n330\n3C\n102\n}\n\
+1\nC:\\Projects\\Site\\aerial.PNG\n10\n400.0\n20\n200.0\n\
+0\nPDFDEFINITION\n5\n3B\n1\n../refs/survey.pdf\n2\n1\n\
+0\nENDSEC\n0\nEOF\n";
+
+        let drawing = parse_dxf(content).unwrap();
+        let entities = &drawing.layers["SITE"].entities;
+
+        let image = &entities[0];
+        assert_eq!(image.entity_type(), "IMAGE");
+        assert_eq!(data(image)["definition"], "3A");
+        assert_eq!(data(image)["brightness"], 60);
+        assert_eq!(
+            (image.min_x, image.min_y, image.max_x, image.max_y),
+            (100.0, 50.0, 300.0, 150.0)
+        );
+        assert_eq!(
+            data(image)["clip_boundary"],
+            json!({
+                "type": "rectangular",
+                "vertices": [{"x": -0.5, "y": -0.5}, {"x": 199.5, "y": 99.5}]
+            })
+        );
+
+        let underlay = &entities[1];
+        assert_eq!(underlay.entity_type(), "PDFUNDERLAY");
+        assert_eq!(data(underlay)["definition"], "3B");
+        assert_eq!(data(underlay)["clip_boundary"]["type"], "polygonal");
+        assert!((underlay.min_x - 0.0).abs() < 1e-9);
+        assert!((underlay.max_y - 40.0).abs() < 1e-9);
+
+        assert_eq!(
+            drawing.references,
+            vec![
+                CreateExternalReferenceInput {
+                    handle: "3A".to_string(),
+                    kind: ReferenceKind::Image,
+                    path: "C:\\Projects\\Site\\aerial.PNG".to_string(),
+                    name: None,
+                    width_px: Some(400.0),
+                    height_px: Some(200.0),
+                },
+                CreateExternalReferenceInput {
+                    handle: "3B".to_string(),
+                    kind: ReferenceKind::Pdf,
+                    path: "../refs/survey.pdf".to_string(),
+                    name: Some("1".to_string()),
+                    width_px: None,
+                    height_px: None,
+                },
+            ]
+        );
+        assert!(drawing.unsupported.is_empty());
+    }
+
+    #[test]
+    fn test_applies_sort_table_draw_order() {
+        let content = "0\nSECTION\n2\nENTITIES\n\
+0\nLINE\n5\n10\n8\n0\n10\n0.0\n20\n0.0\n11\n10.0\n21\n5.0\n\
+0\nWIPEOUT\n5\n11\n8\n0\n10\n0.0\n20\n0.0\n30\n0.0\n11\n10.0\n21\n0.0\n31\n0.0\n\
+12\n0.0\n22\n5.0\n32\n0.0\n13\n1.0\n23\n1.0\n71\n2\n91\n5\n\
+14\n-0.5\n24\n-0.5\n14\n0.5\n24\n-0.5\n14\n0.5\n24\n0.5\n14\n-0.5\n24\n0.5\n14\n-0.5\n24\n-0.5\n\
+0\nLINE\n5\n12\n8\n0\n10\n0.0\n20\n5.0\n11\n10.0\n21\n0.0\n\
+0\nCIRCLE\n8\n0\n10\n5.0\n20\n2.5\n40\n1.0\n\
+0\nENDSEC\n0\nSECTION\n2\nOBJECTS\n\
+0\nSORTENTSTABLE\n5\n40\n102\n{ACAD_REACTORS\n330\n41\n102\n}\n330\n41\n\
+100\nAcDbSortentsTable\n330\n1F\n331\n11\n5\n9\n\
+0\nENDSEC\n0\nEOF\n";
+
+        let drawing = parse_dxf(content).unwrap();
+        let entities = &drawing.layers["0"].entities;
+
+        let order: Vec<(&str, u64)> = entities
+            .iter()
+            .map(|entity| (entity.entity_type(), entity.draw_order))
+            .collect();
+        assert_eq!(
+            order,
+            vec![("LINE", 1), ("WIPEOUT", 0), ("LINE", 2), ("CIRCLE", 3)]
+        );
+
+        let wipeout = &entities[1];
+        assert_eq!(
+            data(wipeout)["boundary"],
+            json!([
+                {"x": 0.0, "y": 5.0, "z": 0.0},
+                {"x": 10.0, "y": 5.0, "z": 0.0},
+                {"x": 10.0, "y": 0.0, "z": 0.0},
+                {"x": 0.0, "y": 0.0, "z": 0.0}
+            ])
+        );
+        assert_eq!(
+            (wipeout.min_x, wipeout.min_y, wipeout.max_x, wipeout.max_y),
+            (0.0, 0.0, 10.0, 5.0)
+        );
+        assert!(drawing.unsupported.is_empty());
+    }
+
+    #[test]
+    fn test_preserves_unknown_entities_and_objects() {
+        let content = "0\nSECTION\n2\nENTITIES\n\
+0\nLINE\n5\n10\n8\n0\n10\n0.0\n20\n0.0\n11\n1.0\n21\n1.0\n\
+0\nACAD_PROXY_ENTITY\n5\n11\n330\n1F\n100\nAcDbProxyEntity\n90\n498\n310\n0A0B\n1\n text \n\
+0\nINSERT\n5\n12\n8\n0\n66\n1\n2\nDOOR\n0\nATTRIB\n5\n13\n8\n0\n0\nSEQEND\n5\n14\n8\n0\n\
+0\nENDSEC\n0\nSECTION\n2\nOBJECTS\n\
+0\nDICTIONARY\n5\nC\n3\nACME\n350\n20\n\
+0\nACME_OBJECT\n5\n20\n102\n{ACAD_REACTORS\n330\nC\n102\n}\n330\nC\n1\nkeep me\n\
+0\nENDSEC\n0\nEOF\n";
+
+        let drawing = parse_dxf(content).unwrap();
+
+        let records: Vec<(RawSection, &str, Option<&str>, u64)> = drawing
+            .raw_records
+            .iter()
+            .map(|record| {
+                (
+                    record.section,
+                    record.record_type.as_str(),
+                    record.handle.as_deref(),
+                    record.position,
+                )
+            })
+            .collect();
+        assert_eq!(
+            records,
+            vec![
+                (RawSection::Entities, "ACAD_PROXY_ENTITY", Some("11"), 1),
+                (RawSection::Entities, "INSERT", Some("12"), 1),
+                (RawSection::Entities, "ATTRIB", Some("13"), 1),
+                (RawSection::Entities, "SEQEND", Some("14"), 1),
+                (RawSection::Objects, "ACME_OBJECT", Some("20"), 1),
+            ]
+        );
+
+        let proxy = &drawing.raw_records[0];
+        assert_eq!(proxy.owner_handle.as_deref(), Some("1F"));
+        assert_eq!(proxy.layout.as_deref(), Some(MODEL_LAYOUT));
+        assert_eq!(
+            proxy.content,
+            "0\nACAD_PROXY_ENTITY\n5\n11\n330\n1F\n100\nAcDbProxyEntity\n90\n498\n310\n0A0B\n1\n text \n"
+        );
+
+        let object = &drawing.raw_records[4];
+        assert_eq!(object.owner_handle.as_deref(), Some("C"));
+        assert_eq!(object.layout, None);
+        assert!(object
+            .content
+            .contains("102\n{ACAD_REACTORS\n330\nC\n102\n}\n"));
+        assert_eq!(drawing.layers["0"].entities.len(), 1);
+    }
+
+    #[test]
+    fn test_parses_acad_table_grid() {
+        let content = "0\nSECTION\n2\nENTITIES\n\
+0\nACAD_TABLE\n5\n50\n8\nSCHEDULE\n100\nAcDbBlockReference\n2\n*T1\n\
+10\n100.0\n20\n50.0\n30\n0.0\n100\nAcDbTable\n280\n0\n342\n51\n343\n52\n\
+11\n1.0\n21\n0.0\n31\n0.0\n90\n22\n91\n2\n92\n2\n\
+141\n10.0\n141\n8.0\n142\n40.0\n142\n25.0\n\
+171\n1\n172\n0\n173\n1\n175\n2\n176\n1\n140\n4.5\n1\nDOOR SCHEDULE\n\
+171\n1\n172\n0\n173\n1\n175\n1\n176\n1\n1\n\n\
+171\n1\n2\nDOOR-\n1\n01\n\
+171\n1\n301\nCELL_VALUE\n302\n900\n304\nACVALUE_END\n\
+0\nENDSEC\n0\nEOF\n";
+
+        let drawing = parse_dxf(content).unwrap();
+        let table = &drawing.layers["SCHEDULE"].entities[0];
+
+        assert_eq!(table.entity_type(), "TABLE");
+        assert_eq!(table.handle.as_deref(), Some("50"));
+        assert_eq!(data(table)["rows"], 2);
+        assert_eq!(data(table)["columns"], 2);
+        assert_eq!(data(table)["row_heights"], json!([10.0, 8.0]));
+        assert_eq!(data(table)["cells"][0][0]["text"], "DOOR SCHEDULE");
+        assert_eq!(data(table)["cells"][0][0]["column_span"], 2);
+        assert_eq!(data(table)["cells"][0][0]["text_height"], 4.5);
+        assert_eq!(data(table)["cells"][1][0]["text"], "DOOR-01");
+        assert_eq!(data(table)["cells"][1][1]["text"], "900");
+        // The insertion point is the top left corner.
+        assert_eq!(
+            (table.min_x, table.min_y, table.max_x, table.max_y),
+            (100.0, 32.0, 165.0, 50.0)
+        );
+        assert!(drawing.unsupported.is_empty());
+    }
+
+    #[test]
+    fn test_keeps_table_spans_within_the_grid() {
+        let content = "0\nSECTION\n2\nENTITIES\n\
+0\nACAD_TABLE\n5\n50\n8\nSCHEDULE\n100\nAcDbTable\n91\n2\n92\n3\n\
+171\n1\n175\n2\n176\n4000000000\n1\nA\n\
+171\n1\n1\nB\n\
+171\n1\n1\nC\n\
+171\n1\n175\n18446744073709551615\n176\n18446744073709551615\n1\nD\n\
+0\nENDSEC\n0\nEOF\n";
+
+        let drawing = parse_dxf(content).unwrap();
+        let table = data(&drawing.layers["SCHEDULE"].entities[0]);
+
+        assert_eq!(table["cells"][0][0]["row_span"], 2);
+        assert_eq!(table["cells"][0][0]["column_span"], 2);
+        assert_eq!(table["cells"][1][0]["row_span"], 1);
+        assert_eq!(table["cells"][1][0]["column_span"], 3);
+    }
+
+    #[test]
+    fn test_geometry_round_trips_with_version() {
+        let content = "0\nSECTION\n2\nENTITIES\n\
+0\nARC\n8\n0\n6\nDASHED\n39\n2.0\n10\n5.0\n20\n5.0\n40\n2.5\n50\n0.0\n51\n90.0\n\
+0\nTEXT\n8\n0\n10\n1.0\n20\n2.0\n40\n3.0\n1\nNOTE\n\
+0\nDIMENSION\n8\n0\n70\n32\n10\n0.0\n20\n0.0\n11\n5.0\n21\n1.0\n42\n10.0\n\
+0\nENDSEC\n0\nEOF\n";
+
+        let drawing = parse_dxf(content).unwrap();
+        let entities = &drawing.layers["0"].entities;
+        assert_eq!(entities.len(), 3);
+        for entity in entities {
+            let value = data(entity);
+            assert_eq!(value["type"], entity.entity_type());
+            assert_eq!(value["version"], GEOMETRY_VERSION);
+            let decoded: Geometry = serde_json::from_value(value).unwrap();
+            assert_eq!(decoded, entity.data);
+        }
+        assert_eq!(data(&entities[0])["thickness"], 2.0);
+        assert!(data(&entities[2]).get("point_13").is_none());
+
+        // Data from before versioning, as in the seed rows.
+        let legacy: Geometry = serde_json::from_value(json!({
+            "type": "TEXT",
+            "content": "Demo Annotation",
+            "position": {"x": 20, "y": 20},
+            "height": 12
+        }))
+        .unwrap();
+        let Geometry::Text(text) = legacy else {
+            panic!("expected TEXT, got {legacy:?}");
+        };
+        assert_eq!(text.text, "Demo Annotation");
+        assert_eq!(text.style, "STANDARD");
+        assert_eq!(text.width_factor, 1.0);
+
+        let newer = json!({"type": "LINE", "version": GEOMETRY_VERSION + 1});
+        assert!(serde_json::from_value::<Geometry>(newer).is_err());
+    }
+
+    /// A vendor pipe, drawn as a polyline along its axis.
+    struct PipeHandler;
+
+    impl EntityHandler for PipeHandler {
+        fn parse(&self, reader: &mut dyn EntityReader) -> Result<Option<ParsedEntity>> {
+            let mut layer = "0".to_string();
+            let mut ends = [[0.0; 2]; 2];
+            while let Some(group) = reader.next_field()? {
+                match group.code.as_str() {
+                    "8" => layer = group.value().to_string(),
+                    "10" => ends[0][0] = reader.number(&group, 0.0)?,
+                    "20" => ends[0][1] = reader.number(&group, 0.0)?,
+                    "11" => ends[1][0] = reader.number(&group, 0.0)?,
+                    "21" => ends[1][1] = reader.number(&group, 0.0)?,
+                    _ => {}
+                }
+            }
+            let [[x1, y1], [x2, y2]] = ends;
+            Ok(Some(ParsedEntity {
+                layer,
+                geometry: Geometry::Polyline(geometry::Polyline {
+                    vertices: vec![Point3::new(x1, y1, 0.0), Point3::new(x2, y2, 0.0)],
+                    ..Default::default()
+                }),
+                bbox: BoundingBox::new(x1.min(x2), y1.min(y2), x1.max(x2), y1.max(y2)),
+                elevation: ElevationRange::from_z(0.0),
+            }))
+        }
+    }
+
+    #[test]
+    fn test_registered_handler_reads_vendor_entities() {
+        let content = "0\nSECTION\n2\nENTITIES\n\
+0\nACME_PIPE\n5\n10\n8\nPIPES\n10\n0.0\n20\n0.0\n11\n4.0\n21\n3.0\n1001\nACME\n1000\nDN50\n\
+0\nACME_PIPE\n5\n11\n8\nPIPES\n10\nbad\n\
+0\nACME_VALVE\n5\n12\n8\nPIPES\n\
+0\nENDSEC\n0\nEOF\n";
+
+        let mut handlers = EntityHandlerRegistry::default();
+        handlers.register("ACME_PIPE", PipeHandler);
+        let drawing =
+            DxfParser::with_handlers(content.as_bytes(), ParseMode::Lenient, Arc::new(handlers))
+                .unwrap()
+                .collect_drawing()
+                .unwrap();
+
+        let pipes = &drawing.layers["PIPES"].entities;
+        assert_eq!(pipes.len(), 1);
+        assert_eq!(pipes[0].entity_type(), "POLYLINE");
+        assert_eq!(pipes[0].handle.as_deref(), Some("10"));
+        assert_eq!(data(&pipes[0])["vertices"][1]["y"], 3.0);
+        assert_eq!(pipes[0].xdata.as_ref().unwrap()["ACME"][0]["value"], "DN50");
+        assert_eq!((pipes[0].max_x, pipes[0].max_y), (4.0, 3.0));
+
+        // A malformed pipe is skipped like any other entity.
+        assert!(drawing
+            .diagnostics
+            .iter()
+            .any(|d| d.entity_handle.as_deref() == Some("11")));
+
+        // Entities without a handler are still unsupported and kept raw.
+        assert_eq!(drawing.raw_records.len(), 1);
+        assert_eq!(drawing.raw_records[0].record_type, "ACME_VALVE");
+        assert_eq!(drawing.unsupported.len(), 1);
+    }
+}
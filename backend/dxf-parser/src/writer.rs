@@ -0,0 +1,1780 @@
+use crate::groups::raw_groups;
+use crate::models::geometry::{self, ClipBoundary, ClipKind, EntityProperties};
+use crate::models::{
+    CreateEntityInput, CreateRawRecordInput, Geometry, Point3, RawSection, ReferenceKind,
+    MODEL_LAYOUT,
+};
+use crate::parser::{
+    is_paper_space_block, ParsedDrawing, DIMSTYLE_VARIABLES, MODEL_SPACE_BLOCK, PAPER_SPACE_BLOCK,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+use std::fmt::{Display, Write as _};
+
+/// Record types an R12 reader knows. Raw records of other types are left
+/// out of R12 files.
+const R12_RECORDS: &[&str] = &[
+    "3DFACE",
+    "ARC",
+    "ATTDEF",
+    "ATTRIB",
+    "BLOCK",
+    "CIRCLE",
+    "DIMENSION",
+    "ENDBLK",
+    "INSERT",
+    "LINE",
+    "POINT",
+    "POLYLINE",
+    "SEQEND",
+    "SHAPE",
+    "SOLID",
+    "TEXT",
+    "TRACE",
+    "VERTEX",
+    "VIEWPORT",
+];
+
+/// Longest string AutoCAD reads from one group; longer MTEXT and table
+/// cell text is split over several.
+const CHUNK_LENGTH: usize = 250;
+
+/// The DXF version a drawing is written as.
+///
+/// R12 files have no handles, no OBJECTS section and no entity types
+/// introduced with R13 (MTEXT, images, wipeouts, tables and underlays);
+/// those are left out. R2018 files keep everything the parser stores.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DxfVersion {
+    R12,
+    #[default]
+    R2018,
+}
+
+impl DxfVersion {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DxfVersion::R12 => "r12",
+            DxfVersion::R2018 => "r2018",
+        }
+    }
+
+    /// The `$ACADVER` header value.
+    pub fn acad_version(&self) -> &'static str {
+        match self {
+            DxfVersion::R12 => "AC1009",
+            DxfVersion::R2018 => "AC1032",
+        }
+    }
+
+    fn supports(&self, data: &Geometry) -> bool {
+        *self == DxfVersion::R2018
+            || !matches!(
+                data,
+                Geometry::MText(_)
+                    | Geometry::Image(_)
+                    | Geometry::Wipeout(_)
+                    | Geometry::Hatch(_)
+                    | Geometry::Table(_)
+                    | Geometry::PdfUnderlay(_)
+                    | Geometry::DwfUnderlay(_)
+                    | Geometry::DgnUnderlay(_)
+            )
+    }
+}
+
+/// Writes a drawing as an ASCII DXF file.
+///
+/// Entities are written in draw order, paper space layouts other than the
+/// active one inside their `*Paper_Space…` blocks. Raw records go back at
+/// their recorded positions, block definitions after the layout blocks. Handles are kept; records the parser does not
+/// store, such as table entries and dictionaries, get new handles above
+/// the highest one in use.
+pub fn write_dxf(drawing: &ParsedDrawing, version: DxfVersion) -> String {
+    DxfWriter::new(drawing, version).write()
+}
+
+/// An entity, or a raw entity record, with the layer it is on.
+#[derive(Debug, Clone, Copy)]
+enum Item<'a> {
+    Entity(&'a str, &'a CreateEntityInput),
+    Raw(&'a CreateRawRecordInput),
+}
+
+impl<'a> Item<'a> {
+    fn layout(&self) -> &'a str {
+        match self {
+            Item::Entity(_, entity) => &entity.layout,
+            Item::Raw(record) => record.layout.as_deref().unwrap_or(MODEL_LAYOUT),
+        }
+    }
+
+    fn handle(&self) -> Option<&'a str> {
+        match self {
+            Item::Entity(_, entity) => entity.handle.as_deref(),
+            Item::Raw(record) => record.handle.as_deref(),
+        }
+    }
+
+    fn owner_handle(&self) -> Option<&'a str> {
+        match self {
+            Item::Entity(_, entity) => entity.owner_handle.as_deref(),
+            Item::Raw(record) => record.owner_handle.as_deref(),
+        }
+    }
+}
+
+/// A layout block with its BLOCK_RECORD handle and entities.
+#[derive(Debug)]
+struct Block<'a> {
+    name: String,
+    record: String,
+    items: Vec<Item<'a>>,
+}
+
+impl Block<'_> {
+    /// Model space and the active paper space layout are written to the
+    /// ENTITIES section rather than inside their blocks.
+    fn in_entities_section(&self) -> bool {
+        self.name.eq_ignore_ascii_case(MODEL_SPACE_BLOCK) || self.is_active_paper_space()
+    }
+
+    fn is_active_paper_space(&self) -> bool {
+        self.name.eq_ignore_ascii_case(PAPER_SPACE_BLOCK)
+    }
+}
+
+/// A block definition kept as a raw record, with its BLOCK_RECORD handle.
+#[derive(Debug)]
+struct UserBlock<'a> {
+    name: &'a str,
+    record: String,
+    raw: &'a CreateRawRecordInput,
+}
+
+/// A DICTIONARY object rebuilt from the named object paths.
+#[derive(Debug)]
+struct Dictionary<'a> {
+    handle: String,
+    owner: String,
+    entries: Vec<(&'a str, &'a str)>,
+}
+
+struct DxfWriter<'a> {
+    drawing: &'a ParsedDrawing,
+    version: DxfVersion,
+    out: String,
+    next_handle: u64,
+    layer_handles: HashMap<&'a str, String>,
+}
+
+impl<'a> DxfWriter<'a> {
+    fn new(drawing: &'a ParsedDrawing, version: DxfVersion) -> Self {
+        Self {
+            drawing,
+            version,
+            out: String::new(),
+            next_handle: max_handle(drawing) + 1,
+            layer_handles: HashMap::new(),
+        }
+    }
+
+    fn write(mut self) -> String {
+        let blocks = self.layout_blocks();
+        let user_blocks = self.user_blocks(&blocks);
+
+        self.tables(&blocks, &user_blocks);
+        self.blocks(&blocks, &user_blocks);
+        self.entities(&blocks);
+        if self.version == DxfVersion::R2018 {
+            self.objects(&blocks);
+        }
+        self.group(0, "EOF");
+
+        // $HANDSEED is only known once every record has its handle.
+        let body = std::mem::take(&mut self.out);
+        self.header();
+        self.out.push_str(&body);
+        self.out
+    }
+
+    fn r2018(&self) -> bool {
+        self.version == DxfVersion::R2018
+    }
+
+    fn group(&mut self, code: impl Display, value: impl Display) {
+        let _ = writeln!(self.out, "{:>3}\n{}", code, value);
+    }
+
+    fn point(&mut self, code: i32, point: &Point3) {
+        self.group(code, point.x);
+        self.group(code + 10, point.y);
+        self.group(code + 20, point.z);
+    }
+
+    fn point2(&mut self, code: i32, x: f64, y: f64) {
+        self.group(code, x);
+        self.group(code + 10, y);
+    }
+
+    /// Subclass markers only exist from R13 on.
+    fn subclass(&mut self, name: &str) {
+        if self.r2018() {
+            self.group(100, name);
+        }
+    }
+
+    fn new_handle(&mut self) -> String {
+        let handle = format!("{:X}", self.next_handle);
+        self.next_handle += 1;
+        handle
+    }
+
+    fn begin_section(&mut self, name: &str) {
+        self.group(0, "SECTION");
+        self.group(2, name);
+    }
+
+    fn end_section(&mut self) {
+        self.group(0, "ENDSEC");
+    }
+
+    fn header(&mut self) {
+        self.begin_section("HEADER");
+        self.group(9, "$ACADVER");
+        self.group(1, self.version.acad_version());
+        if self.r2018() {
+            self.group(9, "$HANDSEED");
+            self.group(5, format!("{:X}", self.next_handle));
+        }
+        if let Some(scale) = self.drawing.linetype_scale {
+            self.group(9, "$LTSCALE");
+            self.group(40, scale);
+        }
+        self.end_section();
+    }
+
+    /// Groups the entities and raw entity records by layout block, each in
+    /// draw order. A raw record goes before the entity whose draw order
+    /// equals its position.
+    fn layout_blocks(&mut self) -> Vec<Block<'a>> {
+        let drawing = self.drawing;
+        let mut layers: Vec<_> = drawing.layers.values().collect();
+        layers.sort_by(|a, b| a.input.name.cmp(&b.input.name));
+
+        let mut items: Vec<((u64, u8), Item<'a>)> = layers
+            .into_iter()
+            .flat_map(|layer| {
+                layer.entities.iter().map(|entity| {
+                    (
+                        (entity.draw_order, 1),
+                        Item::Entity(&layer.input.name, entity),
+                    )
+                })
+            })
+            .filter(|(_, item)| match item {
+                Item::Entity(_, entity) => self.version.supports(&entity.data),
+                Item::Raw(_) => true,
+            })
+            .collect();
+        let r2018 = self.r2018();
+        items.extend(
+            drawing
+                .raw_records
+                .iter()
+                .filter(|record| record.section == RawSection::Entities)
+                .filter(|record| r2018 || R12_RECORDS.contains(&record.record_type.as_str()))
+                .map(|record| ((record.position, 0), Item::Raw(record))),
+        );
+        items.sort_by_key(|(key, _)| *key);
+
+        let mut blocks: Vec<Block<'a>> = [MODEL_SPACE_BLOCK, PAPER_SPACE_BLOCK]
+            .into_iter()
+            .map(|name| Block {
+                name: name.to_string(),
+                record: String::new(),
+                items: Vec::new(),
+            })
+            .collect();
+        let layout_blocks = drawing
+            .layouts
+            .iter()
+            .filter_map(|layout| layout.block_name.clone());
+        for (_, item) in items {
+            let name = self.block_name(item.layout());
+            block_entry(&mut blocks, &name).items.push(item);
+        }
+        for name in layout_blocks {
+            block_entry(&mut blocks, &name);
+        }
+
+        // Block records keep the handle their entities name as owner.
+        let item_handles: HashSet<&'a str> = blocks
+            .iter()
+            .flat_map(|block| block.items.iter().filter_map(Item::handle))
+            .collect();
+        let mut records: HashSet<String> = HashSet::new();
+        for block in &mut blocks {
+            let owner = block
+                .items
+                .iter()
+                .filter_map(Item::owner_handle)
+                .find(|owner| !item_handles.contains(owner) && !records.contains(*owner))
+                .map(str::to_string);
+            block.record = owner.unwrap_or_else(|| self.new_handle());
+            records.insert(block.record.clone());
+        }
+        blocks
+    }
+
+    /// The block definitions kept as raw records, in file order. Each keeps
+    /// the BLOCK_RECORD handle its BLOCK names as owner, unless a layout
+    /// block took it.
+    fn user_blocks(&mut self, blocks: &[Block<'a>]) -> Vec<UserBlock<'a>> {
+        let mut records: Vec<&'a CreateRawRecordInput> = self
+            .drawing
+            .raw_records
+            .iter()
+            .filter(|record| record.section == RawSection::Blocks)
+            .collect();
+        records.sort_by_key(|record| record.position);
+
+        let mut handles: HashSet<String> =
+            blocks.iter().map(|block| block.record.clone()).collect();
+        records
+            .into_iter()
+            .map(|raw| {
+                let name = raw_groups(&raw.content)
+                    .find(|(code, _)| *code == "2")
+                    .map_or("", |(_, name)| name.trim());
+                let record = match &raw.owner_handle {
+                    Some(owner) if !handles.contains(owner) => owner.clone(),
+                    _ => self.new_handle(),
+                };
+                handles.insert(record.clone());
+                UserBlock { name, record, raw }
+            })
+            .collect()
+    }
+
+    /// The block holding a layout's entities.
+    fn block_name(&self, layout: &str) -> String {
+        self.drawing
+            .layouts
+            .iter()
+            .find(|candidate| candidate.name == layout)
+            .and_then(|layout| layout.block_name.clone())
+            .unwrap_or_else(|| {
+                if layout == MODEL_LAYOUT {
+                    MODEL_SPACE_BLOCK.to_string()
+                } else {
+                    layout.to_string()
+                }
+            })
+    }
+
+    fn begin_table(&mut self, name: &str, count: usize) -> String {
+        self.group(0, "TABLE");
+        self.group(2, name);
+        let handle = self.new_handle();
+        if self.r2018() {
+            self.group(5, &handle);
+            self.group(330, 0);
+            self.group(100, "AcDbSymbolTable");
+        }
+        self.group(70, count);
+        handle
+    }
+
+    fn end_table(&mut self) {
+        self.group(0, "ENDTAB");
+    }
+
+    /// Starts a table record: its handle (`5`, or `105` for DIMSTYLE) and
+    /// owning table, then the subclass markers.
+    fn table_record(&mut self, kind: &str, handle: &str, table: &str, subclass: &str) {
+        self.group(0, kind);
+        if self.r2018() {
+            self.group(if kind == "DIMSTYLE" { 105 } else { 5 }, handle);
+            self.group(330, table);
+            self.group(100, "AcDbSymbolTableRecord");
+            self.group(100, subclass);
+        }
+    }
+
+    fn tables(&mut self, blocks: &[Block<'a>], user_blocks: &[UserBlock<'a>]) {
+        let drawing = self.drawing;
+        self.begin_section("TABLES");
+
+        let table = self.begin_table("LTYPE", drawing.linetypes.len());
+        for linetype in &drawing.linetypes {
+            let handle = self.new_handle();
+            self.table_record("LTYPE", &handle, &table, "AcDbLinetypeTableRecord");
+            self.group(2, &linetype.name);
+            self.group(70, 0);
+            self.group(3, linetype.description.as_deref().unwrap_or(""));
+            self.group(72, 65);
+            self.group(73, linetype.elements.len());
+            self.group(40, linetype.pattern_length);
+            for element in &linetype.elements {
+                self.group(49, element.length);
+                if !self.r2018() {
+                    continue;
+                }
+                let Some(embedded) = &element.embedded else {
+                    self.group(74, 0);
+                    continue;
+                };
+                let kind = if embedded.kind == "text" { 2 } else { 4 };
+                self.group(74, kind | i32::from(embedded.absolute_rotation));
+                if let Some(shape_number) = embedded.shape_number {
+                    self.group(75, shape_number);
+                }
+                if let Some(style) = &embedded.style_handle {
+                    self.group(340, style);
+                }
+                self.group(46, embedded.scale);
+                self.group(50, embedded.rotation);
+                self.group(44, embedded.offset_x);
+                self.group(45, embedded.offset_y);
+                if let Some(text) = &embedded.text {
+                    self.group(9, text);
+                }
+            }
+        }
+        self.end_table();
+
+        let mut layers: Vec<_> = drawing.layers.values().map(|layer| &layer.input).collect();
+        layers.sort_by(|a, b| a.name.cmp(&b.name));
+        let table = self.begin_table("LAYER", layers.len());
+        for layer in layers {
+            let handle = self.new_handle();
+            self.table_record("LAYER", &handle, &table, "AcDbLayerTableRecord");
+            self.group(2, &layer.name);
+            self.group(70, if layer.is_locked { 4 } else { 0 });
+            if let Some(color) = &layer.color {
+                self.group(62, color);
+            }
+            if let Some(line_type) = &layer.line_type {
+                self.group(6, line_type);
+            }
+            if let Some(line_weight) = layer.line_weight.as_ref().filter(|_| self.r2018()) {
+                self.group(370, line_weight);
+            }
+            self.layer_handles.insert(&layer.name, handle);
+        }
+        self.end_table();
+
+        // A dimension style names its text style by handle, so the style
+        // keeps the handle the dimension style refers to.
+        let mut style_handles: HashMap<&str, &str> = HashMap::new();
+        for dim_style in &drawing.dim_styles {
+            if let (Some(style), Some(Value::String(handle))) = (
+                dim_style.text_style.as_deref(),
+                dim_style.properties.get("DIMTXSTY"),
+            ) {
+                style_handles.entry(style).or_insert(handle.as_str());
+            }
+        }
+        let table = self.begin_table("STYLE", drawing.text_styles.len());
+        for style in &drawing.text_styles {
+            let handle = match style_handles.get(style.name.as_str()) {
+                Some(handle) => handle.to_string(),
+                None => self.new_handle(),
+            };
+            self.table_record("STYLE", &handle, &table, "AcDbTextStyleTableRecord");
+            self.group(2, &style.name);
+            self.group(70, style.flags);
+            self.group(40, style.fixed_height);
+            self.group(41, style.width_factor);
+            self.group(50, style.oblique_angle);
+            self.group(71, 0);
+            self.group(
+                42,
+                if style.fixed_height > 0.0 {
+                    style.fixed_height
+                } else {
+                    2.5
+                },
+            );
+            self.group(3, style.font_file.as_deref().unwrap_or(""));
+            self.group(4, style.bigfont_file.as_deref().unwrap_or(""));
+        }
+        self.end_table();
+
+        let mut applications: Vec<&str> = drawing
+            .layers
+            .values()
+            .flat_map(|layer| &layer.entities)
+            .filter_map(|entity| entity.xdata.as_ref()?.as_object())
+            .flat_map(|xdata| xdata.keys().map(String::as_str))
+            .chain(["ACAD"])
+            .collect();
+        applications.sort_unstable();
+        applications.dedup();
+        let table = self.begin_table("APPID", applications.len());
+        for application in applications {
+            let handle = self.new_handle();
+            self.table_record("APPID", &handle, &table, "AcDbRegAppTableRecord");
+            self.group(2, application);
+            self.group(70, 0);
+        }
+        self.end_table();
+
+        let table = self.begin_table("DIMSTYLE", drawing.dim_styles.len());
+        for dim_style in &drawing.dim_styles {
+            let handle = self.new_handle();
+            self.table_record("DIMSTYLE", &handle, &table, "AcDbDimStyleTableRecord");
+            self.group(2, &dim_style.name);
+            self.group(70, 0);
+            for (code, variable) in DIMSTYLE_VARIABLES {
+                let Some(value) = dim_style.properties.get(*variable) else {
+                    continue;
+                };
+                // DIMDEC and DIMTXSTY were added in R13.
+                if !self.r2018() && code.parse::<i32>().is_ok_and(|code| code >= 270) {
+                    continue;
+                }
+                match value {
+                    Value::String(value) => self.group(code, value),
+                    value => self.group(code, value),
+                }
+            }
+        }
+        self.end_table();
+
+        if self.r2018() {
+            let table = self.begin_table("BLOCK_RECORD", blocks.len() + user_blocks.len());
+            let records = blocks
+                .iter()
+                .map(|block| (block.record.as_str(), block.name.as_str()))
+                .chain(
+                    user_blocks
+                        .iter()
+                        .map(|block| (block.record.as_str(), block.name)),
+                );
+            for (record, name) in records {
+                self.table_record("BLOCK_RECORD", record, &table, "AcDbBlockTableRecord");
+                self.group(2, name);
+            }
+            self.end_table();
+        }
+
+        self.end_section();
+    }
+
+    /// Writes the layout blocks, then the other block definitions; paper
+    /// space layouts other than the active one carry their entities.
+    fn blocks(&mut self, blocks: &[Block<'a>], user_blocks: &[UserBlock<'a>]) {
+        self.begin_section("BLOCKS");
+        // R12 has no model space block and a single paper space.
+        let r2018 = self.r2018();
+        for block in blocks
+            .iter()
+            .filter(|block| r2018 || !block.in_entities_section())
+        {
+            let handle = self.new_handle();
+            self.group(0, "BLOCK");
+            if self.r2018() {
+                self.group(5, &handle);
+                self.group(330, &block.record);
+                self.group(100, "AcDbEntity");
+            }
+            self.group(8, 0);
+            self.subclass("AcDbBlockBegin");
+            self.group(2, &block.name);
+            self.group(70, 0);
+            self.point(10, &Point3::default());
+            self.group(3, &block.name);
+            self.group(1, "");
+
+            if !block.in_entities_section() {
+                let paper_space = is_paper_space_block(&block.name);
+                let items: Vec<(Item<'a>, bool)> = block
+                    .items
+                    .iter()
+                    .map(|item| (*item, paper_space))
+                    .collect();
+                self.items(&items);
+            }
+
+            let handle = self.new_handle();
+            self.group(0, "ENDBLK");
+            if self.r2018() {
+                self.group(5, &handle);
+                self.group(330, &block.record);
+                self.group(100, "AcDbEntity");
+            }
+            self.group(8, 0);
+            self.subclass("AcDbBlockEnd");
+        }
+        for block in user_blocks {
+            self.raw(block.raw);
+        }
+        self.end_section();
+    }
+
+    fn entities(&mut self, blocks: &[Block<'a>]) {
+        self.begin_section("ENTITIES");
+        let mut items: Vec<(Item<'a>, bool)> = Vec::new();
+        for block in blocks.iter().filter(|block| block.in_entities_section()) {
+            let paper_space = block.is_active_paper_space();
+            items.extend(block.items.iter().map(|item| (*item, paper_space)));
+        }
+        // Model and paper space entities interleave in draw order.
+        items.sort_by_key(|(item, _)| match item {
+            Item::Entity(_, entity) => (entity.draw_order, 1),
+            Item::Raw(record) => (record.position, 0),
+        });
+        self.items(&items);
+        self.end_section();
+    }
+
+    /// Writes entities and raw records in order, each with whether it is
+    /// in paper space. A POLYLINE is closed by the raw SEQEND that follows
+    /// it, or by a new one; a flat polyline without one was read from an
+    /// LWPOLYLINE (or made up) and is written as one where the version has
+    /// them.
+    fn items(&mut self, items: &[(Item<'a>, bool)]) {
+        let mut items = items.iter().peekable();
+        while let Some((item, paper_space)) = items.next() {
+            match item {
+                Item::Raw(record) => self.raw(record),
+                Item::Entity(layer, entity) => {
+                    let Geometry::Polyline(polyline) = &entity.data else {
+                        self.entity(layer, entity, *paper_space, false);
+                        continue;
+                    };
+                    match items.peek() {
+                        Some((Item::Raw(record), _)) if record.record_type == "SEQEND" => {
+                            self.entity(layer, entity, *paper_space, false);
+                            self.raw(record);
+                            items.next();
+                        }
+                        _ if self.r2018() && is_flat(polyline) => {
+                            self.entity(layer, entity, *paper_space, true);
+                        }
+                        _ => {
+                            self.entity(layer, entity, *paper_space, false);
+                            self.seqend(layer, entity.handle.as_deref());
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// Writes a raw record as it was read. R12 files only get the records
+    /// and group codes an R12 reader knows: no handles, owners, subclass
+    /// markers, application groups or XDATA.
+    fn raw(&mut self, record: &CreateRawRecordInput) {
+        if self.r2018() {
+            self.out.push_str(&record.content);
+            return;
+        }
+
+        let mut known = false;
+        let mut app_group = false;
+        for (code, value) in raw_groups(&record.content) {
+            let Ok(code) = code.parse::<i32>() else {
+                continue;
+            };
+            match code {
+                0 => known = R12_RECORDS.contains(&value.trim()),
+                102 => app_group = value.trim_start().starts_with('{'),
+                _ => {}
+            }
+            if known && !app_group && matches!(code, 0..=4 | 6..=89 | 210..=239) {
+                self.group(code, value);
+            }
+        }
+    }
+
+    /// The groups every entity starts with, up to its layer and linetype.
+    fn entity_header(
+        &mut self,
+        kind: &str,
+        layer: &str,
+        entity: Option<&CreateEntityInput>,
+        owner: Option<&str>,
+        paper_space: bool,
+    ) {
+        self.group(0, kind);
+        if self.r2018() {
+            let handle = match entity {
+                Some(entity) => entity.handle.clone(),
+                None => Some(self.new_handle()),
+            };
+            if let Some(handle) = handle {
+                self.group(5, handle);
+            }
+            if let Some(dictionary) = entity.and_then(|entity| entity.extension_dictionary.as_ref())
+            {
+                self.group(102, "{ACAD_XDICTIONARY");
+                self.group(360, dictionary);
+                self.group(102, "}");
+            }
+            if let Some(owner) = owner {
+                self.group(330, owner);
+            }
+            self.group(100, "AcDbEntity");
+        }
+        if paper_space {
+            self.group(67, 1);
+        }
+        self.group(8, layer);
+    }
+
+    /// Writes an entity; `lightweight` writes a polyline as an LWPOLYLINE.
+    fn entity(
+        &mut self,
+        layer: &str,
+        entity: &CreateEntityInput,
+        paper_space: bool,
+        lightweight: bool,
+    ) {
+        let data = &entity.data;
+        let kind = match data {
+            Geometry::Polyline(_) if lightweight => "LWPOLYLINE",
+            Geometry::Table(_) => "ACAD_TABLE",
+            data => data.entity_type(),
+        };
+        self.entity_header(
+            kind,
+            layer,
+            Some(entity),
+            entity.owner_handle.as_deref(),
+            paper_space,
+        );
+        if let Some(properties) = properties(data) {
+            if let Some(line_type) = &properties.line_type {
+                self.group(6, line_type);
+            }
+            if let Some(scale) = properties.line_type_scale.filter(|_| self.r2018()) {
+                self.group(48, scale);
+            }
+            if let Some(thickness) = properties.thickness {
+                self.group(39, thickness);
+            }
+            if let Some(color) = properties.color {
+                self.group(62, color);
+            }
+            if self.r2018() {
+                if let Some(line_weight) = properties.line_weight {
+                    self.group(370, line_weight);
+                }
+                if let Some(true_color) = properties.true_color {
+                    self.group(420, true_color);
+                }
+            }
+        }
+
+        match data {
+            Geometry::Line(line) => {
+                self.subclass("AcDbLine");
+                self.point(10, &line.start);
+                self.point(11, &line.end);
+            }
+            Geometry::Polyline(polyline) if lightweight => self.lwpolyline(polyline),
+            Geometry::Polyline(polyline) => self.polyline(layer, entity, polyline, paper_space),
+            Geometry::Arc(arc) => {
+                self.subclass("AcDbCircle");
+                self.point(10, &arc.center);
+                self.group(40, arc.radius);
+                self.subclass("AcDbArc");
+                self.group(50, arc.start_angle);
+                self.group(51, arc.end_angle);
+            }
+            Geometry::Circle(circle) => {
+                self.subclass("AcDbCircle");
+                self.point(10, &circle.center);
+                self.group(40, circle.radius);
+            }
+            Geometry::Text(text) => {
+                self.subclass("AcDbText");
+                self.point(10, &text.position);
+                self.group(40, text.height);
+                self.group(1, &text.text);
+                self.group(50, text.rotation);
+                self.group(41, text.width_factor);
+                self.group(51, text.oblique_angle);
+                self.group(7, &text.style);
+                self.subclass("AcDbText");
+            }
+            Geometry::MText(text) => {
+                self.subclass("AcDbMText");
+                self.point(10, &text.position);
+                self.group(40, text.height);
+                self.group(41, text.width);
+                self.group(71, text.attachment_point);
+                self.group(72, 1);
+                self.chunked_text(&text.text, 3);
+                self.group(7, &text.style);
+                self.group(50, text.rotation);
+            }
+            Geometry::Dimension(dimension) => {
+                self.subclass("AcDbDimension");
+                if let Some(block) = &dimension.block_name {
+                    self.group(2, block);
+                }
+                if let Some(point) = &dimension.definition_point {
+                    self.point(10, point);
+                }
+                if let Some(point) = &dimension.text_position {
+                    self.point(11, point);
+                }
+                self.group(70, dimension.dimension_type);
+                if let Some(text) = &dimension.text {
+                    self.group(1, text);
+                }
+                if let Some(measurement) = dimension.measurement.filter(|_| self.r2018()) {
+                    self.group(42, measurement);
+                }
+                self.group(3, &dimension.dim_style);
+                self.subclass("AcDbAlignedDimension");
+                for (code, point) in [
+                    (13, &dimension.point_13),
+                    (14, &dimension.point_14),
+                    (15, &dimension.point_15),
+                    (16, &dimension.point_16),
+                ] {
+                    if let Some(point) = point {
+                        self.point(code, point);
+                    }
+                }
+            }
+            Geometry::Viewport(viewport) => {
+                self.subclass("AcDbViewport");
+                self.point(10, &viewport.center);
+                self.group(40, viewport.width);
+                self.group(41, viewport.height);
+                self.group(68, viewport.status);
+                self.group(69, viewport.id);
+                self.point2(12, viewport.view_center.x, viewport.view_center.y);
+                self.point(16, &viewport.view_direction);
+                self.point(17, &viewport.view_target);
+                self.group(45, viewport.view_height);
+                self.group(51, viewport.twist_angle);
+                if self.r2018() {
+                    for layer in &viewport.frozen_layers {
+                        // Layers that did not resolve keep their handle.
+                        let handle = self
+                            .layer_handles
+                            .get(layer.as_str())
+                            .cloned()
+                            .unwrap_or_else(|| layer.clone());
+                        self.group(331, handle);
+                    }
+                }
+            }
+            Geometry::Image(image) => {
+                self.subclass("AcDbRasterImage");
+                self.group(90, 0);
+                self.point(10, &image.insertion);
+                self.point(11, &image.u_vector);
+                self.point(12, &image.v_vector);
+                self.point2(13, image.width_px, image.height_px);
+                if let Some(definition) = &image.definition {
+                    self.group(340, definition);
+                }
+                self.group(70, image.display_flags);
+                self.group(280, i32::from(image.clip_boundary.is_some()));
+                self.group(281, image.brightness);
+                self.group(282, image.contrast);
+                self.group(283, image.fade);
+                if let Some(clip) = &image.clip_boundary {
+                    self.clip_boundary(clip, 14);
+                }
+            }
+            Geometry::Wipeout(wipeout) => self.wipeout(wipeout),
+            Geometry::Hatch(hatch) => self.hatch(hatch),
+            Geometry::Table(table) => self.table(table),
+            Geometry::PdfUnderlay(underlay)
+            | Geometry::DwfUnderlay(underlay)
+            | Geometry::DgnUnderlay(underlay) => {
+                self.subclass("AcDbUnderlayReference");
+                if let Some(definition) = &underlay.definition {
+                    self.group(340, definition);
+                }
+                self.point(10, &underlay.insertion);
+                self.group(41, underlay.scale.x);
+                self.group(42, underlay.scale.y);
+                self.group(43, underlay.scale.z);
+                self.group(50, underlay.rotation);
+                self.group(280, underlay.flags);
+                self.group(281, underlay.contrast);
+                self.group(282, underlay.fade);
+                if let Some(clip) = &underlay.clip_boundary {
+                    for vertex in &clip.vertices {
+                        self.point2(11, vertex.x, vertex.y);
+                    }
+                }
+            }
+            Geometry::Insert(insert) => {
+                self.subclass("AcDbBlockReference");
+                self.group(2, &insert.block_name);
+                self.point(10, &insert.position);
+                self.group(41, insert.scale.x);
+                self.group(42, insert.scale.y);
+                self.group(43, insert.scale.z);
+                self.group(50, insert.rotation);
+            }
+        }
+
+        if let Some(Value::Object(xdata)) = &entity.xdata {
+            for (application, values) in xdata {
+                self.group(1001, application);
+                for value in values.as_array().into_iter().flatten() {
+                    let Some(code) = value["code"].as_i64() else {
+                        continue;
+                    };
+                    match &value["value"] {
+                        Value::Array(point) => {
+                            for (axis, coordinate) in point.iter().enumerate() {
+                                self.group(code + 10 * axis as i64, coordinate);
+                            }
+                        }
+                        Value::String(text) => self.group(code, text),
+                        other => self.group(code, other),
+                    }
+                }
+            }
+        }
+    }
+
+    /// Writes a POLYLINE and its VERTEX records. Polylines whose vertices
+    /// share one Z are written as 2D at that elevation.
+    fn polyline(
+        &mut self,
+        layer: &str,
+        entity: &CreateEntityInput,
+        polyline: &geometry::Polyline,
+        paper_space: bool,
+    ) {
+        let elevation = polyline.vertices.first().map_or(0.0, |vertex| vertex.z);
+        let is_3d = !is_flat(polyline);
+        self.subclass(if is_3d {
+            "AcDb3dPolyline"
+        } else {
+            "AcDb2dPolyline"
+        });
+        self.group(66, 1);
+        self.point(
+            10,
+            &Point3::new(0.0, 0.0, if is_3d { 0.0 } else { elevation }),
+        );
+        self.group(
+            70,
+            if is_3d { 8 } else { 0 } | if polyline.closed { 1 } else { 0 },
+        );
+
+        for (index, vertex) in polyline.vertices.iter().enumerate() {
+            self.entity_header("VERTEX", layer, None, entity.handle.as_deref(), paper_space);
+            self.subclass("AcDbVertex");
+            self.subclass(if is_3d {
+                "AcDb3dPolylineVertex"
+            } else {
+                "AcDb2dVertex"
+            });
+            self.point(10, vertex);
+            if let Some(bulge) = polyline.bulges.get(index).filter(|bulge| **bulge != 0.0) {
+                self.group(42, bulge);
+            }
+            self.group(70, if is_3d { 32 } else { 0 });
+        }
+    }
+
+    /// Writes an LWPOLYLINE: the vertices inline, at one elevation.
+    fn lwpolyline(&mut self, polyline: &geometry::Polyline) {
+        self.subclass("AcDbPolyline");
+        self.group(90, polyline.vertices.len());
+        self.group(70, if polyline.closed { 1 } else { 0 });
+        let elevation = polyline.vertices.first().map_or(0.0, |vertex| vertex.z);
+        if elevation != 0.0 {
+            self.group(38, elevation);
+        }
+        for (index, vertex) in polyline.vertices.iter().enumerate() {
+            self.point2(10, vertex.x, vertex.y);
+            if let Some(bulge) = polyline.bulges.get(index).filter(|bulge| **bulge != 0.0) {
+                self.group(42, bulge);
+            }
+        }
+    }
+
+    fn seqend(&mut self, layer: &str, owner: Option<&str>) {
+        self.entity_header("SEQEND", layer, None, owner, false);
+    }
+
+    /// A wipeout is stored as its world boundary; it is written as an
+    /// image of one pixel spanning the boundary's extents, with the
+    /// boundary in image coordinates (-0.5 to 0.5, Y down).
+    fn wipeout(&mut self, wipeout: &geometry::Wipeout) {
+        let Some(first) = wipeout.boundary.first() else {
+            return;
+        };
+        let (mut min_x, mut min_y) = (first.x, first.y);
+        let (mut max_x, mut max_y) = (first.x, first.y);
+        for point in &wipeout.boundary {
+            min_x = min_x.min(point.x);
+            min_y = min_y.min(point.y);
+            max_x = max_x.max(point.x);
+            max_y = max_y.max(point.y);
+        }
+        let width = if max_x > min_x { max_x - min_x } else { 1.0 };
+        let height = if max_y > min_y { max_y - min_y } else { 1.0 };
+
+        self.subclass("AcDbWipeout");
+        self.group(90, 0);
+        self.point(10, &Point3::new(min_x, min_y, first.z));
+        self.point(11, &Point3::new(width, 0.0, 0.0));
+        self.point(12, &Point3::new(0.0, height, 0.0));
+        self.point2(13, 1.0, 1.0);
+        self.group(70, 7);
+        self.group(280, 1);
+        self.group(281, 50);
+        self.group(282, 50);
+        self.group(283, 0);
+        self.group(71, 2);
+        self.group(91, wipeout.boundary.len());
+        for point in &wipeout.boundary {
+            self.point2(
+                14,
+                (point.x - min_x) / width - 0.5,
+                0.5 - (point.y - min_y) / height,
+            );
+        }
+    }
+
+    /// Writes a HATCH with every loop as a polyline boundary path. It is
+    /// not associative: there are no boundary objects to point to.
+    fn hatch(&mut self, hatch: &geometry::Hatch) {
+        let elevation = hatch
+            .loops
+            .iter()
+            .find_map(|boundary| boundary.vertices.first())
+            .map_or(0.0, |vertex| vertex.z);
+        self.subclass("AcDbHatch");
+        self.point(10, &Point3::new(0.0, 0.0, elevation));
+        self.point(210, &Point3::new(0.0, 0.0, 1.0));
+        self.group(2, &hatch.pattern_name);
+        self.group(70, i32::from(hatch.solid));
+        self.group(71, 0);
+        self.group(91, hatch.loops.len());
+        for boundary in &hatch.loops {
+            let has_bulges = !boundary.bulges.is_empty();
+            self.group(92, boundary.flags | 2);
+            self.group(72, i32::from(has_bulges));
+            self.group(73, 1);
+            self.group(93, boundary.vertices.len());
+            for (index, vertex) in boundary.vertices.iter().enumerate() {
+                self.point2(10, vertex.x, vertex.y);
+                if has_bulges {
+                    self.group(42, boundary.bulges.get(index).copied().unwrap_or(0.0));
+                }
+            }
+            self.group(97, 0);
+        }
+        self.group(75, hatch.style);
+        self.group(76, 1);
+        if !hatch.solid {
+            self.group(52, hatch.pattern_angle);
+            self.group(41, hatch.pattern_scale);
+            self.group(77, 0);
+            self.group(78, hatch.pattern_lines.len());
+            for line in &hatch.pattern_lines {
+                self.group(53, line.angle);
+                self.group(43, line.base.x);
+                self.group(44, line.base.y);
+                self.group(45, line.offset.x);
+                self.group(46, line.offset.y);
+                self.group(79, line.dashes.len());
+                for dash in &line.dashes {
+                    self.group(49, dash);
+                }
+            }
+        }
+        self.group(98, 0);
+    }
+
+    fn table(&mut self, table: &geometry::Table) {
+        self.subclass("AcDbBlockReference");
+        if let Some(block) = &table.block_name {
+            self.group(2, block);
+        }
+        self.point(10, &table.insertion);
+        self.subclass("AcDbTable");
+        self.group(280, 0);
+        self.point(11, &Point3::new(table.direction.x, table.direction.y, 0.0));
+        self.group(91, table.rows);
+        self.group(92, table.columns);
+        for height in &table.row_heights {
+            self.group(141, height);
+        }
+        for width in &table.column_widths {
+            self.group(142, width);
+        }
+        for cell in table.cells.iter().flatten() {
+            self.group(171, if cell.block.is_some() { 2 } else { 1 });
+            self.group(175, cell.column_span);
+            self.group(176, cell.row_span);
+            if let Some(height) = cell.text_height {
+                self.group(140, height);
+            }
+            if let Some(block) = &cell.block {
+                self.group(340, block);
+            }
+            if !cell.text.is_empty() {
+                self.chunked_text(&cell.text, 2);
+            }
+        }
+    }
+
+    fn clip_boundary(&mut self, clip: &ClipBoundary, code: i32) {
+        self.group(
+            71,
+            match clip.kind {
+                ClipKind::Rectangular => 1,
+                ClipKind::Polygonal => 2,
+            },
+        );
+        self.group(91, clip.vertices.len());
+        for vertex in &clip.vertices {
+            self.point2(code, vertex.x, vertex.y);
+        }
+    }
+
+    /// Writes text of any length: full chunks under `chunk_code`, the rest
+    /// under `1`.
+    fn chunked_text(&mut self, text: &str, chunk_code: i32) {
+        let chars: Vec<char> = text.chars().collect();
+        let mut chunks = chars.chunks(CHUNK_LENGTH).peekable();
+        if chunks.peek().is_none() {
+            self.group(1, "");
+        }
+        while let Some(chunk) = chunks.next() {
+            let chunk: String = chunk.iter().collect();
+            if chunks.peek().is_some() {
+                self.group(chunk_code, chunk);
+            } else {
+                self.group(1, chunk);
+            }
+        }
+    }
+
+    /// Writes the root dictionary and the dictionaries below it, LAYOUT,
+    /// GROUP and definition objects, then the raw objects at their
+    /// positions. Named objects that were not kept otherwise are written
+    /// as bare records of their type, so their dictionary entries resolve.
+    fn objects(&mut self, blocks: &[Block<'a>]) {
+        let drawing = self.drawing;
+        self.begin_section("OBJECTS");
+
+        let root = self.new_handle();
+        let mut parents: HashMap<&str, &str> = HashMap::new();
+        let mut dictionaries = vec![Dictionary {
+            handle: root.clone(),
+            owner: "0".to_string(),
+            entries: Vec::new(),
+        }];
+        let mut dictionary_paths: HashMap<&str, usize> = HashMap::new();
+        for object in drawing
+            .named_objects
+            .iter()
+            .filter(|object| object.object_type == "DICTIONARY")
+        {
+            if dictionary_paths.contains_key(object.path.as_str())
+                || dictionaries
+                    .iter()
+                    .any(|dictionary| dictionary.handle == object.handle)
+            {
+                continue;
+            }
+            dictionary_paths.insert(&object.path, dictionaries.len());
+            dictionaries.push(Dictionary {
+                handle: object.handle.clone(),
+                owner: String::new(),
+                entries: Vec::new(),
+            });
+        }
+        for object in &drawing.named_objects {
+            let (parent, name) = match object.path.rsplit_once('/') {
+                Some((parent, name)) => (dictionary_paths.get(parent).copied(), name),
+                None => (Some(0), object.path.as_str()),
+            };
+            let Some(parent) = parent else {
+                continue;
+            };
+            dictionaries[parent].entries.push((name, &object.handle));
+            if let Some(&index) = dictionary_paths.get(object.path.as_str()) {
+                dictionaries[index].owner = dictionaries[parent].handle.clone();
+            }
+        }
+        for dictionary in &dictionaries {
+            for (_, child) in &dictionary.entries {
+                parents.entry(*child).or_insert(dictionary.handle.as_str());
+            }
+        }
+        let parent_of = |handle: &str| -> String {
+            parents
+                .get(handle)
+                .map_or_else(|| root.clone(), |parent| parent.to_string())
+        };
+
+        let mut written: HashSet<String> = drawing
+            .raw_records
+            .iter()
+            .filter(|record| record.section == RawSection::Objects)
+            .filter_map(|record| record.handle.clone())
+            .collect();
+        let mut raw_objects = drawing
+            .raw_records
+            .iter()
+            .filter(|record| record.section == RawSection::Objects)
+            .peekable();
+        let mut count: u64 = 0;
+        let mut flush = |writer: &mut Self, count: &mut u64| {
+            while let Some(record) = raw_objects.next_if(|record| record.position <= *count) {
+                writer.out.push_str(&record.content);
+                *count += 1;
+            }
+            *count += 1;
+        };
+
+        for dictionary in &dictionaries {
+            if !written.insert(dictionary.handle.clone()) {
+                continue;
+            }
+            flush(self, &mut count);
+            self.group(0, "DICTIONARY");
+            self.group(5, &dictionary.handle);
+            self.group(330, &dictionary.owner);
+            self.group(100, "AcDbDictionary");
+            self.group(281, 1);
+            for (name, child) in &dictionary.entries {
+                self.group(3, name);
+                self.group(350, child);
+            }
+        }
+
+        for layout in &drawing.layouts {
+            let named = drawing.named_objects.iter().find(|object| {
+                object.object_type == "LAYOUT"
+                    && object.path.rsplit('/').next() == Some(layout.name.as_str())
+            });
+            let handle = match named {
+                Some(object) => object.handle.clone(),
+                None => self.new_handle(),
+            };
+            if !written.insert(handle.clone()) {
+                continue;
+            }
+            let block = layout.block_name.as_ref().and_then(|name| {
+                blocks
+                    .iter()
+                    .find(|block| block.name == *name)
+                    .map(|block| block.record.clone())
+            });
+            flush(self, &mut count);
+            self.group(0, "LAYOUT");
+            self.group(5, &handle);
+            self.group(330, parent_of(&handle));
+            self.group(100, "AcDbPlotSettings");
+            self.group(1, "");
+            self.group(100, "AcDbLayout");
+            self.group(1, &layout.name);
+            self.group(70, 1);
+            self.group(71, layout.tab_order);
+            self.point2(10, layout.min_x, layout.min_y);
+            self.point2(11, layout.max_x, layout.max_y);
+            if let Some(block) = block {
+                self.group(330, block);
+            }
+        }
+
+        for group in &drawing.groups {
+            if !written.insert(group.handle.clone()) {
+                continue;
+            }
+            flush(self, &mut count);
+            self.group(0, "GROUP");
+            self.group(5, &group.handle);
+            self.group(330, parent_of(&group.handle));
+            self.group(100, "AcDbGroup");
+            self.group(300, group.description.as_deref().unwrap_or(""));
+            self.group(70, 0);
+            self.group(71, i32::from(group.is_selectable));
+            for member in &group.members {
+                self.group(340, member);
+            }
+        }
+
+        for reference in &drawing.references {
+            if !written.insert(reference.handle.clone()) {
+                continue;
+            }
+            flush(self, &mut count);
+            let kind = match reference.kind {
+                ReferenceKind::Image => "IMAGEDEF",
+                ReferenceKind::Pdf => "PDFDEFINITION",
+                ReferenceKind::Dwf => "DWFDEFINITION",
+                ReferenceKind::Dgn => "DGNDEFINITION",
+            };
+            self.group(0, kind);
+            self.group(5, &reference.handle);
+            self.group(330, parent_of(&reference.handle));
+            if reference.kind == ReferenceKind::Image {
+                self.group(100, "AcDbRasterImageDef");
+                self.group(90, 0);
+                self.group(1, &reference.path);
+                if let (Some(width), Some(height)) = (reference.width_px, reference.height_px) {
+                    self.point2(10, width, height);
+                }
+            } else {
+                self.group(100, "AcDbUnderlayDefinition");
+                self.group(1, &reference.path);
+                self.group(2, reference.name.as_deref().unwrap_or(""));
+            }
+        }
+
+        for object in &drawing.named_objects {
+            if !written.insert(object.handle.clone()) {
+                continue;
+            }
+            flush(self, &mut count);
+            self.group(0, &object.object_type);
+            self.group(5, &object.handle);
+            self.group(330, parent_of(&object.handle));
+        }
+
+        for record in raw_objects {
+            self.out.push_str(&record.content);
+        }
+        self.end_section();
+    }
+}
+
+/// Finds or adds the block with this name; block names are not case
+/// sensitive.
+fn block_entry<'a, 'b>(blocks: &'b mut Vec<Block<'a>>, name: &str) -> &'b mut Block<'a> {
+    let index = match blocks
+        .iter()
+        .position(|block| block.name.eq_ignore_ascii_case(name))
+    {
+        Some(index) => index,
+        None => {
+            blocks.push(Block {
+                name: name.to_string(),
+                record: String::new(),
+                items: Vec::new(),
+            });
+            blocks.len() - 1
+        }
+    };
+    &mut blocks[index]
+}
+
+/// Whether all of a polyline's vertices share one Z.
+fn is_flat(polyline: &geometry::Polyline) -> bool {
+    let elevation = polyline.vertices.first().map_or(0.0, |vertex| vertex.z);
+    polyline.vertices.iter().all(|vertex| vertex.z == elevation)
+}
+
+fn properties(data: &Geometry) -> Option<&EntityProperties> {
+    match data {
+        Geometry::Line(line) => Some(&line.properties),
+        Geometry::Polyline(polyline) => Some(&polyline.properties),
+        Geometry::Arc(arc) => Some(&arc.properties),
+        Geometry::Circle(circle) => Some(&circle.properties),
+        Geometry::Text(text) => Some(&text.properties),
+        Geometry::MText(text) => Some(&text.properties),
+        Geometry::Dimension(dimension) => Some(&dimension.properties),
+        Geometry::Hatch(hatch) => Some(&hatch.properties),
+        _ => None,
+    }
+}
+
+/// The highest handle referenced anywhere in the drawing, so that new
+/// handles cannot collide with kept ones.
+fn max_handle(drawing: &ParsedDrawing) -> u64 {
+    let mut handles: Vec<&str> = Vec::new();
+    for entity in drawing.layers.values().flat_map(|layer| &layer.entities) {
+        handles.extend(entity.handle.as_deref());
+        handles.extend(entity.owner_handle.as_deref());
+        handles.extend(entity.extension_dictionary.as_deref());
+        match &entity.data {
+            Geometry::Viewport(viewport) => {
+                handles.extend(viewport.frozen_layers.iter().map(String::as_str))
+            }
+            Geometry::Image(image) => handles.extend(image.definition.as_deref()),
+            Geometry::PdfUnderlay(underlay)
+            | Geometry::DwfUnderlay(underlay)
+            | Geometry::DgnUnderlay(underlay) => handles.extend(underlay.definition.as_deref()),
+            Geometry::Table(table) => handles.extend(
+                table
+                    .cells
+                    .iter()
+                    .flatten()
+                    .filter_map(|cell| cell.block.as_deref()),
+            ),
+            _ => {}
+        }
+    }
+    for record in &drawing.raw_records {
+        handles.extend(record.handle.as_deref());
+        handles.extend(record.owner_handle.as_deref());
+        // Block definitions hold their entities' handles.
+        if record.section == RawSection::Blocks {
+            handles.extend(
+                raw_groups(&record.content)
+                    .filter(|(code, _)| *code == "5")
+                    .map(|(_, handle)| handle.trim()),
+            );
+        }
+    }
+    for group in &drawing.groups {
+        handles.push(&group.handle);
+        handles.extend(group.members.iter().map(String::as_str));
+    }
+    handles.extend(
+        drawing
+            .named_objects
+            .iter()
+            .map(|object| object.handle.as_str()),
+    );
+    handles.extend(
+        drawing
+            .references
+            .iter()
+            .map(|reference| reference.handle.as_str()),
+    );
+    for dim_style in &drawing.dim_styles {
+        handles.extend(dim_style.properties.get("DIMTXSTY").and_then(Value::as_str));
+    }
+    for linetype in &drawing.linetypes {
+        handles.extend(
+            linetype
+                .elements
+                .iter()
+                .filter_map(|element| element.embedded.as_ref()?.style_handle.as_deref()),
+        );
+    }
+
+    handles
+        .into_iter()
+        .filter_map(|handle| u64::from_str_radix(handle, 16).ok())
+        .max()
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse_dxf;
+    use serde_json::json;
+
+    const SAMPLE_LAYOUTS: &str = include_str!("../../fixtures/sample_layouts.dxf");
+    const SAMPLE_TABLES: &str = include_str!("../../fixtures/sample_tables.dxf");
+
+    fn export(drawing: &ParsedDrawing, version: DxfVersion) -> ParsedDrawing {
+        parse_dxf(&write_dxf(drawing, version)).unwrap()
+    }
+
+    /// Entities with everything the parser stores, in draw order.
+    fn entities(drawing: &ParsedDrawing) -> Vec<Value> {
+        let mut entities: Vec<(&str, &CreateEntityInput)> = drawing
+            .layers
+            .values()
+            .flat_map(|layer| {
+                let name = layer.input.name.as_str();
+                layer.entities.iter().map(move |entity| (name, entity))
+            })
+            .collect();
+        entities.sort_by_key(|(_, entity)| entity.draw_order);
+        entities
+            .into_iter()
+            .map(|(layer, entity)| {
+                let mut value = serde_json::to_value(entity).unwrap();
+                value["draw_order"] = json!(null);
+                value["layer"] = json!(layer);
+                value
+            })
+            .collect()
+    }
+
+    fn layers(drawing: &ParsedDrawing) -> Value {
+        let mut layers: Vec<_> = drawing.layers.values().map(|layer| &layer.input).collect();
+        layers.sort_by(|a, b| a.name.cmp(&b.name));
+        serde_json::to_value(layers).unwrap()
+    }
+
+    fn tables(drawing: &ParsedDrawing) -> Value {
+        json!({
+            "linetypes": drawing.linetypes,
+            "text_styles": drawing.text_styles,
+            "dim_styles": drawing.dim_styles,
+            "linetype_scale": drawing.linetype_scale,
+        })
+    }
+
+    fn objects(drawing: &ParsedDrawing) -> Value {
+        json!({
+            "layouts": drawing.layouts,
+            "named_objects": drawing.named_objects,
+            "groups": drawing.groups,
+            "references": drawing.references,
+        })
+    }
+
+    /// Raw records without their positions, which count records the parser
+    /// does not keep.
+    fn raw_records(drawing: &ParsedDrawing) -> Vec<Value> {
+        drawing
+            .raw_records
+            .iter()
+            .map(|record| {
+                json!([
+                    record.section,
+                    record.record_type,
+                    record.handle,
+                    record.owner_handle,
+                    record.layout,
+                    record.content,
+                ])
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_round_trip_keeps_tables_layouts_and_entities() {
+        let drawing = parse_dxf(SAMPLE_LAYOUTS).unwrap();
+        let exported = export(&drawing, DxfVersion::R2018);
+
+        assert_eq!(layers(&exported), layers(&drawing));
+        assert_eq!(tables(&exported), tables(&drawing));
+        assert_eq!(objects(&exported), objects(&drawing));
+        assert_eq!(entities(&exported), entities(&drawing));
+        assert_eq!(raw_records(&exported), raw_records(&drawing));
+        assert!(exported.diagnostics.is_empty());
+
+        // The fixture covers every entity type the writer knows, in all
+        // three layouts.
+        let types: HashSet<&str> = drawing
+            .layers
+            .values()
+            .flat_map(|layer| &layer.entities)
+            .map(CreateEntityInput::entity_type)
+            .collect();
+        for kind in [
+            "LINE",
+            "POLYLINE",
+            "ARC",
+            "CIRCLE",
+            "TEXT",
+            "MTEXT",
+            "DIMENSION",
+            "VIEWPORT",
+            "IMAGE",
+            "WIPEOUT",
+            "HATCH",
+            "TABLE",
+            "PDFUNDERLAY",
+        ] {
+            assert!(types.contains(kind), "no {kind} in the fixture");
+        }
+        let layouts: Vec<&str> = drawing.layouts.iter().map(|l| l.name.as_str()).collect();
+        assert_eq!(layouts, vec!["Model", "Layout1", "Sheet 2"]);
+        assert_eq!(drawing.groups[0].name, "DOORS");
+        assert_eq!(drawing.references.len(), 2);
+        let frozen: Vec<&String> = drawing.layers["VIEWPORTS"]
+            .entities
+            .iter()
+            .filter_map(|entity| match &entity.data {
+                Geometry::Viewport(viewport) => Some(&viewport.frozen_layers),
+                _ => None,
+            })
+            .flatten()
+            .collect();
+        assert_eq!(frozen, vec!["NOTES"]);
+    }
+
+    #[test]
+    fn test_round_trip_keeps_draw_order() {
+        let drawing = parse_dxf(SAMPLE_LAYOUTS).unwrap();
+        let exported = export(&drawing, DxfVersion::R2018);
+
+        let order = |drawing: &ParsedDrawing| -> Vec<Option<String>> {
+            let mut entities: Vec<&CreateEntityInput> = drawing
+                .layers
+                .values()
+                .flat_map(|layer| &layer.entities)
+                .collect();
+            entities.sort_by_key(|entity| entity.draw_order);
+            entities
+                .iter()
+                .map(|entity| entity.handle.clone())
+                .collect()
+        };
+        assert_eq!(order(&exported), order(&drawing));
+    }
+
+    #[test]
+    fn test_round_trip_keeps_user_blocks() {
+        let content = "0\nSECTION\n2\nTABLES\n\
+0\nTABLE\n2\nBLOCK_RECORD\n5\n1\n70\n3\n\
+0\nBLOCK_RECORD\n5\n1F\n330\n1\n2\n*Model_Space\n\
+0\nBLOCK_RECORD\n5\n1B\n330\n1\n2\n*Paper_Space\n\
+0\nBLOCK_RECORD\n5\n40\n330\n1\n2\nCHAIR\n\
+0\nENDTAB\n0\nENDSEC\n\
+0\nSECTION\n2\nBLOCKS\n\
+0\nBLOCK\n5\n20\n330\n1F\n100\nAcDbEntity\n8\n0\n100\nAcDbBlockBegin\n2\n*Model_Space\n70\n0\n\
+0\nENDBLK\n5\n21\n330\n1F\n100\nAcDbEntity\n8\n0\n100\nAcDbBlockEnd\n\
+0\nBLOCK\n5\n41\n330\n40\n100\nAcDbEntity\n8\n0\n100\nAcDbBlockBegin\n2\nCHAIR\n70\n0\n\
+10\n0.0\n20\n0.0\n30\n0.0\n3\nCHAIR\n1\n\n\
+0\nCIRCLE\n5\n42\n330\n40\n100\nAcDbEntity\n8\n0\n100\nAcDbCircle\n\
+10\n0.0\n20\n0.0\n30\n0.0\n40\n0.5\n\
+0\nENDBLK\n5\n43\n330\n40\n100\nAcDbEntity\n8\n0\n100\nAcDbBlockEnd\n\
+0\nENDSEC\n\
+0\nSECTION\n2\nENTITIES\n\
+0\nINSERT\n5\n50\n330\n1F\n100\nAcDbEntity\n8\nFURNITURE\n100\nAcDbBlockReference\n\
+2\nCHAIR\n10\n5.0\n20\n5.0\n30\n0.0\n\
+0\nENDSEC\n0\nEOF\n";
+        let drawing = parse_dxf(content).unwrap();
+        let blocks = |drawing: &ParsedDrawing| -> Vec<CreateRawRecordInput> {
+            drawing
+                .raw_records
+                .iter()
+                .filter(|record| record.section == RawSection::Blocks)
+                .cloned()
+                .collect()
+        };
+        let [chair] = &blocks(&drawing)[..] else {
+            panic!("expected one block");
+        };
+        assert_eq!(chair.handle.as_deref(), Some("41"));
+        assert_eq!(chair.owner_handle.as_deref(), Some("40"));
+        assert!(chair.content.contains("0\nCIRCLE\n"));
+        assert!(chair.content.ends_with("100\nAcDbBlockEnd\n"));
+
+        let written = write_dxf(&drawing, DxfVersion::R2018);
+        assert!(written.contains("  0\nBLOCK_RECORD\n  5\n40\n"));
+        assert!(written.contains(&chair.content));
+        let exported = parse_dxf(&written).unwrap();
+        assert_eq!(raw_records(&exported), raw_records(&drawing));
+        assert!(exported.diagnostics.is_empty());
+
+        let written = write_dxf(&drawing, DxfVersion::R12);
+        assert!(!written.contains("AcDb"));
+        let exported = parse_dxf(&written).unwrap();
+        let [chair] = &blocks(&exported)[..] else {
+            panic!("expected one block");
+        };
+        assert_eq!(chair.handle, None);
+        assert!(chair.content.contains("CHAIR"));
+        assert!(chair.content.contains("CIRCLE"));
+        assert!(exported
+            .raw_records
+            .iter()
+            .any(|record| record.record_type == "INSERT" && record.content.contains("CHAIR")));
+    }
+
+    #[test]
+    fn test_export_is_stable() {
+        for content in [SAMPLE_LAYOUTS, SAMPLE_TABLES] {
+            let first = write_dxf(&parse_dxf(content).unwrap(), DxfVersion::R2018);
+            let second = write_dxf(&parse_dxf(&first).unwrap(), DxfVersion::R2018);
+            assert_eq!(first, second);
+
+            let first = write_dxf(&parse_dxf(content).unwrap(), DxfVersion::R12);
+            let second = write_dxf(&parse_dxf(&first).unwrap(), DxfVersion::R12);
+            assert_eq!(first, second);
+        }
+    }
+
+    #[test]
+    fn test_round_trip_sample_tables() {
+        let drawing = parse_dxf(SAMPLE_TABLES).unwrap();
+        let exported = export(&drawing, DxfVersion::R2018);
+
+        assert_eq!(layers(&exported), layers(&drawing));
+        assert_eq!(tables(&exported), tables(&drawing));
+        assert_eq!(entities(&exported), entities(&drawing));
+    }
+
+    #[test]
+    fn test_r12_keeps_tables_and_r12_entities() {
+        let drawing = parse_dxf(SAMPLE_LAYOUTS).unwrap();
+        let content = write_dxf(&drawing, DxfVersion::R12);
+        let exported = parse_dxf(&content).unwrap();
+
+        assert!(content.contains("AC1009"));
+        assert!(!content.contains("AcDb"));
+        assert!(!content.contains("OBJECTS"));
+        // R12 has no lineweights.
+        let without_line_weights = |drawing: &ParsedDrawing| -> Value {
+            let mut layers = layers(drawing);
+            for layer in layers.as_array_mut().unwrap() {
+                layer["line_weight"] = Value::Null;
+            }
+            layers
+        };
+        assert_eq!(
+            without_line_weights(&exported),
+            without_line_weights(&drawing)
+        );
+        assert_eq!(exported.linetypes.len(), drawing.linetypes.len());
+        // Raw records an R12 reader does not know are left out.
+        assert!(exported
+            .raw_records
+            .iter()
+            .all(|record| R12_RECORDS.contains(&record.record_type.as_str())));
+
+        // R12 has no handles, linetype scales, lineweights or true colors;
+        // compare model space geometry by type.
+        let model = |drawing: &ParsedDrawing, kind: &str| -> Vec<Value> {
+            entities(drawing)
+                .into_iter()
+                .filter(|entity| entity["layout"] == MODEL_LAYOUT)
+                .map(|mut entity| {
+                    if let Value::Object(data) = &mut entity["data"] {
+                        data.remove("line_type_scale");
+                        data.remove("line_weight");
+                        data.remove("true_color");
+                    }
+                    entity["data"].clone()
+                })
+                .filter(|data| data["type"] == kind)
+                .collect()
+        };
+        for kind in ["LINE", "POLYLINE", "ARC", "CIRCLE", "TEXT"] {
+            assert!(!model(&drawing, kind).is_empty());
+            assert_eq!(model(&exported, kind), model(&drawing, kind), "{kind}");
+        }
+        for kind in ["MTEXT", "IMAGE", "WIPEOUT", "TABLE", "PDFUNDERLAY"] {
+            assert!(model(&exported, kind).is_empty(), "{kind}");
+        }
+    }
+
+    #[test]
+    fn test_splits_long_text_into_chunks() {
+        let text = "0123456789".repeat(60);
+        let content = format!(
+            "0\nSECTION\n2\nENTITIES\n0\nMTEXT\n8\n0\n10\n0.0\n20\n0.0\n40\n2.5\n3\n{}\n3\n{}\n1\n{}\n0\nENDSEC\n0\nEOF\n",
+            &text[..250],
+            &text[250..500],
+            &text[500..]
+        );
+        let drawing = parse_dxf(&content).unwrap();
+        let written = write_dxf(&drawing, DxfVersion::R2018);
+
+        assert!(written.contains(&format!("  3\n{}\n", &text[..250])));
+        assert!(written.contains(&format!("  1\n{}\n", &text[500..])));
+        let Geometry::MText(mtext) =
+            &export(&drawing, DxfVersion::R2018).layers["0"].entities[0].data
+        else {
+            panic!("expected MTEXT");
+        };
+        assert_eq!(mtext.text, text);
+    }
+}
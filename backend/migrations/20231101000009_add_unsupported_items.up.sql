@@ -0,0 +1,10 @@
+CREATE TABLE IF NOT EXISTS unsupported_items (
+    id BIGINT UNSIGNED AUTO_INCREMENT PRIMARY KEY,
+    file_id BIGINT UNSIGNED NOT NULL,
+    kind ENUM('entity', 'section', 'table') NOT NULL,
+    name VARCHAR(255) NOT NULL,
+    count BIGINT UNSIGNED NOT NULL,
+    created_at DATETIME(6) NOT NULL DEFAULT CURRENT_TIMESTAMP(6),
+    CONSTRAINT fk_unsupported_items_file FOREIGN KEY (file_id) REFERENCES files(id) ON DELETE CASCADE,
+    UNIQUE KEY uk_unsupported_items_file_kind_name (file_id, kind, name)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
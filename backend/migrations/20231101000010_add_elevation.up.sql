@@ -0,0 +1,9 @@
+ALTER TABLE entities
+ADD COLUMN min_z DOUBLE NOT NULL DEFAULT 0 AFTER max_y,
+ADD COLUMN max_z DOUBLE NOT NULL DEFAULT 0 AFTER min_z,
+ADD INDEX idx_entities_min_z (min_z),
+ADD INDEX idx_entities_max_z (max_z);
+
+ALTER TABLE layers
+ADD COLUMN min_z DOUBLE NULL AFTER max_y,
+ADD COLUMN max_z DOUBLE NULL AFTER min_z;
@@ -0,0 +1,6 @@
+ALTER TABLE entities
+DROP INDEX idx_entities_handle,
+DROP COLUMN xdata,
+DROP COLUMN extension_dictionary,
+DROP COLUMN owner_handle,
+DROP COLUMN handle;
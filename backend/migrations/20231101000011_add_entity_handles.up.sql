@@ -0,0 +1,6 @@
+ALTER TABLE entities
+ADD COLUMN handle VARCHAR(16) NULL AFTER layer_id,
+ADD COLUMN owner_handle VARCHAR(16) NULL AFTER handle,
+ADD COLUMN extension_dictionary VARCHAR(16) NULL AFTER owner_handle,
+ADD COLUMN xdata JSON NULL AFTER data,
+ADD INDEX idx_entities_handle (handle);
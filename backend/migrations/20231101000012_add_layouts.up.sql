@@ -0,0 +1,18 @@
+CREATE TABLE IF NOT EXISTS layouts (
+    id BIGINT UNSIGNED AUTO_INCREMENT PRIMARY KEY,
+    file_id BIGINT UNSIGNED NOT NULL,
+    name VARCHAR(255) NOT NULL,
+    block_name VARCHAR(255) NULL,
+    tab_order INT NOT NULL DEFAULT 0,
+    min_x DOUBLE NOT NULL DEFAULT 0,
+    min_y DOUBLE NOT NULL DEFAULT 0,
+    max_x DOUBLE NOT NULL DEFAULT 0,
+    max_y DOUBLE NOT NULL DEFAULT 0,
+    created_at DATETIME(6) NOT NULL DEFAULT CURRENT_TIMESTAMP(6),
+    CONSTRAINT fk_layouts_file FOREIGN KEY (file_id) REFERENCES files(id) ON DELETE CASCADE,
+    UNIQUE KEY uk_layouts_file_name (file_id, name)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
+
+ALTER TABLE entities
+ADD COLUMN layout VARCHAR(255) NOT NULL DEFAULT 'Model' AFTER layer_id,
+ADD INDEX idx_entities_layer_layout (layer_id, layout);
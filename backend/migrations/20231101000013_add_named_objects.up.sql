@@ -0,0 +1,23 @@
+CREATE TABLE IF NOT EXISTS named_objects (
+    id BIGINT UNSIGNED AUTO_INCREMENT PRIMARY KEY,
+    file_id BIGINT UNSIGNED NOT NULL,
+    path VARCHAR(1024) NOT NULL,
+    handle VARCHAR(16) NOT NULL,
+    object_type VARCHAR(64) NOT NULL,
+    created_at DATETIME(6) NOT NULL DEFAULT CURRENT_TIMESTAMP(6),
+    CONSTRAINT fk_named_objects_file FOREIGN KEY (file_id) REFERENCES files(id) ON DELETE CASCADE,
+    INDEX idx_named_objects_file_id (file_id)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
+
+CREATE TABLE IF NOT EXISTS entity_groups (
+    id BIGINT UNSIGNED AUTO_INCREMENT PRIMARY KEY,
+    file_id BIGINT UNSIGNED NOT NULL,
+    name VARCHAR(255) NOT NULL,
+    handle VARCHAR(16) NOT NULL,
+    description TEXT NULL,
+    is_selectable TINYINT(1) NOT NULL DEFAULT 1,
+    members JSON NOT NULL,
+    created_at DATETIME(6) NOT NULL DEFAULT CURRENT_TIMESTAMP(6),
+    CONSTRAINT fk_entity_groups_file FOREIGN KEY (file_id) REFERENCES files(id) ON DELETE CASCADE,
+    UNIQUE KEY uk_entity_groups_file_name (file_id, name)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
@@ -0,0 +1,13 @@
+CREATE TABLE IF NOT EXISTS external_references (
+    id BIGINT UNSIGNED AUTO_INCREMENT PRIMARY KEY,
+    file_id BIGINT UNSIGNED NOT NULL,
+    handle VARCHAR(16) NOT NULL,
+    kind VARCHAR(16) NOT NULL,
+    path VARCHAR(1024) NOT NULL,
+    name VARCHAR(255) NULL,
+    width_px DOUBLE NULL,
+    height_px DOUBLE NULL,
+    created_at DATETIME(6) NOT NULL DEFAULT CURRENT_TIMESTAMP(6),
+    CONSTRAINT fk_external_references_file FOREIGN KEY (file_id) REFERENCES files(id) ON DELETE CASCADE,
+    UNIQUE KEY uk_external_references_file_handle (file_id, handle)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
@@ -0,0 +1,3 @@
+ALTER TABLE entities
+DROP INDEX idx_entities_layer_layout_draw_order,
+DROP COLUMN draw_order;
@@ -0,0 +1,3 @@
+ALTER TABLE entities
+ADD COLUMN draw_order BIGINT UNSIGNED NOT NULL DEFAULT 0 AFTER extension_dictionary,
+ADD INDEX idx_entities_layer_layout_draw_order (layer_id, layout, draw_order);
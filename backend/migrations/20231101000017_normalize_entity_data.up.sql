@@ -0,0 +1,28 @@
+UPDATE entities
+SET data = JSON_REMOVE(
+        IF(JSON_CONTAINS_PATH(data, 'one', '$.vertices'),
+           data,
+           JSON_SET(data, '$.vertices', JSON_EXTRACT(data, '$.points'))),
+        '$.points')
+WHERE JSON_CONTAINS_PATH(data, 'one', '$.points')
+  AND NOT JSON_CONTAINS_PATH(data, 'one', '$.version');
+
+UPDATE entities
+SET data = JSON_REMOVE(
+        IF(JSON_CONTAINS_PATH(data, 'one', '$.text'),
+           data,
+           JSON_SET(data, '$.text', JSON_EXTRACT(data, '$.content'))),
+        '$.content')
+WHERE JSON_CONTAINS_PATH(data, 'one', '$.content')
+  AND NOT JSON_CONTAINS_PATH(data, 'one', '$.version');
+
+UPDATE entities
+SET data = JSON_SET(data, '$.scale.z', 1)
+WHERE entity_type = 'INSERT'
+  AND JSON_CONTAINS_PATH(data, 'one', '$.scale')
+  AND NOT JSON_CONTAINS_PATH(data, 'one', '$.scale.z')
+  AND NOT JSON_CONTAINS_PATH(data, 'one', '$.version');
+
+UPDATE entities
+SET data = JSON_SET(data, '$.type', entity_type, '$.version', 1)
+WHERE NOT JSON_CONTAINS_PATH(data, 'one', '$.version');
@@ -17,6 +17,30 @@ pub enum AppError {
     #[error("Project not found")]
     ProjectNotFound,
 
+    #[error("File not found")]
+    FileNotFound,
+
+    #[error("Layer not found")]
+    LayerNotFound,
+
+    #[error("Entity not found")]
+    EntityNotFound,
+
+    #[error("Layout not found")]
+    LayoutNotFound,
+
+    #[error("Viewport not found")]
+    ViewportNotFound,
+
+    #[error("Group not found")]
+    GroupNotFound,
+
+    #[error("Table not found")]
+    TableNotFound,
+
+    #[error("Thumbnail not found")]
+    ThumbnailNotFound,
+
     #[error("File too large")]
     FileTooLarge,
 
@@ -26,9 +50,21 @@ pub enum AppError {
     #[error("Storage limit exceeded")]
     StorageLimitExceeded,
 
+    #[error("A file with this name already exists")]
+    FileNameTaken,
+
     #[error("Invalid multipart data: {0}")]
     InvalidMultipart(String),
 
+    #[error("Invalid query: {0}")]
+    InvalidQuery(String),
+
+    #[error("Invalid DXF: {0}")]
+    InvalidDxf(String),
+
+    #[error("Invalid GeoJSON: {0}")]
+    InvalidGeoJson(String),
+
     #[error("Internal server error: {0}")]
     Internal(String),
 }
@@ -37,10 +73,22 @@ impl IntoResponse for AppError {
     fn into_response(self) -> Response {
         let (status, error_message) = match self {
             AppError::ProjectNotFound => (StatusCode::NOT_FOUND, self.to_string()),
+            AppError::FileNotFound => (StatusCode::NOT_FOUND, self.to_string()),
+            AppError::LayerNotFound => (StatusCode::NOT_FOUND, self.to_string()),
+            AppError::EntityNotFound => (StatusCode::NOT_FOUND, self.to_string()),
+            AppError::LayoutNotFound => (StatusCode::NOT_FOUND, self.to_string()),
+            AppError::ViewportNotFound => (StatusCode::NOT_FOUND, self.to_string()),
+            AppError::GroupNotFound => (StatusCode::NOT_FOUND, self.to_string()),
+            AppError::TableNotFound => (StatusCode::NOT_FOUND, self.to_string()),
+            AppError::ThumbnailNotFound => (StatusCode::NOT_FOUND, self.to_string()),
             AppError::FileTooLarge => (StatusCode::PAYLOAD_TOO_LARGE, self.to_string()),
             AppError::InvalidFileType => (StatusCode::BAD_REQUEST, self.to_string()),
             AppError::StorageLimitExceeded => (StatusCode::CONFLICT, self.to_string()),
+            AppError::FileNameTaken => (StatusCode::CONFLICT, self.to_string()),
             AppError::InvalidMultipart(ref msg) => (StatusCode::BAD_REQUEST, msg.clone()),
+            AppError::InvalidQuery(_) => (StatusCode::BAD_REQUEST, self.to_string()),
+            AppError::InvalidDxf(_) => (StatusCode::UNPROCESSABLE_ENTITY, self.to_string()),
+            AppError::InvalidGeoJson(_) => (StatusCode::UNPROCESSABLE_ENTITY, self.to_string()),
             AppError::Database(ref e) => {
                 tracing::error!("Database error: {:?}", e);
                 (
@@ -72,4 +120,13 @@ impl IntoResponse for AppError {
     }
 }
 
+impl From<dxf_parser::Error> for AppError {
+    fn from(error: dxf_parser::Error) -> Self {
+        match error {
+            dxf_parser::Error::Io(e) => AppError::Io(e),
+            dxf_parser::Error::InvalidDxf(msg) => AppError::InvalidDxf(msg),
+        }
+    }
+}
+
 pub type Result<T> = std::result::Result<T, AppError>;
@@ -0,0 +1,913 @@
+//! Converting drawings to and from GeoJSON (RFC 7946). Coordinates are
+//! taken as they are, in drawing units; nothing is projected.
+
+use crate::error::{AppError, Result};
+use crate::models::geometry::{Hatch, Line, Polyline, Text};
+use crate::models::table::plain_text;
+use crate::models::{
+    CreateEntityInput, CreateLayerInput, Entity, Geometry, Layer, Point, Point3, MODEL_LAYOUT,
+};
+use crate::render::{bulge_arc, segments};
+use dxf_parser::{ParsedDrawing, ParsedLayer};
+use serde_json::{json, Map, Value};
+use std::collections::HashMap;
+use std::f64::consts::TAU;
+
+/// Straight segments a full turn of an arc is flattened into.
+const SEGMENTS_PER_TURN: f64 = 64.0;
+
+/// The layer of imported features without the layer property.
+const DEFAULT_LAYER: &str = "0";
+
+/// Height of imported text without a `height` property.
+const DEFAULT_TEXT_HEIGHT: f64 = 2.5;
+
+/// Entities as a FeatureCollection. Lines, open polylines and arcs become
+/// LineStrings, closed polylines, circles and wipeouts Polygons, hatches
+/// Polygons with their islands as holes (MultiPolygons if they have more
+/// than one outer loop), and text Points with the text in their
+/// properties; arcs are flattened. Every
+/// feature has its layer, entity type and handle as properties. Entities
+/// on layers not in `layers`, and types without a 2D outline, are left
+/// out.
+pub fn feature_collection(layers: &[Layer], entities: &[Entity]) -> Value {
+    let layer_names: HashMap<u64, &str> = layers
+        .iter()
+        .map(|layer| (layer.id, layer.name.as_str()))
+        .collect();
+
+    let features: Vec<Value> = entities
+        .iter()
+        .filter_map(|entity| {
+            let layer = layer_names.get(&entity.layer_id)?;
+            let (geometry, mut properties) = feature_geometry(&entity.data)?;
+            properties.insert("layer".to_string(), json!(layer));
+            properties.insert("entity_type".to_string(), json!(entity.entity_type));
+            properties.insert("handle".to_string(), json!(entity.handle));
+            let mut feature = json!({
+                "type": "Feature",
+                "geometry": geometry,
+                "properties": properties,
+            });
+            if let Some(handle) = &entity.handle {
+                feature["id"] = json!(handle);
+            }
+            Some(feature)
+        })
+        .collect();
+
+    json!({
+        "type": "FeatureCollection",
+        "features": features,
+    })
+}
+
+fn feature_geometry(data: &Geometry) -> Option<(Value, Map<String, Value>)> {
+    let point = |point: &Point3| Point {
+        x: point.x,
+        y: point.y,
+    };
+    let geometry = match data {
+        Geometry::Line(line) => line_string(&[point(&line.start), point(&line.end)]),
+        Geometry::Polyline(polyline) => {
+            let vertices: Vec<Point> = polyline.vertices.iter().map(point).collect();
+            let points = flatten(&vertices, &polyline.bulges, polyline.closed);
+            if polyline.closed && points.len() >= 3 {
+                polygon(points)
+            } else {
+                line_string(&points)
+            }
+        }
+        Geometry::Arc(arc) => {
+            let sweep = match (arc.end_angle - arc.start_angle).rem_euclid(360.0) {
+                0.0 => 360.0,
+                sweep => sweep,
+            };
+            line_string(&arc_points(
+                point(&arc.center),
+                arc.radius,
+                arc.start_angle.to_radians(),
+                sweep.to_radians(),
+            ))
+        }
+        Geometry::Circle(circle) => {
+            let mut ring = arc_points(point(&circle.center), circle.radius, 0.0, TAU);
+            ring.pop();
+            polygon(ring)
+        }
+        Geometry::Wipeout(wipeout) => polygon(wipeout.boundary.iter().map(point).collect()),
+        Geometry::Hatch(hatch) => {
+            let mut properties = Map::new();
+            properties.insert("pattern".to_string(), json!(hatch.pattern_name));
+            properties.insert("solid".to_string(), json!(hatch.solid));
+            return Some((hatch_polygons(hatch)?, properties));
+        }
+        Geometry::Text(text) => {
+            return Some(text_point(
+                &text.position,
+                plain_text(&text.text),
+                text.height,
+                text.rotation,
+                &text.style,
+            ))
+        }
+        Geometry::MText(text) => {
+            return Some(text_point(
+                &text.position,
+                plain_text(&text.text),
+                text.height,
+                text.rotation,
+                &text.style,
+            ))
+        }
+        _ => return None,
+    };
+    Some((geometry, Map::new()))
+}
+
+fn text_point(
+    position: &Point3,
+    text: String,
+    height: f64,
+    rotation: f64,
+    style: &str,
+) -> (Value, Map<String, Value>) {
+    let mut properties = Map::new();
+    properties.insert("text".to_string(), json!(text));
+    properties.insert("height".to_string(), json!(height));
+    properties.insert("rotation".to_string(), json!(rotation));
+    properties.insert("style".to_string(), json!(style));
+    (
+        json!({
+            "type": "Point",
+            "coordinates": [position.x, position.y],
+        }),
+        properties,
+    )
+}
+
+fn line_string(points: &[Point]) -> Value {
+    json!({
+        "type": "LineString",
+        "coordinates": positions(points),
+    })
+}
+
+/// A Polygon of one ring.
+fn polygon(ring: Vec<Point>) -> Value {
+    json!({
+        "type": "Polygon",
+        "coordinates": [closed_ring(ring, true)],
+    })
+}
+
+/// The positions of a ring, counterclockwise for outer rings and clockwise
+/// for holes as RFC 7946 asks, closed by repeating its first position.
+fn closed_ring(mut ring: Vec<Point>, outer: bool) -> Vec<[f64; 2]> {
+    if (signed_area(&ring) < 0.0) == outer {
+        ring.reverse();
+    }
+    if let Some(first) = ring.first().copied() {
+        ring.push(first);
+    }
+    positions(&ring)
+}
+
+/// The loops of a hatch as polygons: loops inside an even number of
+/// others are outer rings, the others holes in the smallest outer ring
+/// around them. Loops the hatch style does not fill around are left out.
+/// `None` without a loop of three points.
+fn hatch_polygons(hatch: &Hatch) -> Option<Value> {
+    let rings: Vec<Vec<Point>> = hatch
+        .loops
+        .iter()
+        .map(|boundary| {
+            let vertices: Vec<Point> = boundary
+                .vertices
+                .iter()
+                .map(|vertex| Point {
+                    x: vertex.x,
+                    y: vertex.y,
+                })
+                .collect();
+            flatten(&vertices, &boundary.bulges, true)
+        })
+        .filter(|ring| ring.len() >= 3)
+        .collect();
+    let depths: Vec<usize> = rings
+        .iter()
+        .enumerate()
+        .map(|(index, ring)| {
+            rings
+                .iter()
+                .enumerate()
+                .filter(|(other, around)| *other != index && contains(around, ring[0]))
+                .count()
+        })
+        .collect();
+    // The outermost style fills up to the first islands; the ignore style
+    // fills the outer loops whole.
+    let max_depth = match hatch.style {
+        1 => 1,
+        2 => 0,
+        _ => usize::MAX,
+    };
+
+    let mut polygons: Vec<(usize, Vec<Vec<[f64; 2]>>)> = Vec::new();
+    for (index, ring) in rings.iter().enumerate() {
+        if depths[index].is_multiple_of(2) && depths[index] <= max_depth {
+            polygons.push((index, vec![closed_ring(ring.clone(), true)]));
+        }
+    }
+    for (index, ring) in rings.iter().enumerate() {
+        if depths[index].is_multiple_of(2) || depths[index] > max_depth {
+            continue;
+        }
+        let outer = polygons.iter_mut().find(|(outer, _)| {
+            depths[*outer] + 1 == depths[index] && contains(&rings[*outer], ring[0])
+        });
+        if let Some((_, polygon)) = outer {
+            polygon.push(closed_ring(ring.clone(), false));
+        }
+    }
+
+    match polygons.len() {
+        0 => None,
+        1 => Some(json!({
+            "type": "Polygon",
+            "coordinates": polygons.remove(0).1,
+        })),
+        _ => Some(json!({
+            "type": "MultiPolygon",
+            "coordinates": polygons.into_iter().map(|(_, polygon)| polygon).collect::<Vec<_>>(),
+        })),
+    }
+}
+
+/// Whether a point lies inside a ring, by the even-odd rule.
+fn contains(ring: &[Point], point: Point) -> bool {
+    segments(ring, &[], true)
+        .iter()
+        .filter(|(from, to, _)| {
+            (from.y > point.y) != (to.y > point.y)
+                && point.x < from.x + (point.y - from.y) / (to.y - from.y) * (to.x - from.x)
+        })
+        .count()
+        % 2
+        == 1
+}
+
+fn positions(points: &[Point]) -> Vec<[f64; 2]> {
+    points.iter().map(|point| [point.x, point.y]).collect()
+}
+
+fn signed_area(ring: &[Point]) -> f64 {
+    segments(ring, &[], true)
+        .iter()
+        .map(|(from, to, _)| from.x * to.y - to.x * from.y)
+        .sum::<f64>()
+        / 2.0
+}
+
+/// The points of a path with its arc segments flattened. A closed path
+/// does not repeat its first point.
+fn flatten(vertices: &[Point], bulges: &[f64], closed: bool) -> Vec<Point> {
+    let mut points: Vec<Point> = vertices.first().copied().into_iter().collect();
+    for (from, to, bulge) in segments(vertices, bulges, closed) {
+        if bulge != 0.0 {
+            let (center, radius, start, sweep) = bulge_arc(from, to, bulge);
+            let arc = arc_points(center, radius, start, sweep);
+            points.extend(&arc[1..arc.len() - 1]);
+        }
+        points.push(to);
+    }
+    if closed && points.len() > 1 {
+        points.pop();
+    }
+    points
+}
+
+/// Points along an arc from a start angle through a signed sweep, both in
+/// radians, including both ends.
+fn arc_points(center: Point, radius: f64, start: f64, sweep: f64) -> Vec<Point> {
+    // Less a hair, so that a quarter turn is not split into one more
+    // segment for rounding.
+    let count = (sweep.abs() / TAU * SEGMENTS_PER_TURN - 1e-9)
+        .ceil()
+        .max(1.0) as usize;
+    (0..=count)
+        .map(|index| {
+            let (sin, cos) = (start + sweep * index as f64 / count as f64).sin_cos();
+            Point {
+                x: center.x + radius * cos,
+                y: center.y + radius * sin,
+            }
+        })
+        .collect()
+}
+
+/// A drawing of the features of a FeatureCollection, in model space, with
+/// a layer for each value of the `layer_property` property. LineStrings
+/// become lines (two positions) or polylines, each ring of a Polygon a
+/// closed polyline, and Points with a `text` property text, with its
+/// `height` and `rotation` if given; other Points, and features without a
+/// geometry, are left out.
+pub fn import_feature_collection(
+    collection: &Value,
+    layer_property: &str,
+) -> Result<ParsedDrawing> {
+    if collection["type"] != "FeatureCollection" {
+        return Err(invalid("expected a FeatureCollection"));
+    }
+    let features = collection["features"]
+        .as_array()
+        .ok_or_else(|| invalid("a FeatureCollection needs a features array"))?;
+
+    let mut drawing = ParsedDrawing::default();
+    layer_entry(&mut drawing, DEFAULT_LAYER);
+    let mut draw_order = 0;
+    for (index, feature) in features.iter().enumerate() {
+        let properties = feature["properties"].as_object();
+        let layer = properties
+            .and_then(|properties| properties.get(layer_property))
+            .map(layer_name)
+            .unwrap_or_else(|| DEFAULT_LAYER.to_string());
+
+        let mut geometries = Vec::new();
+        feature_geometries(&feature["geometry"], properties, &mut geometries)
+            .map_err(|e| invalid(&format!("feature {}: {}", index, e)))?;
+
+        let entities = &mut layer_entry(&mut drawing, &layer).entities;
+        for data in geometries {
+            entities.push(entity(data, draw_order));
+            draw_order += 1;
+        }
+    }
+    Ok(drawing)
+}
+
+fn invalid(message: &str) -> AppError {
+    AppError::InvalidGeoJson(message.to_string())
+}
+
+/// The layer of a name. Layer names are stored case-insensitively, so
+/// names differing only in case share the layer of the first one seen.
+fn layer_entry<'a>(drawing: &'a mut ParsedDrawing, name: &str) -> &'a mut ParsedLayer {
+    let lowercase = name.to_lowercase();
+    let key = drawing
+        .layers
+        .keys()
+        .find(|key| key.to_lowercase() == lowercase)
+        .cloned()
+        .unwrap_or_else(|| name.to_string());
+    drawing.layers.entry(key).or_insert_with(|| ParsedLayer {
+        input: CreateLayerInput {
+            name: name.to_string(),
+            is_locked: false,
+            is_visible: true,
+            color: Some("7".to_string()),
+            line_type: Some("CONTINUOUS".to_string()),
+            line_weight: None,
+        },
+        entities: Vec::new(),
+    })
+}
+
+/// A layer name for a property value, with the characters layer names
+/// cannot hold replaced.
+fn layer_name(value: &Value) -> String {
+    let name = match value {
+        Value::Null => return DEFAULT_LAYER.to_string(),
+        Value::String(name) => name.trim().to_string(),
+        value => value.to_string(),
+    };
+    if name.is_empty() {
+        return DEFAULT_LAYER.to_string();
+    }
+    name.chars()
+        .map(|ch| match ch {
+            '<' | '>' | '/' | '\\' | '"' | ':' | ';' | '?' | '*' | '|' | '=' | '`' => '_',
+            ch if ch.is_control() => '_',
+            ch => ch,
+        })
+        .collect()
+}
+
+fn feature_geometries(
+    geometry: &Value,
+    properties: Option<&Map<String, Value>>,
+    geometries: &mut Vec<Geometry>,
+) -> std::result::Result<(), String> {
+    if geometry.is_null() {
+        return Ok(());
+    }
+    let coordinates = &geometry["coordinates"];
+    let kind = geometry["type"].as_str().unwrap_or_default();
+    match kind {
+        "Point" => geometries.extend(point_text(position(coordinates)?, properties)),
+        "MultiPoint" => {
+            for position_value in array(coordinates)? {
+                geometries.extend(point_text(position(position_value)?, properties));
+            }
+        }
+        "LineString" => geometries.push(line_geometry(positions_of(coordinates)?, false)?),
+        "MultiLineString" => {
+            for line in array(coordinates)? {
+                geometries.push(line_geometry(positions_of(line)?, false)?);
+            }
+        }
+        "Polygon" => polygon_geometries(coordinates, geometries)?,
+        "MultiPolygon" => {
+            for polygon in array(coordinates)? {
+                polygon_geometries(polygon, geometries)?;
+            }
+        }
+        "GeometryCollection" => {
+            for geometry in array(&geometry["geometries"])? {
+                feature_geometries(geometry, properties, geometries)?;
+            }
+        }
+        "" => return Err("a geometry needs a type".to_string()),
+        kind => return Err(format!("unknown geometry type {}", kind)),
+    }
+    Ok(())
+}
+
+fn polygon_geometries(
+    coordinates: &Value,
+    geometries: &mut Vec<Geometry>,
+) -> std::result::Result<(), String> {
+    for ring in array(coordinates)? {
+        let mut ring = positions_of(ring)?;
+        if ring.len() > 1 && ring.first() == ring.last() {
+            ring.pop();
+        }
+        geometries.push(line_geometry(ring, true)?);
+    }
+    Ok(())
+}
+
+fn point_text(position: Point3, properties: Option<&Map<String, Value>>) -> Option<Geometry> {
+    let properties = properties?;
+    let text = match properties.get("text")? {
+        Value::String(text) => text.clone(),
+        Value::Null => return None,
+        value => value.to_string(),
+    };
+    let number = |name: &str| properties.get(name).and_then(Value::as_f64);
+    Some(Geometry::Text(Text {
+        position,
+        text,
+        height: number("height")
+            .filter(|height| *height > 0.0)
+            .unwrap_or(DEFAULT_TEXT_HEIGHT),
+        style: "Standard".to_string(),
+        width_factor: 1.0,
+        oblique_angle: 0.0,
+        rotation: number("rotation").unwrap_or(0.0),
+        properties: Default::default(),
+    }))
+}
+
+fn line_geometry(vertices: Vec<Point3>, closed: bool) -> std::result::Result<Geometry, String> {
+    match vertices.len() {
+        0 | 1 => Err("a line needs at least two positions".to_string()),
+        2 if !closed => Ok(Geometry::Line(Line {
+            start: vertices[0],
+            end: vertices[1],
+            properties: Default::default(),
+        })),
+        _ => Ok(Geometry::Polyline(Polyline {
+            vertices,
+            closed,
+            ..Default::default()
+        })),
+    }
+}
+
+fn array(value: &Value) -> std::result::Result<&Vec<Value>, String> {
+    value
+        .as_array()
+        .ok_or_else(|| format!("expected an array, not {}", value))
+}
+
+fn positions_of(value: &Value) -> std::result::Result<Vec<Point3>, String> {
+    array(value)?.iter().map(position).collect()
+}
+
+/// `[x, y]` or `[x, y, z]`.
+fn position(value: &Value) -> std::result::Result<Point3, String> {
+    let numbers: Option<Vec<f64>> = array(value)?.iter().map(Value::as_f64).collect();
+    match numbers.as_deref() {
+        Some([x, y]) => Ok(Point3::new(*x, *y, 0.0)),
+        Some([x, y, z, ..]) => Ok(Point3::new(*x, *y, *z)),
+        _ => Err(format!("expected a position, not {}", value)),
+    }
+}
+
+fn entity(data: Geometry, draw_order: u64) -> CreateEntityInput {
+    let points: Vec<Point3> = match &data {
+        Geometry::Line(line) => vec![line.start, line.end],
+        Geometry::Polyline(polyline) => polyline.vertices.clone(),
+        Geometry::Text(text) => vec![text.position],
+        _ => Vec::new(),
+    };
+    let bound = |value: fn(&Point3) -> f64, pick: fn(f64, f64) -> f64| {
+        points.iter().map(value).reduce(pick).unwrap_or(0.0)
+    };
+    CreateEntityInput {
+        min_x: bound(|point| point.x, f64::min),
+        min_y: bound(|point| point.y, f64::min),
+        max_x: bound(|point| point.x, f64::max),
+        max_y: bound(|point| point.y, f64::max),
+        min_z: bound(|point| point.z, f64::min),
+        max_z: bound(|point| point.z, f64::max),
+        data,
+        layout: MODEL_LAYOUT.to_string(),
+        draw_order,
+        ..Default::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::geometry::{Arc, Circle, HatchLoop};
+    use dxf_parser::{parse_dxf_bytes, write_dxf, DxfVersion, ParseMode};
+
+    fn layer(id: u64, name: &str) -> Layer {
+        Layer {
+            id,
+            file_id: 1,
+            name: name.to_string(),
+            is_locked: false,
+            is_visible: true,
+            color: Some("7".to_string()),
+            line_type: None,
+            line_weight: None,
+            min_x: None,
+            min_y: None,
+            max_x: None,
+            max_y: None,
+            min_z: None,
+            max_z: None,
+            created_at: chrono::Utc::now(),
+            updated_at: chrono::Utc::now(),
+        }
+    }
+
+    fn entity(layer_id: u64, handle: &str, data: Geometry) -> Entity {
+        Entity {
+            id: 1,
+            layer_id,
+            layout: MODEL_LAYOUT.to_string(),
+            handle: Some(handle.to_string()),
+            owner_handle: None,
+            extension_dictionary: None,
+            draw_order: 0,
+            entity_type: data.entity_type().to_string(),
+            data,
+            xdata: None,
+            min_x: 0.0,
+            min_y: 0.0,
+            max_x: 0.0,
+            max_y: 0.0,
+            min_z: 0.0,
+            max_z: 0.0,
+            created_at: chrono::Utc::now(),
+            updated_at: chrono::Utc::now(),
+        }
+    }
+
+    fn polyline(vertices: &[(f64, f64)], bulges: Vec<f64>, closed: bool) -> Geometry {
+        Geometry::Polyline(Polyline {
+            vertices: vertices
+                .iter()
+                .map(|&(x, y)| Point3::new(x, y, 0.0))
+                .collect(),
+            bulges,
+            closed,
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn test_exports_entities_as_features() {
+        let layers = [layer(1, "WALLS"), layer(2, "NOTES")];
+        let entities = [
+            entity(
+                1,
+                "A1",
+                Geometry::Line(Line {
+                    start: Point3::new(0.0, 0.0, 0.0),
+                    end: Point3::new(10.0, 0.0, 0.0),
+                    properties: Default::default(),
+                }),
+            ),
+            // Clockwise, so the ring is turned around.
+            entity(
+                1,
+                "A2",
+                polyline(&[(0.0, 0.0), (0.0, 5.0), (5.0, 5.0)], Vec::new(), true),
+            ),
+            entity(
+                2,
+                "A3",
+                Geometry::Text(Text {
+                    position: Point3::new(1.0, 2.0, 0.0),
+                    text: "%%c50".to_string(),
+                    height: 2.5,
+                    style: "Standard".to_string(),
+                    width_factor: 1.0,
+                    oblique_angle: 0.0,
+                    rotation: 90.0,
+                    properties: Default::default(),
+                }),
+            ),
+            // Not on a requested layer.
+            entity(
+                3,
+                "A4",
+                Geometry::Circle(Circle {
+                    center: Point3::new(0.0, 0.0, 0.0),
+                    radius: 1.0,
+                    properties: Default::default(),
+                }),
+            ),
+        ];
+
+        let collection = feature_collection(&layers, &entities);
+        let features = collection["features"].as_array().unwrap();
+
+        assert_eq!(collection["type"], "FeatureCollection");
+        assert_eq!(features.len(), 3);
+        assert_eq!(
+            features[0],
+            json!({
+                "type": "Feature",
+                "id": "A1",
+                "geometry": {"type": "LineString", "coordinates": [[0.0, 0.0], [10.0, 0.0]]},
+                "properties": {"layer": "WALLS", "entity_type": "LINE", "handle": "A1"},
+            })
+        );
+        assert_eq!(
+            features[1]["geometry"],
+            json!({
+                "type": "Polygon",
+                "coordinates": [[[5.0, 5.0], [0.0, 5.0], [0.0, 0.0], [5.0, 5.0]]],
+            })
+        );
+        assert_eq!(
+            features[2]["geometry"],
+            json!({"type": "Point", "coordinates": [1.0, 2.0]})
+        );
+        assert_eq!(features[2]["properties"]["text"], "⌀50");
+        assert_eq!(features[2]["properties"]["rotation"], 90.0);
+        assert_eq!(features[2]["properties"]["layer"], "NOTES");
+    }
+
+    #[test]
+    fn test_exports_hatches_as_polygons_with_holes() {
+        let boundary = |vertices: &[(f64, f64)], bulges: Vec<f64>| HatchLoop {
+            flags: 0,
+            vertices: vertices
+                .iter()
+                .map(|&(x, y)| Point3::new(x, y, 0.0))
+                .collect(),
+            bulges,
+        };
+        let square = |min: f64, max: f64| {
+            boundary(
+                &[(min, min), (max, min), (max, max), (min, max)],
+                Vec::new(),
+            )
+        };
+        let hatch = |style: i32| Hatch {
+            pattern_name: "SOLID".to_string(),
+            solid: true,
+            style,
+            loops: vec![
+                // A hole with an island in it, and a separate square.
+                square(0.0, 10.0),
+                boundary(&[(2.0, 5.0), (8.0, 5.0)], vec![1.0, 1.0]),
+                square(4.0, 6.0),
+                square(20.0, 30.0),
+            ],
+            ..Default::default()
+        };
+        let export = |style: i32| {
+            let entities = [entity(1, "H", Geometry::Hatch(hatch(style)))];
+            feature_collection(&[layer(1, "AREAS")], &entities)["features"][0].clone()
+        };
+
+        let feature = export(0);
+        assert_eq!(feature["geometry"]["type"], "MultiPolygon");
+        assert_eq!(feature["properties"]["pattern"], "SOLID");
+        assert_eq!(feature["properties"]["solid"], true);
+        let polygons = feature["geometry"]["coordinates"].as_array().unwrap();
+        assert_eq!(polygons.len(), 3);
+        let rings = polygons[0].as_array().unwrap();
+        assert_eq!(rings.len(), 2);
+        assert_eq!(rings[0][1], json!([10.0, 0.0]));
+        // The hole is a flattened circle, clockwise.
+        let hole: Vec<Point> = rings[1]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|position| Point {
+                x: position[0].as_f64().unwrap(),
+                y: position[1].as_f64().unwrap(),
+            })
+            .collect();
+        assert_eq!(hole.len(), 65);
+        assert!(signed_area(&hole) < 0.0);
+        assert_eq!(polygons[1].as_array().unwrap().len(), 1);
+        assert_eq!(polygons[2][0][0], json!([20.0, 20.0]));
+
+        // The outermost style leaves out the island, the ignore style the
+        // hole as well.
+        let feature = export(1);
+        let polygons = feature["geometry"]["coordinates"].as_array().unwrap();
+        assert_eq!(polygons.len(), 2);
+        assert_eq!(polygons[0].as_array().unwrap().len(), 2);
+        let feature = export(2);
+        let polygons = feature["geometry"]["coordinates"].as_array().unwrap();
+        assert_eq!(polygons.len(), 2);
+        assert_eq!(polygons[0].as_array().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_flattens_arcs() {
+        let layers = [layer(1, "0")];
+        let entities = [
+            entity(
+                1,
+                "B1",
+                Geometry::Arc(Arc {
+                    center: Point3::new(0.0, 0.0, 0.0),
+                    radius: 10.0,
+                    start_angle: 0.0,
+                    end_angle: 90.0,
+                    properties: Default::default(),
+                }),
+            ),
+            // A half circle bulging below the chord from (0, 0) to (20, 0).
+            entity(
+                1,
+                "B2",
+                polyline(&[(0.0, 0.0), (20.0, 0.0)], vec![1.0, 0.0], false),
+            ),
+        ];
+
+        let collection = feature_collection(&layers, &entities);
+        let arc = collection["features"][0]["geometry"]["coordinates"]
+            .as_array()
+            .unwrap();
+        let half_circle = collection["features"][1]["geometry"]["coordinates"]
+            .as_array()
+            .unwrap();
+
+        // A quarter of 64 segments per turn.
+        assert_eq!(arc.len(), 17);
+        assert_eq!(half_circle.len(), 33);
+        for position in half_circle {
+            let (x, y) = (position[0].as_f64().unwrap(), position[1].as_f64().unwrap());
+            assert!(((x - 10.0).hypot(y) - 10.0).abs() < 1e-9);
+            assert!(y <= 1e-9);
+        }
+        assert_eq!(half_circle[32], json!([20.0, 0.0]));
+    }
+
+    #[test]
+    fn test_imports_features_onto_layers_from_a_property() {
+        let collection = json!({
+            "type": "FeatureCollection",
+            "features": [
+                {
+                    "type": "Feature",
+                    "geometry": {"type": "LineString", "coordinates": [[0, 0], [10, 0, 2]]},
+                    "properties": {"zone": "A/1"},
+                },
+                {
+                    "type": "Feature",
+                    "geometry": {
+                        "type": "Polygon",
+                        "coordinates": [
+                            [[0, 0], [10, 0], [10, 10], [0, 0]],
+                            [[2, 1], [8, 1], [8, 7], [2, 1]],
+                        ],
+                    },
+                    "properties": {"zone": 7},
+                },
+                {
+                    "type": "Feature",
+                    "geometry": {"type": "Point", "coordinates": [5, 5]},
+                    "properties": {"zone": "A/1", "text": "Plot 7", "height": 4},
+                },
+                {
+                    "type": "Feature",
+                    "geometry": {"type": "Point", "coordinates": [1, 1]},
+                    "properties": null,
+                },
+                {"type": "Feature", "geometry": null, "properties": {}},
+            ],
+        });
+
+        let drawing = import_feature_collection(&collection, "zone").unwrap();
+        let mut names: Vec<&String> = drawing.layers.keys().collect();
+        names.sort();
+        assert_eq!(names, ["0", "7", "A_1"]);
+        assert!(drawing.layers["0"].entities.is_empty());
+
+        let zone_a = &drawing.layers["A_1"].entities;
+        assert_eq!(zone_a.len(), 2);
+        assert_eq!(zone_a[0].entity_type(), "LINE");
+        assert_eq!(zone_a[0].max_z, 2.0);
+        let Geometry::Text(text) = &zone_a[1].data else {
+            panic!("expected text, got {:?}", zone_a[1].data);
+        };
+        assert_eq!((text.text.as_str(), text.height), ("Plot 7", 4.0));
+        assert_eq!(zone_a[1].draw_order, 3);
+
+        let rings = &drawing.layers["7"].entities;
+        assert_eq!(rings.len(), 2);
+        let Geometry::Polyline(outer) = &rings[0].data else {
+            panic!("expected a polyline, got {:?}", rings[0].data);
+        };
+        assert!(outer.closed);
+        assert_eq!(outer.vertices.len(), 3);
+    }
+
+    #[test]
+    fn test_merges_layers_differing_only_in_case() {
+        let line = |layer: &str| {
+            json!({
+                "type": "Feature",
+                "geometry": {"type": "LineString", "coordinates": [[0, 0], [10, 0]]},
+                "properties": {"layer": layer},
+            })
+        };
+        let collection = json!({
+            "type": "FeatureCollection",
+            "features": [line("Road"), line("ROAD"), line("road")],
+        });
+
+        let drawing = import_feature_collection(&collection, "layer").unwrap();
+        let mut names: Vec<&String> = drawing.layers.keys().collect();
+        names.sort();
+        assert_eq!(names, ["0", "Road"]);
+        assert_eq!(drawing.layers["Road"].input.name, "Road");
+        assert_eq!(drawing.layers["Road"].entities.len(), 3);
+    }
+
+    #[test]
+    fn test_imported_drawing_writes_as_dxf() {
+        let collection = json!({
+            "type": "FeatureCollection",
+            "features": [{
+                "type": "Feature",
+                "geometry": {
+                    "type": "MultiLineString",
+                    "coordinates": [[[0, 0], [1, 1], [2, 0]], [[0, 5], [5, 5]]],
+                },
+                "properties": {"layer": "ROADS"},
+            }],
+        });
+
+        let drawing = import_feature_collection(&collection, "layer").unwrap();
+        let dxf = write_dxf(&drawing, DxfVersion::R2018);
+        let parsed = parse_dxf_bytes(dxf.as_bytes(), ParseMode::Strict).unwrap();
+
+        let types: Vec<&str> = parsed.layers["ROADS"]
+            .entities
+            .iter()
+            .map(|entity| entity.entity_type())
+            .collect();
+        assert_eq!(types, ["POLYLINE", "LINE"]);
+    }
+
+    #[test]
+    fn test_rejects_what_is_not_a_feature_collection() {
+        let errors = [
+            json!({"type": "Feature", "geometry": null}),
+            json!({"type": "FeatureCollection"}),
+            json!({
+                "type": "FeatureCollection",
+                "features": [{"type": "Feature", "geometry": {"type": "LineString", "coordinates": [[0, 0]]}}],
+            }),
+            json!({
+                "type": "FeatureCollection",
+                "features": [{"type": "Feature", "geometry": {"type": "Curve", "coordinates": []}}],
+            }),
+        ];
+        for collection in errors {
+            assert!(
+                matches!(
+                    import_feature_collection(&collection, "layer"),
+                    Err(AppError::InvalidGeoJson(_))
+                ),
+                "{collection}"
+            );
+        }
+    }
+}
@@ -1,6 +1,8 @@
 pub mod config;
 pub mod error;
+pub mod geojson;
 pub mod models;
+pub mod render;
 pub mod routes;
 pub mod services;
 
@@ -8,6 +10,7 @@ use axum::{
     routing::{get, post},
     Router,
 };
+use dxf_parser::EntityHandlerRegistry;
 use routes::AppState;
 use services::FileStorage;
 use sqlx::mysql::MySqlPoolOptions;
@@ -16,6 +19,15 @@ use tower_http::limit::RequestBodyLimitLayer;
 use tower_http::trace::TraceLayer;
 
 pub async fn create_app(config: config::Config) -> anyhow::Result<Router> {
+    create_app_with_handlers(config, EntityHandlerRegistry::default()).await
+}
+
+/// Like [`create_app`], with the entity handlers the parser uses, e.g. the
+/// default ones plus handlers for vendor entities.
+pub async fn create_app_with_handlers(
+    config: config::Config,
+    entity_handlers: EntityHandlerRegistry,
+) -> anyhow::Result<Router> {
     let db = MySqlPoolOptions::new()
         .max_connections(5)
         .connect(&config.database_url)
@@ -29,15 +41,101 @@ pub async fn create_app(config: config::Config) -> anyhow::Result<Router> {
         db,
         file_storage,
         max_file_size: config.max_file_size,
+        entity_handlers: Arc::new(entity_handlers),
     };
 
     let app = Router::new()
         .route("/health", get(routes::health_check))
         .route("/api/projects/:project_id/files", post(routes::upload_file))
+        .route(
+            "/api/projects/:project_id/files/import.geojson",
+            post(routes::import_geojson),
+        )
+        .route(
+            "/api/projects/:project_id/files/:file_id/content",
+            get(routes::get_file_content),
+        )
         .route(
             "/api/projects/:project_id/files/:file_id/parse",
-            post(routes::parse_file),
+            post(routes::parse_file).get(routes::get_parse_status),
+        )
+        .route(
+            "/api/projects/:project_id/files/:file_id/layouts",
+            get(routes::list_layouts),
+        )
+        .route(
+            "/api/projects/:project_id/files/:file_id/layouts/:layout/sheet",
+            get(routes::get_sheet),
+        )
+        .route(
+            "/api/projects/:project_id/files/:file_id/objects",
+            get(routes::list_named_objects),
+        )
+        .route(
+            "/api/projects/:project_id/files/:file_id/groups",
+            get(routes::list_groups),
+        )
+        .route(
+            "/api/projects/:project_id/files/:file_id/groups/:name/entities",
+            get(routes::get_group_entities),
+        )
+        .route(
+            "/api/projects/:project_id/files/:file_id/raw-records",
+            get(routes::list_raw_records),
+        )
+        .route(
+            "/api/projects/:project_id/files/:file_id/references",
+            get(routes::list_references),
+        )
+        .route(
+            "/api/projects/:project_id/files/:file_id/tables",
+            get(routes::list_tables),
+        )
+        .route(
+            "/api/projects/:project_id/files/:file_id/tables/:handle",
+            get(routes::get_table),
+        )
+        .route(
+            "/api/projects/:project_id/files/:file_id/linetypes",
+            get(routes::list_linetypes),
+        )
+        .route(
+            "/api/projects/:project_id/files/:file_id/text-styles",
+            get(routes::list_text_styles),
+        )
+        .route(
+            "/api/projects/:project_id/files/:file_id/dim-styles",
+            get(routes::list_dim_styles),
+        )
+        .route(
+            "/api/projects/:project_id/files/:file_id/diagnostics",
+            get(routes::list_diagnostics),
+        )
+        .route(
+            "/api/projects/:project_id/files/:file_id/export.dxf",
+            get(routes::export_dxf),
+        )
+        .route(
+            "/api/projects/:project_id/files/:file_id/export.svg",
+            get(routes::export_svg),
+        )
+        .route(
+            "/api/projects/:project_id/files/:file_id/export.geojson",
+            get(routes::export_geojson),
+        )
+        .route(
+            "/api/projects/:project_id/files/:file_id/plot.pdf",
+            get(routes::plot_pdf),
+        )
+        .route(
+            "/api/projects/:project_id/files/:file_id/thumbnail",
+            get(routes::get_thumbnail),
+        )
+        .route(
+            "/api/projects/:project_id/files/:file_id/entities/:handle",
+            get(routes::get_entity_by_handle),
         )
+        .route("/api/layers/:layer_id/entities", get(routes::list_entities))
         .layer(RequestBodyLimitLayer::new(config.max_file_size))
         .layer(TraceLayer::new_for_http())
         .with_state(state);
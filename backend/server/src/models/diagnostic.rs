@@ -0,0 +1,16 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct Diagnostic {
+    pub id: u64,
+    pub file_id: u64,
+    pub line: u64,
+    pub group_code: Option<i32>,
+    pub entity_type: Option<String>,
+    pub entity_handle: Option<String>,
+    pub severity: String,
+    pub message: String,
+    pub created_at: DateTime<Utc>,
+}
@@ -1,70 +1,12 @@
-use chrono::{DateTime, Utc};
-use serde::{Deserialize, Serialize};
-use serde_json::Value;
-use sqlx::FromRow;
+use crate::models::{BoundingBox, ElevationRange};
 
-#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
-pub struct Entity {
-    pub id: u64,
-    pub layer_id: u64,
-    pub entity_type: String,
-    pub data: Value,
-    pub min_x: f64,
-    pub min_y: f64,
-    pub max_x: f64,
-    pub max_y: f64,
-    pub created_at: DateTime<Utc>,
-    pub updated_at: DateTime<Utc>,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct CreateEntityInput {
-    pub entity_type: String,
-    pub data: Value,
-    pub min_x: f64,
-    pub min_y: f64,
-    pub max_x: f64,
-    pub max_y: f64,
-}
-
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct BoundingBox {
-    pub min_x: f64,
-    pub min_y: f64,
-    pub max_x: f64,
-    pub max_y: f64,
-}
-
-impl BoundingBox {
-    pub fn new(min_x: f64, min_y: f64, max_x: f64, max_y: f64) -> Self {
-        Self {
-            min_x,
-            min_y,
-            max_x,
-            max_y,
-        }
-    }
-
-    pub fn from_point(x: f64, y: f64) -> Self {
-        Self {
-            min_x: x,
-            min_y: y,
-            max_x: x,
-            max_y: y,
-        }
-    }
-
-    pub fn expand(&mut self, x: f64, y: f64) {
-        self.min_x = self.min_x.min(x);
-        self.min_y = self.min_y.min(y);
-        self.max_x = self.max_x.max(x);
-        self.max_y = self.max_y.max(y);
-    }
+pub use dxf_parser::models::Entity;
 
-    pub fn merge(&mut self, other: &BoundingBox) {
-        self.min_x = self.min_x.min(other.min_x);
-        self.min_y = self.min_y.min(other.min_y);
-        self.max_x = self.max_x.max(other.max_x);
-        self.max_y = self.max_y.max(other.max_y);
-    }
+/// Which of a layer's entities an entity query returns. Entities match when
+/// they are in `layout` and their extents overlap `bbox` and `elevation`.
+#[derive(Debug, Clone)]
+pub struct EntityFilter {
+    pub layout: String,
+    pub bbox: Option<BoundingBox>,
+    pub elevation: Option<ElevationRange>,
 }
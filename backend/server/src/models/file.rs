@@ -13,6 +13,7 @@ pub struct File {
     pub encoding: Option<String>,
     pub parse_status: Option<String>,
     pub parse_error: Option<String>,
+    pub linetype_scale: Option<f64>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -0,0 +1,32 @@
+use crate::models::CreateLayoutInput;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct Layout {
+    pub id: u64,
+    pub file_id: u64,
+    pub name: String,
+    pub block_name: Option<String>,
+    pub tab_order: i32,
+    pub min_x: f64,
+    pub min_y: f64,
+    pub max_x: f64,
+    pub max_y: f64,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<Layout> for CreateLayoutInput {
+    fn from(layout: Layout) -> Self {
+        Self {
+            name: layout.name,
+            block_name: layout.block_name,
+            tab_order: layout.tab_order,
+            min_x: layout.min_x,
+            min_y: layout.min_y,
+            max_x: layout.max_x,
+            max_y: layout.max_y,
+        }
+    }
+}
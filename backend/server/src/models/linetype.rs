@@ -0,0 +1,28 @@
+use crate::models::CreateLinetypeInput;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::FromRow;
+
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct Linetype {
+    pub id: u64,
+    pub file_id: u64,
+    pub name: String,
+    pub description: Option<String>,
+    pub pattern_length: f64,
+    pub elements: Value,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<Linetype> for CreateLinetypeInput {
+    fn from(linetype: Linetype) -> Self {
+        Self {
+            name: linetype.name,
+            description: linetype.description,
+            pattern_length: linetype.pattern_length,
+            elements: serde_json::from_value(linetype.elements).unwrap_or_default(),
+        }
+    }
+}
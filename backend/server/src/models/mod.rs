@@ -1,9 +1,40 @@
+pub mod diagnostic;
 pub mod entity;
 pub mod file;
 pub mod layer;
+pub mod layout;
+pub mod linetype;
+pub mod object;
+pub mod parse_job;
 pub mod project;
+pub mod raw_record;
+pub mod reference;
+pub mod sheet;
+pub mod style;
+pub mod table;
+pub mod unsupported;
 
-pub use entity::{BoundingBox, CreateEntityInput, Entity};
+pub use diagnostic::Diagnostic;
+pub use dxf_parser::models::geometry;
+pub use dxf_parser::models::{
+    BoundingBox, CreateDiagnosticInput, CreateDimStyleInput, CreateEntityGroupInput,
+    CreateEntityInput, CreateExternalReferenceInput, CreateLayerInput, CreateLayoutInput,
+    CreateLinetypeInput, CreateNamedObjectInput, CreateRawRecordInput, CreateTextStyleInput,
+    CreateUnsupportedItemInput, DiagnosticSeverity, ElevationRange, EmbeddedElement, Geometry,
+    LinetypeElement, LinetypeElementKind, Point, Point3, RawSection, ReferenceKind,
+    UnsupportedKind, GEOMETRY_VERSION, MODEL_LAYOUT,
+};
+pub use entity::{Entity, EntityFilter};
 pub use file::{File, FileUploadResponse};
-pub use layer::{CreateLayerInput, Layer};
+pub use layer::Layer;
+pub use layout::Layout;
+pub use linetype::Linetype;
+pub use object::{EntityGroup, NamedObject};
+pub use parse_job::ParseJob;
 pub use project::Project;
+pub use raw_record::RawRecord;
+pub use reference::ExternalReference;
+pub use sheet::{SheetComposition, SheetViewport};
+pub use style::{DimStyle, TextStyle};
+pub use table::{CellMerge, TableGrid};
+pub use unsupported::UnsupportedItem;
@@ -0,0 +1,53 @@
+use crate::models::{CreateEntityGroupInput, CreateNamedObjectInput};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::FromRow;
+
+/// An object reachable from the root dictionary of the OBJECTS section.
+/// `path` joins the dictionary entry names from the root, e.g.
+/// `ACAD_GROUP/DOORS` or `ACAD_LAYOUT/Layout1`.
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct NamedObject {
+    pub id: u64,
+    pub file_id: u64,
+    pub path: String,
+    pub handle: String,
+    pub object_type: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// A GROUP object: a named set of entities. `members` holds entity handles.
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct EntityGroup {
+    pub id: u64,
+    pub file_id: u64,
+    pub name: String,
+    pub handle: String,
+    pub description: Option<String>,
+    pub is_selectable: bool,
+    pub members: Value,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<NamedObject> for CreateNamedObjectInput {
+    fn from(object: NamedObject) -> Self {
+        Self {
+            path: object.path,
+            handle: object.handle,
+            object_type: object.object_type,
+        }
+    }
+}
+
+impl From<EntityGroup> for CreateEntityGroupInput {
+    fn from(group: EntityGroup) -> Self {
+        Self {
+            name: group.name,
+            handle: group.handle,
+            description: group.description,
+            is_selectable: group.is_selectable,
+            members: serde_json::from_value(group.members).unwrap_or_default(),
+        }
+    }
+}
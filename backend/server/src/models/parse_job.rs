@@ -0,0 +1,27 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+/// Progress of the most recent parse of a file. Updated from outside the
+/// transaction that writes the parse results, so it is visible while the
+/// parse is still running.
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct ParseJob {
+    pub file_id: u64,
+    pub mode: String,
+    pub bytes_total: Option<u64>,
+    pub bytes_read: u64,
+    pub entities_parsed: u64,
+    pub section: Option<String>,
+    pub started_at: DateTime<Utc>,
+    pub finished_at: Option<DateTime<Utc>>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl ParseJob {
+    /// Share of the input consumed, from 0 to 100.
+    pub fn percent(&self) -> Option<f64> {
+        let total = self.bytes_total.filter(|total| *total > 0)?;
+        Some((self.bytes_read as f64 / total as f64 * 100.0).min(100.0))
+    }
+}
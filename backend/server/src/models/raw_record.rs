@@ -0,0 +1,40 @@
+use crate::models::{CreateRawRecordInput, RawSection};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+/// An entity, object or block definition the parser does not read, kept verbatim so
+/// that a DXF writer can emit it again unchanged. `content` holds the
+/// record's group code / value lines in ASCII DXF form, starting with its
+/// `0` group.
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct RawRecord {
+    pub id: u64,
+    pub file_id: u64,
+    pub section: String,
+    pub record_type: String,
+    pub handle: Option<String>,
+    pub owner_handle: Option<String>,
+    pub layout: Option<String>,
+    pub position: u64,
+    pub content: String,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<RawRecord> for CreateRawRecordInput {
+    fn from(record: RawRecord) -> Self {
+        Self {
+            section: match record.section.as_str() {
+                "objects" => RawSection::Objects,
+                "blocks" => RawSection::Blocks,
+                _ => RawSection::Entities,
+            },
+            record_type: record.record_type,
+            handle: record.handle,
+            owner_handle: record.owner_handle,
+            layout: record.layout,
+            position: record.position,
+            content: record.content,
+        }
+    }
+}
@@ -0,0 +1,46 @@
+use crate::models::{CreateExternalReferenceInput, ReferenceKind};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+/// An IMAGEDEF or underlay definition object: the path of a raster image,
+/// PDF, DWF or DGN file referenced by IMAGE and underlay entities.
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct ExternalReference {
+    pub id: u64,
+    pub file_id: u64,
+    pub handle: String,
+    pub kind: String,
+    pub path: String,
+    pub name: Option<String>,
+    pub width_px: Option<f64>,
+    pub height_px: Option<f64>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl ExternalReference {
+    /// The file name at the end of the stored path, which may use either
+    /// Windows or POSIX separators.
+    pub fn file_name(&self) -> &str {
+        self.path.rsplit(['/', '\\']).next().unwrap_or(&self.path)
+    }
+}
+
+impl From<ExternalReference> for CreateExternalReferenceInput {
+    fn from(reference: ExternalReference) -> Self {
+        let kind = match reference.kind.as_str() {
+            "pdf" => ReferenceKind::Pdf,
+            "dwf" => ReferenceKind::Dwf,
+            "dgn" => ReferenceKind::Dgn,
+            _ => ReferenceKind::Image,
+        };
+        Self {
+            handle: reference.handle,
+            kind,
+            path: reference.path,
+            name: reference.name,
+            width_px: reference.width_px,
+            height_px: reference.height_px,
+        }
+    }
+}
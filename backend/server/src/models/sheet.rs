@@ -0,0 +1,87 @@
+use crate::models::{BoundingBox, Entity, Geometry, Layout, Point};
+use serde::{Deserialize, Serialize};
+
+/// A paper space layout and the model space regions its viewports show.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SheetComposition {
+    pub layout: Layout,
+    pub viewports: Vec<SheetViewport>,
+}
+
+/// Where a VIEWPORT places a region of model space on the sheet.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SheetViewport {
+    pub entity_id: u64,
+    pub handle: Option<String>,
+    pub viewport_id: i32,
+    /// The viewport window on the sheet, in paper units.
+    pub paper: BoundingBox,
+    /// Model space point shown at the center of the window.
+    pub model_center: Point,
+    pub model_width: f64,
+    pub model_height: f64,
+    /// Rotation of the view in degrees, counterclockwise.
+    pub twist_angle: f64,
+    /// Axis-aligned model space extent of the (possibly twisted) region,
+    /// for selecting the entities to draw.
+    pub model_bounds: BoundingBox,
+    /// Paper units per model unit.
+    pub scale: f64,
+    /// False when the view direction is not the Z axis; the region is then
+    /// only approximate.
+    pub plan_view: bool,
+    pub frozen_layers: Vec<String>,
+}
+
+impl SheetViewport {
+    /// Builds the placement from a parsed VIEWPORT entity. Returns `None`
+    /// for viewports that are switched off (status 0), for the viewport
+    /// representing the sheet itself (ID 1), for degenerate windows and for
+    /// other entity types.
+    pub fn from_entity(entity: &Entity) -> Option<Self> {
+        let Geometry::Viewport(viewport) = &entity.data else {
+            return None;
+        };
+        let (width, height, view_height) = (viewport.width, viewport.height, viewport.view_height);
+        if viewport.status == 0 || viewport.id == 1 || height <= 0.0 || view_height <= 0.0 {
+            return None;
+        }
+
+        let scale = height / view_height;
+        let model_width = width * view_height / height;
+        let model_height = view_height;
+        // The view center is relative to the target in display coordinates,
+        // which match model coordinates for plan views.
+        let model_center = Point {
+            x: viewport.view_target.x + viewport.view_center.x,
+            y: viewport.view_target.y + viewport.view_center.y,
+        };
+        let direction = viewport.view_direction;
+        let plan_view = direction.x == 0.0 && direction.y == 0.0 && direction.z > 0.0;
+
+        let twist_angle = viewport.twist_angle;
+        let (sin, cos) = twist_angle.to_radians().sin_cos();
+        let half_x = (model_width * cos).abs() / 2.0 + (model_height * sin).abs() / 2.0;
+        let half_y = (model_width * sin).abs() / 2.0 + (model_height * cos).abs() / 2.0;
+
+        Some(Self {
+            entity_id: entity.id,
+            handle: entity.handle.clone(),
+            viewport_id: viewport.id,
+            paper: BoundingBox::new(entity.min_x, entity.min_y, entity.max_x, entity.max_y),
+            model_center,
+            model_width,
+            model_height,
+            twist_angle,
+            model_bounds: BoundingBox::new(
+                model_center.x - half_x,
+                model_center.y - half_y,
+                model_center.x + half_x,
+                model_center.y + half_y,
+            ),
+            scale,
+            plan_view,
+            frozen_layers: viewport.frozen_layers.clone(),
+        })
+    }
+}
@@ -0,0 +1,55 @@
+use crate::models::{CreateDimStyleInput, CreateTextStyleInput};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::FromRow;
+
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct TextStyle {
+    pub id: u64,
+    pub file_id: u64,
+    pub name: String,
+    pub font_file: Option<String>,
+    pub bigfont_file: Option<String>,
+    pub fixed_height: f64,
+    pub width_factor: f64,
+    pub oblique_angle: f64,
+    pub flags: i32,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct DimStyle {
+    pub id: u64,
+    pub file_id: u64,
+    pub name: String,
+    pub text_style: Option<String>,
+    pub properties: Value,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<TextStyle> for CreateTextStyleInput {
+    fn from(style: TextStyle) -> Self {
+        Self {
+            name: style.name,
+            font_file: style.font_file,
+            bigfont_file: style.bigfont_file,
+            fixed_height: style.fixed_height,
+            width_factor: style.width_factor,
+            oblique_angle: style.oblique_angle,
+            flags: style.flags,
+        }
+    }
+}
+
+impl From<DimStyle> for CreateDimStyleInput {
+    fn from(style: DimStyle) -> Self {
+        Self {
+            name: style.name,
+            text_style: style.text_style,
+            properties: serde_json::from_value(style.properties).unwrap_or_default(),
+        }
+    }
+}
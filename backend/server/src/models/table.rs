@@ -0,0 +1,184 @@
+use crate::models::{Entity, Geometry};
+use serde::{Deserialize, Serialize};
+
+/// A TABLE entity (ACAD_TABLE) as a grid of plain text cells.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TableGrid {
+    pub entity_id: u64,
+    pub handle: Option<String>,
+    pub layout: String,
+    pub row_heights: Vec<f64>,
+    pub column_widths: Vec<f64>,
+    /// Cell text by row, with MTEXT formatting removed. Cells covered by a
+    /// merged cell are empty.
+    pub rows: Vec<Vec<String>>,
+    pub merges: Vec<CellMerge>,
+}
+
+/// A cell spanning more than one row or column, by its top left cell.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CellMerge {
+    pub row: usize,
+    pub column: usize,
+    pub row_span: usize,
+    pub column_span: usize,
+}
+
+impl TableGrid {
+    /// Builds the grid from a parsed TABLE entity; `None` for other types.
+    pub fn from_entity(entity: &Entity) -> Option<Self> {
+        let Geometry::Table(table) = &entity.data else {
+            return None;
+        };
+
+        let mut rows: Vec<Vec<String>> = table
+            .cells
+            .iter()
+            .map(|cells| cells.iter().map(|cell| plain_text(&cell.text)).collect())
+            .collect();
+        let mut merges = Vec::new();
+        for (row, cells) in table.cells.iter().enumerate() {
+            for (column, cell) in cells.iter().enumerate() {
+                // Spans come from the file; keep them within the grid.
+                let row_span = cell.row_span.clamp(1, rows.len() - row);
+                let column_span = cell.column_span.clamp(1, cells.len() - column);
+                if row_span > 1 || column_span > 1 {
+                    merges.push(CellMerge {
+                        row,
+                        column,
+                        row_span,
+                        column_span,
+                    });
+                }
+            }
+        }
+
+        // Covered cells may still carry stale text from before the merge.
+        for merge in &merges {
+            for row in merge.row..merge.row + merge.row_span {
+                for column in merge.column..merge.column + merge.column_span {
+                    if (row, column) == (merge.row, merge.column) {
+                        continue;
+                    }
+                    if let Some(cell) = rows.get_mut(row).and_then(|cells| cells.get_mut(column)) {
+                        cell.clear();
+                    }
+                }
+            }
+        }
+
+        Some(Self {
+            entity_id: entity.id,
+            handle: entity.handle.clone(),
+            layout: entity.layout.clone(),
+            row_heights: table.row_heights.clone(),
+            column_widths: table.column_widths.clone(),
+            rows,
+            merges,
+        })
+    }
+
+    /// The rows as CSV (RFC 4180), with CRLF line endings.
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::new();
+        for row in &self.rows {
+            let fields: Vec<String> = row.iter().map(|field| csv_field(field)).collect();
+            csv.push_str(&fields.join(","));
+            csv.push_str("\r\n");
+        }
+        csv
+    }
+}
+
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// Removes MTEXT formatting codes from cell text: font, height, color and
+/// alignment changes, grouping braces and underline/overline toggles.
+/// Paragraph breaks become newlines, stacked fractions `a/b`, `\U+XXXX`
+/// escapes their characters, and the `%%c`, `%%d` and `%%p` codes their
+/// symbols.
+pub fn plain_text(text: &str) -> String {
+    let mut plain = String::new();
+    let mut chars = text.chars().peekable();
+
+    while let Some(ch) = chars.next() {
+        match ch {
+            '{' | '}' => {}
+            '\\' => match chars.next() {
+                Some('P') | Some('X') => plain.push('\n'),
+                Some('~') => plain.push(' '),
+                Some(escaped @ ('\\' | '{' | '}')) => plain.push(escaped),
+                Some('S') => {
+                    for ch in chars.by_ref().take_while(|ch| *ch != ';') {
+                        plain.push(if matches!(ch, '^' | '#') { '/' } else { ch });
+                    }
+                }
+                Some('U') if chars.peek() == Some(&'+') => {
+                    chars.next();
+                    let code: String = chars.by_ref().take(4).collect();
+                    match u32::from_str_radix(&code, 16).ok().and_then(char::from_u32) {
+                        Some(decoded) => plain.push(decoded),
+                        None => {
+                            plain.push_str("\\U+");
+                            plain.push_str(&code);
+                        }
+                    }
+                }
+                // A double-byte character in a code page (`\M+nXXXX`),
+                // which there is no table to decode with.
+                Some('M') if chars.peek() == Some(&'+') => {
+                    chars.nth(5);
+                }
+                Some('A' | 'C' | 'c' | 'F' | 'f' | 'H' | 'p' | 'Q' | 'T' | 'W') => {
+                    for _ in chars.by_ref().take_while(|ch| *ch != ';') {}
+                }
+                Some(_) | None => {}
+            },
+            '%' if chars.peek() == Some(&'%') => {
+                chars.next();
+                match chars.next().map(|ch| ch.to_ascii_lowercase()) {
+                    Some('c') => plain.push('\u{2300}'),
+                    Some('d') => plain.push('\u{00b0}'),
+                    Some('p') => plain.push('\u{00b1}'),
+                    Some('%') => plain.push('%'),
+                    Some(other) => {
+                        plain.push_str("%%");
+                        plain.push(other);
+                    }
+                    None => plain.push_str("%%"),
+                }
+            }
+            _ => plain.push(ch),
+        }
+    }
+
+    plain.trim().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_plain_text_strips_formatting_codes() {
+        assert_eq!(
+            plain_text("{\\fArial|b1|i0;\\H2.5x;\\C1;Door} \\LA\\l\\PB"),
+            "Door A\nB"
+        );
+        assert_eq!(plain_text("\\S1#4;\"  %%c50"), "1/4\"  \u{2300}50");
+        assert_eq!(plain_text("\\pxqc;Centered"), "Centered");
+    }
+
+    #[test]
+    fn test_plain_text_decodes_unicode_escapes() {
+        assert_eq!(plain_text("45\\U+00B0 bend"), "45\u{00b0} bend");
+        assert_eq!(plain_text("\\U+4E2D\\U+6587;x"), "\u{4e2d}\u{6587};x");
+        assert_eq!(plain_text("A\\M+1814BB"), "AB");
+    }
+}
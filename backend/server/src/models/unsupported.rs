@@ -0,0 +1,15 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+/// An entity type, section or table the parser skipped, with the number of
+/// times it occurred in the file.
+#[derive(Debug, Clone, FromRow, Serialize, Deserialize)]
+pub struct UnsupportedItem {
+    pub id: u64,
+    pub file_id: u64,
+    pub kind: String,
+    pub name: String,
+    pub count: u64,
+    pub created_at: DateTime<Utc>,
+}
@@ -0,0 +1,686 @@
+//! Drawing stored entities: resolving their colors and lineweights against
+//! their layers, and turning their geometry into the shapes an output
+//! format draws.
+
+pub mod pdf;
+pub mod png;
+pub mod svg;
+
+pub use pdf::{render_pdf, Orientation, PaperSize, Placement, Plot, PlotView, PLOT_MARGIN};
+pub use png::{render_png, Canvas};
+pub use svg::render_svg;
+
+use crate::models::table::plain_text;
+use crate::models::{
+    geometry::EntityProperties, BoundingBox, Entity, Geometry, Layer, Point, Point3,
+};
+use std::collections::HashMap;
+
+/// Lineweight of entities and layers that use the default one, in mm.
+pub const DEFAULT_LINE_WEIGHT: f64 = 0.25;
+
+/// Distance between the baselines of MTEXT lines, in text heights.
+const MTEXT_LINE_SPACING: f64 = 5.0 / 3.0;
+
+/// Average width of a glyph, in text heights, for aligning and sizing
+/// text without font metrics.
+const AVERAGE_GLYPH_WIDTH: f64 = 0.55;
+
+/// CSS pixels per millimetre, for stroke widths in images.
+const PIXELS_PER_MM: f64 = 96.0 / 25.4;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rgb(pub u8, pub u8, pub u8);
+
+impl Rgb {
+    pub const WHITE: Rgb = Rgb(255, 255, 255);
+    pub const BLACK: Rgb = Rgb(0, 0, 0);
+
+    pub fn from_true_color(color: u32) -> Self {
+        Rgb((color >> 16) as u8, (color >> 8) as u8, color as u8)
+    }
+
+    /// Parses `#rrggbb` or `rrggbb`.
+    pub fn from_hex(hex: &str) -> Option<Self> {
+        let hex = hex.strip_prefix('#').unwrap_or(hex);
+        if hex.len() != 6 {
+            return None;
+        }
+        u32::from_str_radix(hex, 16).ok().map(Self::from_true_color)
+    }
+
+    pub fn to_hex(self) -> String {
+        format!("#{:02x}{:02x}{:02x}", self.0, self.1, self.2)
+    }
+
+    fn is_light(self) -> bool {
+        // ITU-R BT.601 luma.
+        299 * self.0 as u32 + 587 * self.1 as u32 + 114 * self.2 as u32 > 127_500
+    }
+}
+
+/// The RGB value of an AutoCAD Color Index. Color 7 is white on dark
+/// backgrounds and black on light ones, so it takes the background.
+pub fn aci_color(index: i32, background: Rgb) -> Rgb {
+    match index {
+        1 => Rgb(255, 0, 0),
+        2 => Rgb(255, 255, 0),
+        3 => Rgb(0, 255, 0),
+        4 => Rgb(0, 255, 255),
+        5 => Rgb(0, 0, 255),
+        6 => Rgb(255, 0, 255),
+        8 => Rgb(128, 128, 128),
+        9 => Rgb(192, 192, 192),
+        // Hues in steps of 15 degrees, each in five shades at full and at
+        // half saturation.
+        10..=249 => {
+            let hue = ((index - 10) / 10) as f64 * 15.0;
+            let shade = (index % 10) / 2;
+            let value = [255.0, 165.0, 127.0, 76.0, 38.0][shade as usize];
+            let low = if index % 2 == 0 { 0.0 } else { value / 2.0 };
+            let channel = |peak: f64| {
+                let distance = (hue - peak).rem_euclid(360.0);
+                let distance = distance.min(360.0 - distance);
+                let weight = ((120.0 - distance) / 60.0).clamp(0.0, 1.0);
+                (low + (value - low) * weight) as u8
+            };
+            Rgb(channel(0.0), channel(120.0), channel(240.0))
+        }
+        250..=255 => {
+            let grey = [51, 80, 105, 130, 190, 255][(index - 250) as usize];
+            Rgb(grey, grey, grey)
+        }
+        _ if background.is_light() => Rgb::BLACK,
+        _ => Rgb::WHITE,
+    }
+}
+
+/// Something to draw, in drawing units.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Shape {
+    pub kind: ShapeKind,
+    pub color: Rgb,
+    /// Stroke width in millimetres.
+    pub line_weight: f64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ShapeKind {
+    /// Straight and arc segments. A segment's bulge is the tangent of a
+    /// quarter of its arc angle, positive counterclockwise; `bulges` is
+    /// empty when all segments are straight.
+    Path {
+        vertices: Vec<Point>,
+        bulges: Vec<f64>,
+        closed: bool,
+    },
+    Circle {
+        center: Point,
+        radius: f64,
+    },
+    /// A polygon filled in the shape's color, without an outline.
+    Fill {
+        vertices: Vec<Point>,
+    },
+    /// Lines of text from the baseline of the first one down, with the
+    /// rotation in degrees counterclockwise.
+    Text {
+        position: Point,
+        height: f64,
+        rotation: f64,
+        width_factor: f64,
+        align: TextAlign,
+        lines: Vec<String>,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TextAlign {
+    Left,
+    Center,
+    Right,
+}
+
+/// What part of the drawing an image shows and how large it is.
+#[derive(Debug, Clone)]
+pub struct ImageOptions {
+    /// The region of the drawing to show, fitted into the image and
+    /// centered; shapes outside of it are clipped.
+    pub window: BoundingBox,
+    pub width: u32,
+    pub height: u32,
+    /// Transparent when `None`.
+    pub background: Option<Rgb>,
+}
+
+/// Maps drawing coordinates (Y up) to image pixels (Y down).
+struct Viewport {
+    window: BoundingBox,
+    scale: f64,
+    offset_x: f64,
+    offset_y: f64,
+}
+
+impl Viewport {
+    fn new(options: &ImageOptions) -> Self {
+        let window = options.window.clone();
+        let window_width = (window.max_x - window.min_x).max(f64::EPSILON);
+        let window_height = (window.max_y - window.min_y).max(f64::EPSILON);
+        let scale =
+            (options.width as f64 / window_width).min(options.height as f64 / window_height);
+        Self {
+            window,
+            scale,
+            offset_x: (options.width as f64 - window_width * scale) / 2.0,
+            offset_y: (options.height as f64 - window_height * scale) / 2.0,
+        }
+    }
+
+    fn x(&self, x: f64) -> f64 {
+        self.offset_x + (x - self.window.min_x) * self.scale
+    }
+
+    fn y(&self, y: f64) -> f64 {
+        self.offset_y + (self.window.max_y - y) * self.scale
+    }
+
+    fn length(&self, length: f64) -> f64 {
+        length * self.scale
+    }
+}
+
+/// The extents of the entities on the given layers, with a small margin;
+/// a unit square at the origin when there are none.
+pub fn extents(layers: &[Layer], entities: &[Entity]) -> BoundingBox {
+    let mut extents: Option<BoundingBox> = None;
+    for entity in entities
+        .iter()
+        .filter(|entity| layers.iter().any(|layer| layer.id == entity.layer_id))
+    {
+        let bbox = BoundingBox::new(entity.min_x, entity.min_y, entity.max_x, entity.max_y);
+        match &mut extents {
+            Some(extents) => extents.merge(&bbox),
+            None => extents = Some(bbox),
+        }
+    }
+    with_margin(extents)
+}
+
+/// Extents with a small margin; a unit square at the origin for `None`.
+pub fn with_margin(extents: Option<BoundingBox>) -> BoundingBox {
+    let Some(extents) = extents else {
+        return BoundingBox::new(0.0, 0.0, 1.0, 1.0);
+    };
+    let margin = ((extents.max_x - extents.min_x).max(extents.max_y - extents.min_y) * 0.02)
+        .max(f64::EPSILON.sqrt());
+    BoundingBox::new(
+        extents.min_x - margin,
+        extents.min_y - margin,
+        extents.max_x + margin,
+        extents.max_y + margin,
+    )
+}
+
+/// The segments of a path as pairs of vertices with the bulge between
+/// them.
+pub fn segments(vertices: &[Point], bulges: &[f64], closed: bool) -> Vec<(Point, Point, f64)> {
+    let count = if closed {
+        vertices.len()
+    } else {
+        vertices.len().saturating_sub(1)
+    };
+    (0..count)
+        .map(|index| {
+            let next = vertices[(index + 1) % vertices.len()];
+            let bulge = bulges.get(index).copied().unwrap_or(0.0);
+            (vertices[index], next, bulge)
+        })
+        .collect()
+}
+
+/// The cubic Bézier curves (two control points and an end point) of the
+/// arc from one point to another with a bulge, each spanning at most a
+/// quarter turn.
+pub fn bulge_curves(from: Point, to: Point, bulge: f64) -> Vec<[Point; 3]> {
+    let (center, radius, start, sweep) = bulge_arc(from, to, bulge);
+    arc_curves(center, radius, start, sweep)
+}
+
+/// The center, radius, start angle and signed sweep (in radians) of the
+/// arc from one point to another with a non-zero bulge.
+pub fn bulge_arc(from: Point, to: Point, bulge: f64) -> (Point, f64, f64, f64) {
+    let (dx, dy) = (to.x - from.x, to.y - from.y);
+    // The center lies on the chord's bisector, to its left for
+    // counterclockwise arcs of less than a half turn.
+    let offset = (1.0 - bulge * bulge) / (4.0 * bulge);
+    let center = Point {
+        x: (from.x + to.x) / 2.0 - dy * offset,
+        y: (from.y + to.y) / 2.0 + dx * offset,
+    };
+    let radius = dx.hypot(dy) * (1.0 + bulge * bulge) / (4.0 * bulge.abs());
+    let start = (from.y - center.y).atan2(from.x - center.x);
+    (center, radius, start, 4.0 * bulge.atan())
+}
+
+/// The cubic Bézier curves of a circle, starting and ending at angle 0.
+pub fn circle_curves(center: Point, radius: f64) -> Vec<[Point; 3]> {
+    arc_curves(center, radius, 0.0, std::f64::consts::TAU)
+}
+
+/// The cubic Bézier curves of an arc from a start angle through a signed
+/// sweep, both in radians.
+fn arc_curves(center: Point, radius: f64, start: f64, sweep: f64) -> Vec<[Point; 3]> {
+    let count = (sweep.abs() / std::f64::consts::FRAC_PI_2).ceil().max(1.0) as usize;
+    let step = sweep / count as f64;
+    let handle = 4.0 / 3.0 * (step / 4.0).tan() * radius;
+    let at = |angle: f64| {
+        let (sin, cos) = angle.sin_cos();
+        (
+            Point {
+                x: center.x + radius * cos,
+                y: center.y + radius * sin,
+            },
+            (-sin, cos),
+        )
+    };
+    (0..count)
+        .map(|index| {
+            let angle = start + step * index as f64;
+            let (from, (from_x, from_y)) = at(angle);
+            let (end, (end_x, end_y)) = at(angle + step);
+            [
+                Point {
+                    x: from.x + handle * from_x,
+                    y: from.y + handle * from_y,
+                },
+                Point {
+                    x: end.x - handle * end_x,
+                    y: end.y - handle * end_y,
+                },
+                end,
+            ]
+        })
+        .collect()
+}
+
+/// How entities drawn BYLAYER look on one layer.
+struct LayerPen {
+    color: i32,
+    line_weight: f64,
+}
+
+/// The shapes of entities in draw order, colored and weighted for a
+/// background. Wipeouts are filled with the background, hiding what is
+/// drawn before them. Entities on layers not in `layers`, on layers that
+/// are off (negative color) or hidden, and types without a 2D outline to
+/// draw are left out.
+pub fn shapes(layers: &[Layer], entities: &[Entity], background: Rgb) -> Vec<Shape> {
+    let pens: HashMap<u64, LayerPen> = layers
+        .iter()
+        .filter_map(|layer| {
+            let color = layer
+                .color
+                .as_deref()
+                .and_then(|color| color.trim().parse::<i32>().ok())
+                .unwrap_or(7);
+            (layer.is_visible && color >= 0).then(|| {
+                let line_weight = layer
+                    .line_weight
+                    .as_deref()
+                    .and_then(|weight| weight.trim().parse::<i32>().ok())
+                    .filter(|weight| *weight >= 0)
+                    .map_or(DEFAULT_LINE_WEIGHT, |weight| weight as f64 / 100.0);
+                (layer.id, LayerPen { color, line_weight })
+            })
+        })
+        .collect();
+
+    entities
+        .iter()
+        .filter_map(|entity| {
+            let pen = pens.get(&entity.layer_id)?;
+            if let Geometry::Wipeout(wipeout) = &entity.data {
+                return Some(Shape {
+                    kind: ShapeKind::Fill {
+                        vertices: wipeout
+                            .boundary
+                            .iter()
+                            .map(|point| Point {
+                                x: point.x,
+                                y: point.y,
+                            })
+                            .collect(),
+                    },
+                    color: background,
+                    line_weight: 0.0,
+                });
+            }
+            let (kind, properties) = shape_kind(&entity.data)?;
+            Some(Shape {
+                kind,
+                color: resolve_color(properties, pen, background),
+                line_weight: match properties.line_weight {
+                    Some(weight) if weight >= 0 => weight as f64 / 100.0,
+                    Some(-1) | None => pen.line_weight,
+                    // BYBLOCK and the default; entities are drawn outside
+                    // of their blocks.
+                    Some(_) => DEFAULT_LINE_WEIGHT,
+                },
+            })
+        })
+        .collect()
+}
+
+fn resolve_color(properties: &EntityProperties, pen: &LayerPen, background: Rgb) -> Rgb {
+    if let Some(color) = properties.true_color {
+        return Rgb::from_true_color(color);
+    }
+    match properties.color {
+        None | Some(256) => aci_color(pen.color, background),
+        // BYBLOCK outside of a block draws in the foreground color.
+        Some(0) => aci_color(7, background),
+        Some(color) => aci_color(color.abs(), background),
+    }
+}
+
+fn shape_kind(data: &Geometry) -> Option<(ShapeKind, &EntityProperties)> {
+    let point = |point: &Point3| Point {
+        x: point.x,
+        y: point.y,
+    };
+    let kind = match data {
+        Geometry::Line(line) => ShapeKind::Path {
+            vertices: vec![point(&line.start), point(&line.end)],
+            bulges: Vec::new(),
+            closed: false,
+        },
+        Geometry::Polyline(polyline) => ShapeKind::Path {
+            vertices: polyline.vertices.iter().map(point).collect(),
+            bulges: polyline.bulges.clone(),
+            closed: polyline.closed,
+        },
+        Geometry::Arc(arc) => {
+            let sweep = (arc.end_angle - arc.start_angle).rem_euclid(360.0);
+            if sweep == 0.0 {
+                ShapeKind::Circle {
+                    center: point(&arc.center),
+                    radius: arc.radius,
+                }
+            } else {
+                let at = |angle: f64| {
+                    let (sin, cos) = angle.to_radians().sin_cos();
+                    Point {
+                        x: arc.center.x + arc.radius * cos,
+                        y: arc.center.y + arc.radius * sin,
+                    }
+                };
+                ShapeKind::Path {
+                    vertices: vec![at(arc.start_angle), at(arc.end_angle)],
+                    bulges: vec![(sweep / 4.0).to_radians().tan()],
+                    closed: false,
+                }
+            }
+        }
+        Geometry::Circle(circle) => ShapeKind::Circle {
+            center: point(&circle.center),
+            radius: circle.radius,
+        },
+        Geometry::Text(text) => ShapeKind::Text {
+            position: point(&text.position),
+            height: text.height,
+            rotation: text.rotation,
+            width_factor: text.width_factor,
+            align: TextAlign::Left,
+            lines: vec![plain_text(&text.text)],
+        },
+        Geometry::MText(text) => {
+            let lines: Vec<String> = plain_text(&text.text).lines().map(str::to_string).collect();
+            // The attachment point is the top, middle or bottom of the
+            // block of lines; move it to the first baseline.
+            let block_height =
+                text.height * (1.0 + MTEXT_LINE_SPACING * (lines.len() as f64 - 1.0));
+            let drop = match (text.attachment_point - 1) / 3 {
+                0 => text.height,
+                1 => text.height - block_height / 2.0,
+                _ => text.height - block_height,
+            };
+            let (sin, cos) = text.rotation.to_radians().sin_cos();
+            ShapeKind::Text {
+                position: Point {
+                    x: text.position.x + drop * sin,
+                    y: text.position.y - drop * cos,
+                },
+                height: text.height,
+                rotation: text.rotation,
+                width_factor: text.width_factor,
+                align: match (text.attachment_point - 1) % 3 {
+                    1 => TextAlign::Center,
+                    2 => TextAlign::Right,
+                    _ => TextAlign::Left,
+                },
+                lines,
+            }
+        }
+        _ => return None,
+    };
+    let properties = match data {
+        Geometry::Line(line) => &line.properties,
+        Geometry::Polyline(polyline) => &polyline.properties,
+        Geometry::Arc(arc) => &arc.properties,
+        Geometry::Circle(circle) => &circle.properties,
+        Geometry::Text(text) => &text.properties,
+        Geometry::MText(text) => &text.properties,
+        _ => return None,
+    };
+    Some((kind, properties))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_aci_colors() {
+        assert_eq!(aci_color(1, Rgb::WHITE), Rgb(255, 0, 0));
+        assert_eq!(aci_color(7, Rgb::WHITE), Rgb::BLACK);
+        assert_eq!(aci_color(7, Rgb::BLACK), Rgb::WHITE);
+        assert_eq!(aci_color(10, Rgb::WHITE), Rgb(255, 0, 0));
+        assert_eq!(aci_color(11, Rgb::WHITE), Rgb(255, 127, 127));
+        assert_eq!(aci_color(14, Rgb::WHITE), Rgb(127, 0, 0));
+        assert_eq!(aci_color(20, Rgb::WHITE), Rgb(255, 63, 0));
+        assert_eq!(aci_color(21, Rgb::WHITE), Rgb(255, 159, 127));
+        assert_eq!(aci_color(90, Rgb::WHITE), Rgb(0, 255, 0));
+        assert_eq!(aci_color(150, Rgb::WHITE), Rgb(0, 127, 255));
+        assert_eq!(aci_color(250, Rgb::WHITE), Rgb(51, 51, 51));
+    }
+
+    fn layer(id: u64, color: &str, line_weight: Option<&str>) -> Layer {
+        Layer {
+            id,
+            file_id: 1,
+            name: format!("L{}", id),
+            is_locked: false,
+            is_visible: true,
+            color: Some(color.to_string()),
+            line_type: None,
+            line_weight: line_weight.map(str::to_string),
+            min_x: None,
+            min_y: None,
+            max_x: None,
+            max_y: None,
+            min_z: None,
+            max_z: None,
+            created_at: chrono::Utc::now(),
+            updated_at: chrono::Utc::now(),
+        }
+    }
+
+    fn circle(layer_id: u64, properties: EntityProperties) -> Entity {
+        entity(
+            layer_id,
+            Geometry::Circle(crate::models::geometry::Circle {
+                center: Point3::new(0.0, 0.0, 0.0),
+                radius: 1.0,
+                properties,
+            }),
+        )
+    }
+
+    fn entity(layer_id: u64, data: Geometry) -> Entity {
+        Entity {
+            id: 1,
+            layer_id,
+            layout: "Model".to_string(),
+            handle: None,
+            owner_handle: None,
+            extension_dictionary: None,
+            draw_order: 0,
+            entity_type: data.entity_type().to_string(),
+            data,
+            xdata: None,
+            min_x: -1.0,
+            min_y: -1.0,
+            max_x: 1.0,
+            max_y: 1.0,
+            min_z: 0.0,
+            max_z: 0.0,
+            created_at: chrono::Utc::now(),
+            updated_at: chrono::Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_resolves_colors_and_line_weights_by_layer() {
+        let layers = [layer(1, "5", Some("35")), layer(2, "-3", None)];
+        let entities = [
+            circle(1, EntityProperties::default()),
+            circle(
+                1,
+                EntityProperties {
+                    color: Some(7),
+                    line_weight: Some(-3),
+                    ..Default::default()
+                },
+            ),
+            circle(
+                1,
+                EntityProperties {
+                    color: Some(1),
+                    true_color: Some(0x336699),
+                    line_weight: Some(70),
+                    ..Default::default()
+                },
+            ),
+            // Layers that are off and layers not asked for are not drawn.
+            circle(2, EntityProperties::default()),
+            circle(3, EntityProperties::default()),
+        ];
+
+        let shapes = shapes(&layers, &entities, Rgb::BLACK);
+        let pens: Vec<_> = shapes
+            .iter()
+            .map(|shape| (shape.color, shape.line_weight))
+            .collect();
+        assert_eq!(
+            pens,
+            [
+                (Rgb(0, 0, 255), 0.35),
+                (Rgb::WHITE, DEFAULT_LINE_WEIGHT),
+                (Rgb(0x33, 0x66, 0x99), 0.7)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_draws_lwpolylines_as_paths() {
+        let content = "0\nSECTION\n2\nENTITIES\n\
+0\nLWPOLYLINE\n8\nL1\n90\n3\n70\n1\n\
+10\n0.0\n20\n0.0\n42\n1.0\n10\n4.0\n20\n0.0\n10\n4.0\n20\n3.0\n\
+0\nENDSEC\n0\nEOF\n";
+        let mut drawing = dxf_parser::parse_dxf(content).unwrap();
+        let parsed = drawing.layers.remove("L1").unwrap().entities.remove(0);
+
+        let shapes = shapes(
+            &[layer(1, "1", None)],
+            &[entity(1, parsed.data)],
+            Rgb::WHITE,
+        );
+        assert_eq!(
+            shapes,
+            [Shape {
+                kind: ShapeKind::Path {
+                    vertices: vec![
+                        Point { x: 0.0, y: 0.0 },
+                        Point { x: 4.0, y: 0.0 },
+                        Point { x: 4.0, y: 3.0 },
+                    ],
+                    bulges: vec![1.0, 0.0, 0.0],
+                    closed: true,
+                },
+                color: Rgb(255, 0, 0),
+                line_weight: DEFAULT_LINE_WEIGHT,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_fills_wipeouts_with_the_background() {
+        let wipeout = entity(
+            1,
+            Geometry::Wipeout(crate::models::geometry::Wipeout {
+                boundary: vec![
+                    Point3::new(0.0, 0.0, 0.0),
+                    Point3::new(2.0, 0.0, 0.0),
+                    Point3::new(2.0, 2.0, 0.0),
+                ],
+            }),
+        );
+        let entities = [circle(1, EntityProperties::default()), wipeout];
+
+        let shapes = shapes(&[layer(1, "1", None)], &entities, Rgb::WHITE);
+        assert_eq!(shapes.len(), 2);
+        assert_eq!(
+            shapes[1],
+            Shape {
+                kind: ShapeKind::Fill {
+                    vertices: vec![
+                        Point { x: 0.0, y: 0.0 },
+                        Point { x: 2.0, y: 0.0 },
+                        Point { x: 2.0, y: 2.0 },
+                    ],
+                },
+                color: Rgb::WHITE,
+                line_weight: 0.0,
+            }
+        );
+    }
+
+    #[test]
+    fn test_bulge_curves_follow_the_arc() {
+        // A counterclockwise half circle below the chord.
+        let curves = bulge_curves(Point { x: 0.0, y: 0.0 }, Point { x: 4.0, y: 0.0 }, 1.0);
+        assert_eq!(curves.len(), 2);
+        let [_, _, middle] = curves[0];
+        assert!((middle.x - 2.0).abs() < 1e-9 && (middle.y + 2.0).abs() < 1e-9);
+        let [_, _, end] = curves[1];
+        assert!((end.x - 4.0).abs() < 1e-9 && end.y.abs() < 1e-9);
+
+        // A clockwise quarter turn around (1, 1), leaving (1, 0) leftwards.
+        let curves = bulge_curves(
+            Point { x: 1.0, y: 0.0 },
+            Point { x: 0.0, y: 1.0 },
+            -(std::f64::consts::PI / 8.0).tan(),
+        );
+        assert_eq!(curves.len(), 1);
+        let [control, _, _] = curves[0];
+        assert!(control.x < 1.0 && control.y.abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_hex_colors() {
+        assert_eq!(Rgb::from_hex("#ff8000"), Some(Rgb(255, 128, 0)));
+        assert_eq!(Rgb::from_hex("1e1e1e"), Some(Rgb(30, 30, 30)));
+        assert_eq!(Rgb::from_hex("red"), None);
+        assert_eq!(Rgb(255, 128, 0).to_hex(), "#ff8000");
+    }
+}
@@ -0,0 +1,403 @@
+use crate::models::{BoundingBox, Point};
+use crate::render::{
+    bulge_curves, circle_curves, segments, Shape, ShapeKind, TextAlign, AVERAGE_GLYPH_WIDTH,
+    MTEXT_LINE_SPACING,
+};
+use pdf_writer::types::{LineCapStyle, LineJoinStyle};
+use pdf_writer::{Content, Finish, Name, Pdf, Rect, Ref, Str};
+use serde::Deserialize;
+
+/// PDF points per millimetre.
+const POINTS_PER_MM: f64 = 72.0 / 25.4;
+
+/// Unprinted border around the plot area, in mm.
+pub const PLOT_MARGIN: f64 = 10.0;
+
+/// ISO 216 and ANSI paper sizes.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum PaperSize {
+    A0,
+    A1,
+    A2,
+    #[default]
+    A3,
+    A4,
+    AnsiA,
+    AnsiB,
+    AnsiC,
+    AnsiD,
+    AnsiE,
+}
+
+impl PaperSize {
+    /// Width and height in mm, portrait.
+    pub fn size_mm(self) -> (f64, f64) {
+        match self {
+            PaperSize::A0 => (841.0, 1189.0),
+            PaperSize::A1 => (594.0, 841.0),
+            PaperSize::A2 => (420.0, 594.0),
+            PaperSize::A3 => (297.0, 420.0),
+            PaperSize::A4 => (210.0, 297.0),
+            PaperSize::AnsiA => (215.9, 279.4),
+            PaperSize::AnsiB => (279.4, 431.8),
+            PaperSize::AnsiC => (431.8, 558.8),
+            PaperSize::AnsiD => (558.8, 863.6),
+            PaperSize::AnsiE => (863.6, 1117.6),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Orientation {
+    #[default]
+    Landscape,
+    Portrait,
+}
+
+/// Places drawing coordinates on the sheet: the drawing point `origin`
+/// lands on the sheet point `center` (in mm), scaled by `scale` mm per
+/// drawing unit and turned `rotation` degrees counterclockwise.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Placement {
+    pub origin: Point,
+    pub center: Point,
+    pub scale: f64,
+    pub rotation: f64,
+}
+
+impl Placement {
+    pub fn map(&self, point: Point) -> Point {
+        let (sin, cos) = self.rotation.to_radians().sin_cos();
+        let (x, y) = (point.x - self.origin.x, point.y - self.origin.y);
+        Point {
+            x: self.center.x + self.scale * (x * cos - y * sin),
+            y: self.center.y + self.scale * (x * sin + y * cos),
+        }
+    }
+}
+
+/// Shapes placed on the sheet and clipped to a rectangle of it (in mm).
+#[derive(Debug, Clone)]
+pub struct PlotView {
+    pub shapes: Vec<Shape>,
+    pub placement: Placement,
+    pub clip: BoundingBox,
+}
+
+/// A plotted PDF sheet.
+#[derive(Debug, Clone)]
+pub struct Plot {
+    pub pdf: Vec<u8>,
+    /// Characters of text that Helvetica's WinAnsi encoding has no code
+    /// for, drawn as `?`.
+    pub replaced_chars: usize,
+}
+
+/// Plots views onto one sheet of the given size in mm. Lines are drawn in
+/// their lineweights, true to size on paper; text is set in Helvetica.
+pub fn render_pdf(width: f64, height: f64, views: &[PlotView]) -> Plot {
+    let catalog_id = Ref::new(1);
+    let page_tree_id = Ref::new(2);
+    let page_id = Ref::new(3);
+    let content_id = Ref::new(4);
+    let font_id = Ref::new(5);
+    let font_name = Name(b"F1");
+
+    let mut content = Content::new();
+    let mut replaced_chars = 0;
+    content
+        .set_line_cap(LineCapStyle::RoundCap)
+        .set_line_join(LineJoinStyle::RoundJoin);
+    for view in views {
+        let clip = &view.clip;
+        content.save_state();
+        content
+            .rect(
+                points(clip.min_x),
+                points(clip.min_y),
+                points(clip.max_x - clip.min_x),
+                points(clip.max_y - clip.min_y),
+            )
+            .clip_nonzero()
+            .end_path();
+        for shape in &view.shapes {
+            draw_shape(
+                &mut content,
+                shape,
+                &view.placement,
+                font_name,
+                &mut replaced_chars,
+            );
+        }
+        content.restore_state();
+    }
+    let content = content.finish();
+
+    let mut pdf = Pdf::new();
+    pdf.catalog(catalog_id).pages(page_tree_id);
+    pdf.pages(page_tree_id).kids([page_id]).count(1);
+    let mut page = pdf.page(page_id);
+    page.parent(page_tree_id)
+        .media_box(Rect::new(0.0, 0.0, points(width), points(height)))
+        .contents(content_id);
+    page.resources().fonts().pair(font_name, font_id);
+    page.finish();
+    pdf.type1_font(font_id)
+        .base_font(Name(b"Helvetica"))
+        .encoding_predefined(Name(b"WinAnsiEncoding"));
+    pdf.stream(content_id, &content);
+    Plot {
+        pdf: pdf.finish(),
+        replaced_chars,
+    }
+}
+
+/// Draws a shape, adding the characters of its text that could not be
+/// encoded to `replaced_chars`.
+fn draw_shape(
+    content: &mut Content,
+    shape: &Shape,
+    placement: &Placement,
+    font: Name,
+    replaced_chars: &mut usize,
+) {
+    let color = shape.color;
+    let (red, green, blue) = (
+        color.0 as f32 / 255.0,
+        color.1 as f32 / 255.0,
+        color.2 as f32 / 255.0,
+    );
+    let map = |point: Point| {
+        let point = placement.map(point);
+        (points(point.x), points(point.y))
+    };
+    // Graphics state operators are not allowed inside a path, so the
+    // stroke is set up before the path starts.
+    let set_stroke = |content: &mut Content| {
+        content
+            .set_stroke_rgb(red, green, blue)
+            .set_line_width(points(shape.line_weight));
+    };
+
+    match &shape.kind {
+        ShapeKind::Path {
+            vertices,
+            bulges,
+            closed,
+        } => {
+            let Some(first) = vertices.first() else {
+                return;
+            };
+            set_stroke(content);
+            let (x, y) = map(*first);
+            content.move_to(x, y);
+            for (from, to, bulge) in segments(vertices, bulges, *closed) {
+                if bulge == 0.0 {
+                    let (x, y) = map(to);
+                    content.line_to(x, y);
+                } else {
+                    cubic_to(content, &bulge_curves(from, to, bulge), map);
+                }
+            }
+            if *closed {
+                content.close_path();
+            }
+        }
+        ShapeKind::Circle { center, radius } => {
+            let (x, y) = map(Point {
+                x: center.x + radius,
+                y: center.y,
+            });
+            set_stroke(content);
+            content.move_to(x, y);
+            cubic_to(content, &circle_curves(*center, *radius), map);
+            content.close_path();
+        }
+        ShapeKind::Fill { vertices } => {
+            let Some(first) = vertices.first() else {
+                return;
+            };
+            content.set_fill_rgb(red, green, blue);
+            let (x, y) = map(*first);
+            content.move_to(x, y);
+            for vertex in &vertices[1..] {
+                let (x, y) = map(*vertex);
+                content.line_to(x, y);
+            }
+            content.close_path().fill_nonzero();
+            return;
+        }
+        ShapeKind::Text {
+            position,
+            height,
+            rotation,
+            width_factor,
+            align,
+            lines,
+        } => {
+            let size = height * placement.scale * POINTS_PER_MM;
+            let (sin, cos) = (rotation + placement.rotation).to_radians().sin_cos();
+            let origin = placement.map(*position);
+            content.set_fill_rgb(red, green, blue).begin_text();
+            content.set_font(font, size as f32);
+            for (index, line) in lines.iter().enumerate() {
+                let (text, replaced) = win_ansi(line);
+                *replaced_chars += replaced;
+                let shift = match align {
+                    TextAlign::Left => 0.0,
+                    TextAlign::Center => 0.5,
+                    TextAlign::Right => 1.0,
+                } * text.len() as f64
+                    * AVERAGE_GLYPH_WIDTH
+                    * width_factor;
+                let drop = index as f64 * MTEXT_LINE_SPACING;
+                // Offsets along and across the baseline, in text heights.
+                let (along, across) = (-shift * size, -drop * size);
+                content.set_text_matrix([
+                    (cos * width_factor) as f32,
+                    (sin * width_factor) as f32,
+                    -sin as f32,
+                    cos as f32,
+                    (points(origin.x) as f64 + along * cos - across * sin) as f32,
+                    (points(origin.y) as f64 + along * sin + across * cos) as f32,
+                ]);
+                content.show(Str(&text));
+            }
+            content.end_text();
+            return;
+        }
+    }
+    content.stroke();
+}
+
+fn cubic_to(content: &mut Content, curves: &[[Point; 3]], map: impl Fn(Point) -> (f32, f32)) {
+    for [first, second, end] in curves {
+        let (x1, y1) = map(*first);
+        let (x2, y2) = map(*second);
+        let (x3, y3) = map(*end);
+        content.cubic_to(x1, y1, x2, y2, x3, y3);
+    }
+}
+
+fn points(mm: f64) -> f32 {
+    (mm * POINTS_PER_MM) as f32
+}
+
+/// Text in the standard fonts' WinAnsi encoding, which matches Latin-1 in
+/// the printable ranges; other characters become `?`. Also returns how
+/// many were replaced.
+fn win_ansi(text: &str) -> (Vec<u8>, usize) {
+    let mut replaced = 0;
+    let bytes = text
+        .chars()
+        .map(|ch| match ch as u32 {
+            code @ (0x20..=0x7E | 0xA0..=0xFF) => code as u8,
+            _ => {
+                replaced += 1;
+                b'?'
+            }
+        })
+        .collect();
+    (bytes, replaced)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::render::Rgb;
+
+    #[test]
+    fn test_placement_scales_and_rotates_about_the_origin() {
+        let placement = Placement {
+            origin: Point {
+                x: 1000.0,
+                y: 1000.0,
+            },
+            center: Point { x: 210.0, y: 148.5 },
+            scale: 0.01,
+            rotation: 90.0,
+        };
+        let point = placement.map(Point {
+            x: 2000.0,
+            y: 1000.0,
+        });
+        assert!((point.x - 210.0).abs() < 1e-9);
+        assert!((point.y - 158.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_writes_a_page_of_the_paper_size() {
+        let (width, height) = PaperSize::A3.size_mm();
+        let view = PlotView {
+            shapes: vec![Shape {
+                kind: ShapeKind::Circle {
+                    center: Point { x: 0.0, y: 0.0 },
+                    radius: 1000.0,
+                },
+                color: Rgb(255, 0, 0),
+                line_weight: 0.5,
+            }],
+            placement: Placement {
+                origin: Point { x: 0.0, y: 0.0 },
+                center: Point {
+                    x: height / 2.0,
+                    y: width / 2.0,
+                },
+                scale: 0.01,
+                rotation: 0.0,
+            },
+            clip: BoundingBox::new(10.0, 10.0, height - 10.0, width - 10.0),
+        };
+        let plot = render_pdf(height, width, &[view]);
+        let text = String::from_utf8_lossy(&plot.pdf);
+
+        assert!(text.starts_with("%PDF-"));
+        assert!(text.contains("/MediaBox [0 0 1190.5511 841.8898]"));
+        // 0.5 mm in points, and a red stroke.
+        assert!(text.contains("1.4173229 w"));
+        assert!(text.contains("1 0 0 RG"));
+        assert!(text.contains("/BaseFont /Helvetica"));
+        // The stroke is set before the path is built.
+        let path = text.find(" m\n").unwrap();
+        assert!(text.find("1 0 0 RG").unwrap() < path);
+        assert!(text.find("1.4173229 w").unwrap() < path);
+        assert!(text[path..].contains("h\nS\n"));
+    }
+
+    #[test]
+    fn test_encodes_text_as_win_ansi() {
+        assert_eq!(win_ansi("Ø 12°"), (b"\xd8 12\xb0".to_vec(), 0));
+        assert_eq!(win_ansi("测试?"), (b"???".to_vec(), 2));
+    }
+
+    #[test]
+    fn test_counts_text_it_cannot_encode() {
+        let view = PlotView {
+            shapes: vec![Shape {
+                kind: ShapeKind::Text {
+                    position: Point { x: 0.0, y: 0.0 },
+                    height: 2.5,
+                    rotation: 0.0,
+                    width_factor: 1.0,
+                    align: TextAlign::Left,
+                    lines: vec!["门 D1".to_string(), "Tür 2".to_string()],
+                },
+                color: Rgb(0, 0, 0),
+                line_weight: 0.0,
+            }],
+            placement: Placement {
+                origin: Point { x: 0.0, y: 0.0 },
+                center: Point { x: 100.0, y: 100.0 },
+                scale: 1.0,
+                rotation: 0.0,
+            },
+            clip: BoundingBox::new(0.0, 0.0, 200.0, 200.0),
+        };
+        let plot = render_pdf(200.0, 200.0, &[view]);
+
+        assert_eq!(plot.replaced_chars, 1);
+        assert!(String::from_utf8_lossy(&plot.pdf).contains("(? D1) Tj"));
+    }
+}
@@ -0,0 +1,359 @@
+use crate::error::{AppError, Result};
+use crate::models::Point;
+use crate::render::{
+    bulge_curves, circle_curves, segments, ImageOptions, Shape, ShapeKind, TextAlign, Viewport,
+    AVERAGE_GLYPH_WIDTH, MTEXT_LINE_SPACING, PIXELS_PER_MM,
+};
+use tiny_skia::{
+    Color, FillRule, LineCap, LineJoin, Mask, Paint, PathBuilder, Pixmap, Rect, Stroke, Transform,
+};
+
+/// Opacity of the bars that stand in for text.
+const TEXT_BAR_ALPHA: u8 = 96;
+
+/// Rasterizes shapes to a PNG, in order, so that later shapes cover
+/// earlier ones. There are no fonts to render with, so each line of text
+/// is drawn as a faint bar the size of the line.
+pub fn render_png(shapes: &[Shape], options: &ImageOptions) -> Result<Vec<u8>> {
+    let mut canvas = Canvas::new(options)?;
+    canvas.draw(shapes);
+    canvas.encode()
+}
+
+/// An image being drawn, for rasterizing shapes a batch at a time
+/// without holding all of them.
+pub struct Canvas {
+    pixmap: Pixmap,
+    mask: Mask,
+    viewport: Viewport,
+}
+
+impl Canvas {
+    pub fn new(options: &ImageOptions) -> Result<Self> {
+        let viewport = Viewport::new(options);
+        let mut pixmap = Pixmap::new(options.width, options.height)
+            .ok_or_else(|| AppError::Internal("Image size must not be zero".to_string()))?;
+        if let Some(background) = options.background {
+            pixmap.fill(Color::from_rgba8(
+                background.0,
+                background.1,
+                background.2,
+                255,
+            ));
+        }
+
+        let window = &options.window;
+        let clip = Rect::from_ltrb(
+            viewport.x(window.min_x) as f32,
+            viewport.y(window.max_y) as f32,
+            viewport.x(window.max_x) as f32,
+            viewport.y(window.min_y) as f32,
+        )
+        .map(PathBuilder::from_rect);
+        let mut mask = Mask::new(options.width, options.height)
+            .ok_or_else(|| AppError::Internal("Image size must not be zero".to_string()))?;
+        if let Some(clip) = &clip {
+            mask.fill_path(clip, FillRule::Winding, false, Transform::identity());
+        }
+
+        Ok(Self {
+            pixmap,
+            mask,
+            viewport,
+        })
+    }
+
+    /// Draws shapes over what is drawn already.
+    pub fn draw(&mut self, shapes: &[Shape]) {
+        for shape in shapes {
+            draw_shape(&mut self.pixmap, &self.mask, shape, &self.viewport);
+        }
+    }
+
+    pub fn encode(self) -> Result<Vec<u8>> {
+        self.pixmap
+            .encode_png()
+            .map_err(|e| AppError::Internal(format!("Failed to encode PNG: {}", e)))
+    }
+}
+
+fn draw_shape(pixmap: &mut Pixmap, mask: &Mask, shape: &Shape, viewport: &Viewport) {
+    let color = shape.color;
+    let mut paint = Paint::default();
+    paint.set_color_rgba8(color.0, color.1, color.2, 255);
+    paint.anti_alias = true;
+    let map = |point: Point| (viewport.x(point.x) as f32, viewport.y(point.y) as f32);
+
+    let mut path = PathBuilder::new();
+    match &shape.kind {
+        ShapeKind::Path {
+            vertices,
+            bulges,
+            closed,
+        } => {
+            let Some(first) = vertices.first() else {
+                return;
+            };
+            let (x, y) = map(*first);
+            path.move_to(x, y);
+            for (from, to, bulge) in segments(vertices, bulges, *closed) {
+                if bulge == 0.0 {
+                    let (x, y) = map(to);
+                    path.line_to(x, y);
+                } else {
+                    cubic_to(&mut path, &bulge_curves(from, to, bulge), map);
+                }
+            }
+            if *closed {
+                path.close();
+            }
+        }
+        ShapeKind::Circle { center, radius } => {
+            let (x, y) = map(Point {
+                x: center.x + radius,
+                y: center.y,
+            });
+            path.move_to(x, y);
+            cubic_to(&mut path, &circle_curves(*center, *radius), map);
+            path.close();
+        }
+        ShapeKind::Fill { vertices } => {
+            for (index, vertex) in vertices.iter().enumerate() {
+                let (x, y) = map(*vertex);
+                if index == 0 {
+                    path.move_to(x, y);
+                } else {
+                    path.line_to(x, y);
+                }
+            }
+            path.close();
+            if let Some(path) = path.finish() {
+                pixmap.fill_path(
+                    &path,
+                    &paint,
+                    FillRule::Winding,
+                    Transform::identity(),
+                    Some(mask),
+                );
+            }
+            return;
+        }
+        ShapeKind::Text {
+            position,
+            height,
+            rotation,
+            width_factor,
+            align,
+            lines,
+        } => {
+            paint.set_color_rgba8(color.0, color.1, color.2, TEXT_BAR_ALPHA);
+            let (sin, cos) = rotation.to_radians().sin_cos();
+            // A point `along` and `across` the baseline, in text heights.
+            let corner = |along: f64, across: f64| {
+                map(Point {
+                    x: position.x + height * (along * cos - across * sin),
+                    y: position.y + height * (along * sin + across * cos),
+                })
+            };
+            for (index, line) in lines.iter().enumerate() {
+                let length = line.chars().count() as f64 * AVERAGE_GLYPH_WIDTH * width_factor;
+                let start = -length
+                    * match align {
+                        TextAlign::Left => 0.0,
+                        TextAlign::Center => 0.5,
+                        TextAlign::Right => 1.0,
+                    };
+                let baseline = -(index as f64) * MTEXT_LINE_SPACING;
+                let corners = [
+                    corner(start, baseline),
+                    corner(start + length, baseline),
+                    corner(start + length, baseline + 1.0),
+                    corner(start, baseline + 1.0),
+                ];
+                path.move_to(corners[0].0, corners[0].1);
+                for (x, y) in &corners[1..] {
+                    path.line_to(*x, *y);
+                }
+                path.close();
+            }
+            if let Some(path) = path.finish() {
+                pixmap.fill_path(
+                    &path,
+                    &paint,
+                    FillRule::Winding,
+                    Transform::identity(),
+                    Some(mask),
+                );
+            }
+            return;
+        }
+    }
+
+    let Some(path) = path.finish() else {
+        return;
+    };
+    let stroke = Stroke {
+        width: (shape.line_weight * PIXELS_PER_MM).max(1.0) as f32,
+        line_cap: LineCap::Round,
+        line_join: LineJoin::Round,
+        ..Stroke::default()
+    };
+    pixmap.stroke_path(&path, &paint, &stroke, Transform::identity(), Some(mask));
+}
+
+fn cubic_to(path: &mut PathBuilder, curves: &[[Point; 3]], map: impl Fn(Point) -> (f32, f32)) {
+    for [first, second, end] in curves {
+        let (x1, y1) = map(*first);
+        let (x2, y2) = map(*second);
+        let (x, y) = map(*end);
+        path.cubic_to(x1, y1, x2, y2, x, y);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::BoundingBox;
+    use crate::render::Rgb;
+
+    fn options() -> ImageOptions {
+        ImageOptions {
+            window: BoundingBox::new(0.0, 0.0, 100.0, 50.0),
+            width: 200,
+            height: 100,
+            background: Some(Rgb::WHITE),
+        }
+    }
+
+    fn decode(png: &[u8]) -> Pixmap {
+        Pixmap::decode_png(png).expect("valid PNG")
+    }
+
+    fn pixel(pixmap: &Pixmap, x: u32, y: u32) -> (u8, u8, u8, u8) {
+        let pixel = pixmap.pixel(x, y).unwrap().demultiply();
+        (pixel.red(), pixel.green(), pixel.blue(), pixel.alpha())
+    }
+
+    #[test]
+    fn test_strokes_shapes_in_their_colors_with_y_up() {
+        // A horizontal red line a fifth of the way up, and a blue circle
+        // around the middle.
+        let shapes = [
+            Shape {
+                kind: ShapeKind::Path {
+                    vertices: vec![Point { x: 0.0, y: 10.0 }, Point { x: 100.0, y: 10.0 }],
+                    bulges: Vec::new(),
+                    closed: false,
+                },
+                color: Rgb(255, 0, 0),
+                line_weight: 1.0,
+            },
+            Shape {
+                kind: ShapeKind::Circle {
+                    center: Point { x: 50.0, y: 25.0 },
+                    radius: 10.0,
+                },
+                color: Rgb(0, 0, 255),
+                line_weight: 1.0,
+            },
+        ];
+        let pixmap = decode(&render_png(&shapes, &options()).unwrap());
+
+        assert_eq!((pixmap.width(), pixmap.height()), (200, 100));
+        assert_eq!(pixel(&pixmap, 100, 80), (255, 0, 0, 255));
+        assert_eq!(pixel(&pixmap, 100, 20), (255, 255, 255, 255));
+        assert_eq!(pixel(&pixmap, 120, 50), (0, 0, 255, 255));
+        assert_eq!(pixel(&pixmap, 100, 50), (255, 255, 255, 255));
+    }
+
+    #[test]
+    fn test_canvas_draws_shapes_in_batches() {
+        let line = |y: f64, color: Rgb| Shape {
+            kind: ShapeKind::Path {
+                vertices: vec![Point { x: 0.0, y }, Point { x: 100.0, y }],
+                bulges: Vec::new(),
+                closed: false,
+            },
+            color,
+            line_weight: 1.0,
+        };
+        let shapes = [
+            line(10.0, Rgb(255, 0, 0)),
+            line(40.0, Rgb(0, 0, 255)),
+            line(10.0, Rgb(0, 128, 0)),
+        ];
+
+        let mut canvas = Canvas::new(&options()).unwrap();
+        canvas.draw(&shapes[..2]);
+        canvas.draw(&shapes[2..]);
+        let batched = canvas.encode().unwrap();
+
+        assert_eq!(batched, render_png(&shapes, &options()).unwrap());
+        assert_eq!(pixel(&decode(&batched), 100, 80), (0, 128, 0, 255));
+    }
+
+    #[test]
+    fn test_fills_cover_earlier_shapes() {
+        // A red line a fifth of the way up, covered on its left half.
+        let shapes = [
+            Shape {
+                kind: ShapeKind::Path {
+                    vertices: vec![Point { x: 0.0, y: 10.0 }, Point { x: 100.0, y: 10.0 }],
+                    bulges: Vec::new(),
+                    closed: false,
+                },
+                color: Rgb(255, 0, 0),
+                line_weight: 1.0,
+            },
+            Shape {
+                kind: ShapeKind::Fill {
+                    vertices: vec![
+                        Point { x: 0.0, y: 0.0 },
+                        Point { x: 50.0, y: 0.0 },
+                        Point { x: 50.0, y: 20.0 },
+                        Point { x: 0.0, y: 20.0 },
+                    ],
+                },
+                color: Rgb::WHITE,
+                line_weight: 0.0,
+            },
+        ];
+        let pixmap = decode(&render_png(&shapes, &options()).unwrap());
+
+        assert_eq!(pixel(&pixmap, 50, 80), (255, 255, 255, 255));
+        assert_eq!(pixel(&pixmap, 150, 80), (255, 0, 0, 255));
+    }
+
+    #[test]
+    fn test_leaves_the_image_transparent_without_a_background() {
+        let options = ImageOptions {
+            background: None,
+            ..options()
+        };
+        let pixmap = decode(&render_png(&[], &options).unwrap());
+
+        assert_eq!(pixel(&pixmap, 0, 0).3, 0);
+    }
+
+    #[test]
+    fn test_draws_text_as_faint_bars() {
+        let text = Shape {
+            kind: ShapeKind::Text {
+                position: Point { x: 10.0, y: 10.0 },
+                height: 10.0,
+                rotation: 0.0,
+                width_factor: 1.0,
+                align: TextAlign::Left,
+                lines: vec!["TEXT".to_string()],
+            },
+            color: Rgb::BLACK,
+            line_weight: 0.25,
+        };
+        let pixmap = decode(&render_png(&[text], &options()).unwrap());
+
+        // The bar covers 22 by 10 units above the baseline.
+        let (red, _, _, alpha) = pixel(&pixmap, 40, 70);
+        assert!(red > 0 && red < 255 && alpha == 255);
+        assert_eq!(pixel(&pixmap, 40, 90), (255, 255, 255, 255));
+    }
+}
@@ -0,0 +1,259 @@
+use crate::models::Point;
+use crate::render::{
+    segments, ImageOptions, Shape, ShapeKind, TextAlign, Viewport, MTEXT_LINE_SPACING,
+    PIXELS_PER_MM,
+};
+use std::fmt::Write;
+
+/// Draws shapes as an SVG document, in order, so that later shapes cover
+/// earlier ones. `id` names the clip path, and must differ between SVGs
+/// inlined in the same page.
+pub fn render_svg(shapes: &[Shape], options: &ImageOptions, id: &str) -> String {
+    let viewport = Viewport::new(options);
+    let (width, height) = (options.width, options.height);
+    let mut svg = String::new();
+
+    let _ = writeln!(
+        svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="{height}" viewBox="0 0 {width} {height}">"#
+    );
+    if let Some(background) = options.background {
+        let _ = writeln!(
+            svg,
+            r#"<rect width="{width}" height="{height}" fill="{}"/>"#,
+            background.to_hex()
+        );
+    }
+    let window = &options.window;
+    let _ = writeln!(
+        svg,
+        r#"<clipPath id="{}"><rect x="{}" y="{}" width="{}" height="{}"/></clipPath>"#,
+        id,
+        number(viewport.x(window.min_x)),
+        number(viewport.y(window.max_y)),
+        number(viewport.length(window.max_x - window.min_x)),
+        number(viewport.length(window.max_y - window.min_y)),
+    );
+    let _ = writeln!(
+        svg,
+        r#"<g clip-path="url(#{})" fill="none" stroke-linecap="round" stroke-linejoin="round">"#,
+        id
+    );
+    for shape in shapes {
+        shape_element(&mut svg, shape, &viewport);
+    }
+    svg.push_str("</g>\n</svg>\n");
+    svg
+}
+
+fn shape_element(svg: &mut String, shape: &Shape, viewport: &Viewport) {
+    let color = shape.color.to_hex();
+    let stroke = format!(
+        r#"stroke="{}" stroke-width="{}""#,
+        color,
+        number((shape.line_weight * PIXELS_PER_MM).max(1.0))
+    );
+    let _ = match &shape.kind {
+        ShapeKind::Path {
+            vertices,
+            bulges,
+            closed,
+        } => {
+            let Some(first) = vertices.first() else {
+                return;
+            };
+            let mut path = format!("M{}", point(viewport, first));
+            for (from, to, bulge) in segments(vertices, bulges, *closed) {
+                if bulge == 0.0 {
+                    let _ = write!(path, " L{}", point(viewport, &to));
+                    continue;
+                }
+                let angle = 4.0 * bulge.atan();
+                let chord = (to.x - from.x).hypot(to.y - from.y);
+                let radius = number(viewport.length(chord / (2.0 * (angle / 2.0).sin().abs())));
+                // Counterclockwise in the drawing is counter to SVG's
+                // positive sweep once Y points down.
+                let _ = write!(
+                    path,
+                    " A{radius} {radius} 0 {} {} {}",
+                    u8::from(angle.abs() > std::f64::consts::PI),
+                    u8::from(bulge < 0.0),
+                    point(viewport, &to)
+                );
+            }
+            if *closed {
+                path.push_str(" Z");
+            }
+            writeln!(svg, r#"<path d="{path}" {stroke}/>"#)
+        }
+        ShapeKind::Fill { vertices } => {
+            let points: Vec<String> = vertices
+                .iter()
+                .map(|vertex| point(viewport, vertex).replace(' ', ","))
+                .collect();
+            writeln!(
+                svg,
+                r#"<polygon points="{}" fill="{color}" stroke="none"/>"#,
+                points.join(" ")
+            )
+        }
+        ShapeKind::Circle { center, radius } => writeln!(
+            svg,
+            r#"<circle cx="{}" cy="{}" r="{}" {stroke}/>"#,
+            number(viewport.x(center.x)),
+            number(viewport.y(center.y)),
+            number(viewport.length(*radius))
+        ),
+        ShapeKind::Text {
+            position,
+            height,
+            rotation,
+            width_factor,
+            align,
+            lines,
+        } => {
+            let anchor = match align {
+                TextAlign::Left => "start",
+                TextAlign::Center => "middle",
+                TextAlign::Right => "end",
+            };
+            let mut transform = format!("translate({})", point(viewport, position));
+            if *rotation != 0.0 {
+                let _ = write!(transform, " rotate({})", number(-rotation));
+            }
+            if *width_factor != 1.0 {
+                let _ = write!(transform, " scale({} 1)", number(*width_factor));
+            }
+            let _ = write!(
+                svg,
+                r#"<text transform="{transform}" font-family="sans-serif" font-size="{}" text-anchor="{anchor}" fill="{color}">"#,
+                number(viewport.length(*height))
+            );
+            for (index, line) in lines.iter().enumerate() {
+                let dy = if index == 0 {
+                    0.0
+                } else {
+                    height * MTEXT_LINE_SPACING
+                };
+                let _ = write!(
+                    svg,
+                    r#"<tspan x="0" dy="{}">{}</tspan>"#,
+                    number(viewport.length(dy)),
+                    escape(line)
+                );
+            }
+            writeln!(svg, "</text>")
+        }
+    };
+}
+
+fn point(viewport: &Viewport, point: &Point) -> String {
+    format!(
+        "{} {}",
+        number(viewport.x(point.x)),
+        number(viewport.y(point.y))
+    )
+}
+
+/// A coordinate rounded to hundredths of a pixel, without trailing zeros.
+fn number(value: f64) -> String {
+    let rounded = format!("{:.2}", value);
+    let trimmed = rounded.trim_end_matches('0').trim_end_matches('.');
+    match trimmed {
+        "-0" | "" => "0".to_string(),
+        trimmed => trimmed.to_string(),
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::BoundingBox;
+    use crate::render::Rgb;
+
+    fn options() -> ImageOptions {
+        ImageOptions {
+            window: BoundingBox::new(0.0, 0.0, 100.0, 50.0),
+            width: 200,
+            height: 100,
+            background: Some(Rgb::WHITE),
+        }
+    }
+
+    fn shape(kind: ShapeKind) -> Shape {
+        Shape {
+            kind,
+            color: Rgb(255, 0, 0),
+            line_weight: 0.5,
+        }
+    }
+
+    #[test]
+    fn test_flips_y_and_scales_to_the_image() {
+        let line = shape(ShapeKind::Path {
+            vertices: vec![Point { x: 0.0, y: 0.0 }, Point { x: 50.0, y: 25.0 }],
+            bulges: Vec::new(),
+            closed: false,
+        });
+        let svg = render_svg(&[line], &options(), "window");
+
+        assert!(svg.contains(r##"<rect width="200" height="100" fill="#ffffff"/>"##));
+        assert!(svg.contains(r#"<clipPath id="window">"#));
+        assert!(svg.contains(r#"<g clip-path="url(#window)""#));
+        assert!(
+            svg.contains(r##"<path d="M0 100 L100 50" stroke="#ff0000" stroke-width="1.89"/>"##)
+        );
+    }
+
+    #[test]
+    fn test_draws_bulges_as_arcs() {
+        // A counterclockwise half circle from (0, 0) to (20, 0), below the
+        // chord, and back with a straight segment.
+        let polyline = shape(ShapeKind::Path {
+            vertices: vec![Point { x: 0.0, y: 10.0 }, Point { x: 20.0, y: 10.0 }],
+            bulges: vec![1.0, 0.0],
+            closed: true,
+        });
+        let svg = render_svg(&[polyline], &options(), "window");
+
+        assert!(svg.contains(r#"d="M0 80 A20 20 0 0 0 40 80 L0 80 Z""#));
+    }
+
+    #[test]
+    fn test_draws_fills_without_an_outline() {
+        let fill = shape(ShapeKind::Fill {
+            vertices: vec![
+                Point { x: 0.0, y: 0.0 },
+                Point { x: 10.0, y: 0.0 },
+                Point { x: 10.0, y: 5.0 },
+            ],
+        });
+        let svg = render_svg(&[fill], &options(), "window");
+
+        assert!(svg
+            .contains(r##"<polygon points="0,100 20,100 20,90" fill="#ff0000" stroke="none"/>"##));
+    }
+
+    #[test]
+    fn test_writes_escaped_text_lines() {
+        let text = shape(ShapeKind::Text {
+            position: Point { x: 10.0, y: 10.0 },
+            height: 2.5,
+            rotation: 90.0,
+            width_factor: 1.0,
+            align: TextAlign::Center,
+            lines: vec!["A & B".to_string(), "<C>".to_string()],
+        });
+        let svg = render_svg(&[text], &options(), "window");
+
+        assert!(svg.contains(
+            r##"<text transform="translate(20 80) rotate(-90)" font-family="sans-serif" font-size="5" text-anchor="middle" fill="#ff0000"><tspan x="0" dy="0">A &amp; B</tspan><tspan x="0" dy="8.33">&lt;C&gt;</tspan></text>"##
+        ));
+    }
+}
@@ -0,0 +1,130 @@
+use crate::error::{AppError, Result};
+use crate::models::{BoundingBox, ElevationRange, Entity, EntityFilter, MODEL_LAYOUT};
+use crate::routes::files::find_project_file;
+use crate::routes::AppState;
+use crate::services::PersistService;
+use axum::{
+    extract::{Path, Query, State},
+    Json,
+};
+use serde::{Deserialize, Serialize};
+
+pub(crate) const DEFAULT_PAGE_SIZE: u64 = 100;
+pub(crate) const MAX_PAGE_SIZE: u64 = 1000;
+
+#[derive(Debug, Default, Deserialize)]
+pub struct EntityQueryParams {
+    /// Layout name; defaults to `Model`.
+    pub layout: Option<String>,
+    pub min_x: Option<f64>,
+    pub min_y: Option<f64>,
+    pub max_x: Option<f64>,
+    pub max_y: Option<f64>,
+    /// Keeps entities whose Z extent overlaps `min_z..=max_z`. Either bound
+    /// may be given on its own.
+    pub min_z: Option<f64>,
+    pub max_z: Option<f64>,
+    pub page: Option<u64>,
+    pub page_size: Option<u64>,
+}
+
+impl EntityQueryParams {
+    fn filter(&self) -> Result<EntityFilter> {
+        Ok(EntityFilter {
+            layout: self
+                .layout
+                .clone()
+                .unwrap_or_else(|| MODEL_LAYOUT.to_string()),
+            bbox: self.bbox()?,
+            elevation: self.elevation()?,
+        })
+    }
+
+    fn bbox(&self) -> Result<Option<BoundingBox>> {
+        match (self.min_x, self.min_y, self.max_x, self.max_y) {
+            (None, None, None, None) => Ok(None),
+            (Some(min_x), Some(min_y), Some(max_x), Some(max_y)) => {
+                Ok(Some(BoundingBox::new(min_x, min_y, max_x, max_y)))
+            }
+            _ => Err(AppError::InvalidQuery(
+                "min_x, min_y, max_x and max_y must be given together".to_string(),
+            )),
+        }
+    }
+
+    fn elevation(&self) -> Result<Option<ElevationRange>> {
+        if self.min_z.is_none() && self.max_z.is_none() {
+            return Ok(None);
+        }
+        let range = ElevationRange {
+            min_z: self.min_z.unwrap_or(f64::MIN),
+            max_z: self.max_z.unwrap_or(f64::MAX),
+        };
+        if range.min_z > range.max_z {
+            return Err(AppError::InvalidQuery(
+                "min_z must not be greater than max_z".to_string(),
+            ));
+        }
+        Ok(Some(range))
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Pagination {
+    pub page: u64,
+    pub page_size: u64,
+    pub total: u64,
+    pub total_pages: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct EntitiesResponse {
+    pub data: Vec<Entity>,
+    pub pagination: Pagination,
+}
+
+pub async fn list_entities(
+    State(state): State<AppState>,
+    Path(layer_id): Path<u64>,
+    Query(params): Query<EntityQueryParams>,
+) -> Result<Json<EntitiesResponse>> {
+    let filter = params.filter()?;
+    let page = params.page.unwrap_or(1).max(1);
+    let page_size = params
+        .page_size
+        .unwrap_or(DEFAULT_PAGE_SIZE)
+        .clamp(1, MAX_PAGE_SIZE);
+
+    PersistService::get_layer(&state.db, layer_id).await?;
+
+    let (data, total) = PersistService::query_entities(
+        &state.db,
+        layer_id,
+        &filter,
+        page_size,
+        (page - 1) * page_size,
+    )
+    .await?;
+
+    Ok(Json(EntitiesResponse {
+        data,
+        pagination: Pagination {
+            page,
+            page_size,
+            total,
+            total_pages: total.div_ceil(page_size),
+        },
+    }))
+}
+
+/// Looks up an entity by its DXF handle, which is unique within a file.
+pub async fn get_entity_by_handle(
+    State(state): State<AppState>,
+    Path((project_id, file_id, handle)): Path<(u64, u64, String)>,
+) -> Result<Json<Entity>> {
+    find_project_file(&state.db, project_id, file_id).await?;
+
+    let entity = PersistService::get_entity_by_handle(&state.db, file_id, &handle).await?;
+
+    Ok(Json(entity))
+}
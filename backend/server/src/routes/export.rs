@@ -0,0 +1,601 @@
+use crate::error::{AppError, Result};
+use crate::geojson;
+use crate::models::{BoundingBox, Layer, Point, SheetViewport, MODEL_LAYOUT};
+use crate::render::{
+    self, render_pdf, render_svg, Canvas, ImageOptions, Orientation, PaperSize, Placement,
+    PlotView, Rgb, PLOT_MARGIN,
+};
+use crate::routes::files::{content_disposition, content_type, find_project_file};
+use crate::routes::AppState;
+use crate::services::{FileStorage, PersistService};
+use axum::{
+    extract::{Path, Query, State},
+    http::{header, HeaderName, HeaderValue},
+    response::{IntoResponse, Response},
+};
+use dxf_parser::{write_dxf, DxfVersion};
+use serde::Deserialize;
+use std::sync::Arc;
+
+#[derive(Debug, Default, Deserialize)]
+pub struct ExportParams {
+    #[serde(default)]
+    pub version: DxfVersion,
+}
+
+/// Writes a parsed file back out as ASCII DXF (`?version=r2018|r12`).
+/// R12 keeps the tables and the entity types R12 knows; layouts, objects
+/// and handles need R2018.
+pub async fn export_dxf(
+    State(state): State<AppState>,
+    Path((project_id, file_id)): Path<(u64, u64)>,
+    Query(params): Query<ExportParams>,
+) -> Result<Response> {
+    let file = find_project_file(&state.db, project_id, file_id).await?;
+    let drawing = PersistService::load_drawing(&state.db, &file).await?;
+    let content = tokio::task::spawn_blocking(move || write_dxf(&drawing, params.version))
+        .await
+        .map_err(|e| AppError::Internal(e.to_string()))?;
+
+    let file_name = format!("{}-{}.dxf", file_stem(&file.name), params.version.as_str());
+    Ok((
+        [
+            (header::CONTENT_TYPE, content_type("dxf").to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                content_disposition("attachment", &file_name),
+            ),
+        ],
+        content,
+    )
+        .into_response())
+}
+
+/// Width of SVG snapshots when neither `width` nor `height` is given.
+const DEFAULT_SVG_WIDTH: u32 = 1024;
+const MAX_SVG_SIZE: u32 = 8192;
+
+#[derive(Debug, Default, Deserialize)]
+pub struct SvgParams {
+    /// Layout name; defaults to `Model`.
+    pub layout: Option<String>,
+    /// Comma-separated layer names; all layers when absent.
+    pub layers: Option<String>,
+    /// ID of a viewport of the (paper space) layout, to draw the model
+    /// space region it shows without its frozen layers.
+    pub viewport: Option<i32>,
+    pub min_x: Option<f64>,
+    pub min_y: Option<f64>,
+    pub max_x: Option<f64>,
+    pub max_y: Option<f64>,
+    /// Image size in pixels; a missing one follows the window's aspect.
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    /// `#rrggbb`, or `none` for a transparent background. White by default.
+    pub background: Option<String>,
+}
+
+impl SvgParams {
+    fn background(&self) -> Result<Option<Rgb>> {
+        match self.background.as_deref() {
+            None => Ok(Some(Rgb::WHITE)),
+            Some("none") => Ok(None),
+            Some(color) => Rgb::from_hex(color).map(Some).ok_or_else(|| {
+                AppError::InvalidQuery(format!("background must be #rrggbb or none, not {}", color))
+            }),
+        }
+    }
+
+    /// The image size for a window, keeping its aspect for a missing side.
+    fn size(&self, window: &BoundingBox) -> Result<(u32, u32)> {
+        let aspect = (window.max_y - window.min_y) / (window.max_x - window.min_x);
+        let fit = |size: f64| (size.round() as u32).clamp(1, MAX_SVG_SIZE);
+        let (width, height) = match (self.width, self.height) {
+            (Some(width), Some(height)) => (width, height),
+            (Some(width), None) => (width, fit(width as f64 * aspect)),
+            (None, Some(height)) => (fit(height as f64 / aspect), height),
+            (None, None) => (DEFAULT_SVG_WIDTH, fit(DEFAULT_SVG_WIDTH as f64 * aspect)),
+        };
+        if !(1..=MAX_SVG_SIZE).contains(&width) || !(1..=MAX_SVG_SIZE).contains(&height) {
+            return Err(AppError::InvalidQuery(format!(
+                "width and height must be between 1 and {}",
+                MAX_SVG_SIZE
+            )));
+        }
+        Ok((width, height))
+    }
+}
+
+/// Draws a file's lines, polylines, arcs, circles and text as SVG, in
+/// their resolved colors and lineweights. By default the whole of model
+/// space is drawn; `layers`, a window (`min_x`, `min_y`, `max_x`, `max_y`)
+/// or a paper space `viewport` narrow it down.
+pub async fn export_svg(
+    State(state): State<AppState>,
+    Path((project_id, file_id)): Path<(u64, u64)>,
+    Query(params): Query<SvgParams>,
+) -> Result<Response> {
+    let file = find_project_file(&state.db, project_id, file_id).await?;
+    let background = params.background()?;
+    let mut window = window(params.min_x, params.min_y, params.max_x, params.max_y)?;
+    let mut layout = params
+        .layout
+        .clone()
+        .unwrap_or_else(|| MODEL_LAYOUT.to_string());
+
+    let mut layers = select_layers(&state, file_id, params.layers.as_deref()).await?;
+
+    if let Some(viewport_id) = params.viewport {
+        if window.is_some() {
+            return Err(AppError::InvalidQuery(
+                "a viewport and a window cannot be given together".to_string(),
+            ));
+        }
+        let viewport = PersistService::get_viewports_by_layout(&state.db, file_id, &layout)
+            .await?
+            .iter()
+            .filter_map(SheetViewport::from_entity)
+            .find(|viewport| viewport.viewport_id == viewport_id)
+            .ok_or(AppError::ViewportNotFound)?;
+        layers.retain(|layer: &Layer| !viewport.frozen_layers.contains(&layer.name));
+        window = Some(viewport.model_bounds);
+        layout = MODEL_LAYOUT.to_string();
+    }
+
+    let entities =
+        PersistService::get_entities_in_layout(&state.db, file_id, &layout, window.as_ref())
+            .await?;
+    let window = match window {
+        Some(window) => window,
+        None => render::extents(&layers, &entities),
+    };
+    let (width, height) = params.size(&window)?;
+
+    let shapes = render::shapes(&layers, &entities, background.unwrap_or(Rgb::WHITE));
+    let svg = render_svg(
+        &shapes,
+        &ImageOptions {
+            window,
+            width,
+            height,
+            background,
+        },
+        &format!("file-{}-window", file_id),
+    );
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, "image/svg+xml".to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                content_disposition("inline", &format!("{}.svg", file_stem(&file.name))),
+            ),
+        ],
+        svg,
+    )
+        .into_response())
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct GeoJsonParams {
+    /// Layout name; defaults to `Model`.
+    pub layout: Option<String>,
+    /// Comma-separated layer names; all layers when absent.
+    pub layers: Option<String>,
+    /// Only entities whose extents touch this window.
+    pub min_x: Option<f64>,
+    pub min_y: Option<f64>,
+    pub max_x: Option<f64>,
+    pub max_y: Option<f64>,
+}
+
+/// Exports a layout's entities as a GeoJSON FeatureCollection, with each
+/// feature's layer, entity type and handle as properties.
+pub async fn export_geojson(
+    State(state): State<AppState>,
+    Path((project_id, file_id)): Path<(u64, u64)>,
+    Query(params): Query<GeoJsonParams>,
+) -> Result<Response> {
+    let file = find_project_file(&state.db, project_id, file_id).await?;
+    let window = window(params.min_x, params.min_y, params.max_x, params.max_y)?;
+    let layout = params.layout.as_deref().unwrap_or(MODEL_LAYOUT);
+
+    let layers = select_layers(&state, file_id, params.layers.as_deref()).await?;
+    let entities =
+        PersistService::get_entities_in_layout(&state.db, file_id, layout, window.as_ref()).await?;
+    let collection = geojson::feature_collection(&layers, &entities);
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, "application/geo+json".to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                content_disposition("attachment", &format!("{}.geojson", file_stem(&file.name))),
+            ),
+        ],
+        collection.to_string(),
+    )
+        .into_response())
+}
+
+/// Edge lengths of the square thumbnails made of each parsed file, in
+/// pixels; the first is served by default.
+pub const THUMBNAIL_SIZES: [u32; 2] = [256, 1024];
+
+fn thumbnail_name(size: u32) -> String {
+    format!("thumbnail-{}.png", size)
+}
+
+/// Model space entities read and drawn at a time for thumbnails, so that
+/// memory use does not grow with the size of the drawing.
+const THUMBNAIL_PAGE_SIZE: u64 = 5_000;
+
+/// Rasterizes the model space of a parsed file to PNG thumbnails on white
+/// and stores them next to the file. If that fails, thumbnails made of an
+/// earlier parse are removed rather than left out of date.
+pub(crate) async fn store_thumbnails(
+    file_storage: &FileStorage,
+    db: &sqlx::MySqlPool,
+    file_id: u64,
+    storage_path: &str,
+) -> Result<()> {
+    let thumbnails = match render_thumbnails(db, file_id).await {
+        Ok(thumbnails) => thumbnails,
+        Err(e) => {
+            for size in THUMBNAIL_SIZES {
+                file_storage
+                    .remove_derived(storage_path, &thumbnail_name(size))
+                    .await?;
+            }
+            return Err(e);
+        }
+    };
+
+    for (size, png) in thumbnails {
+        file_storage
+            .store_derived(storage_path, &thumbnail_name(size), &png)
+            .await?;
+    }
+    Ok(())
+}
+
+/// Draws the model space thumbnails a page of entities at a time.
+async fn render_thumbnails(db: &sqlx::MySqlPool, file_id: u64) -> Result<Vec<(u32, Vec<u8>)>> {
+    let layers = Arc::new(PersistService::get_layers_by_file(db, file_id).await?);
+    let extents = PersistService::get_layout_extents(db, file_id, MODEL_LAYOUT).await?;
+    let window = render::with_margin(extents);
+    let mut canvases = THUMBNAIL_SIZES
+        .iter()
+        .map(|&size| {
+            let options = ImageOptions {
+                window: window.clone(),
+                width: size,
+                height: size,
+                background: Some(Rgb::WHITE),
+            };
+            Canvas::new(&options).map(|canvas| (size, canvas))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let mut after = None;
+    loop {
+        let entities = PersistService::get_entities_in_layout_page(
+            db,
+            file_id,
+            MODEL_LAYOUT,
+            after,
+            THUMBNAIL_PAGE_SIZE,
+        )
+        .await?;
+        let Some(last) = entities.last() else {
+            break;
+        };
+        after = Some((last.draw_order, last.id));
+        let full_page = entities.len() as u64 == THUMBNAIL_PAGE_SIZE;
+
+        let layers = layers.clone();
+        canvases = tokio::task::spawn_blocking(move || {
+            let shapes = render::shapes(&layers, &entities, Rgb::WHITE);
+            for (_, canvas) in &mut canvases {
+                canvas.draw(&shapes);
+            }
+            canvases
+        })
+        .await
+        .map_err(|e| AppError::Internal(format!("Thumbnail task failed: {}", e)))?;
+
+        if !full_page {
+            break;
+        }
+    }
+
+    tokio::task::spawn_blocking(move || {
+        canvases
+            .into_iter()
+            .map(|(size, canvas)| canvas.encode().map(|png| (size, png)))
+            .collect::<Result<Vec<_>>>()
+    })
+    .await
+    .map_err(|e| AppError::Internal(format!("Thumbnail task failed: {}", e)))?
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct ThumbnailParams {
+    /// One of `THUMBNAIL_SIZES`; the smallest by default.
+    pub size: Option<u32>,
+}
+
+/// A PNG thumbnail of a file's model space, made when the file was
+/// parsed.
+pub async fn get_thumbnail(
+    State(state): State<AppState>,
+    Path((project_id, file_id)): Path<(u64, u64)>,
+    Query(params): Query<ThumbnailParams>,
+) -> Result<Response> {
+    let file = find_project_file(&state.db, project_id, file_id).await?;
+    let size = params.size.unwrap_or(THUMBNAIL_SIZES[0]);
+    if !THUMBNAIL_SIZES.contains(&size) {
+        return Err(AppError::InvalidQuery(format!(
+            "size must be one of {:?}",
+            THUMBNAIL_SIZES
+        )));
+    }
+
+    let storage_path = file.storage_path.ok_or(AppError::ThumbnailNotFound)?;
+    let png = state
+        .file_storage
+        .read_derived(&storage_path, &thumbnail_name(size))
+        .await?
+        .ok_or(AppError::ThumbnailNotFound)?;
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, content_type("png").to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                content_disposition("inline", &format!("{}-{}.png", file_stem(&file.name), size)),
+            ),
+        ],
+        png,
+    )
+        .into_response())
+}
+
+/// Width and height of paper in mm, turned to the orientation.
+fn paper_size(paper: PaperSize, orientation: Orientation) -> (f64, f64) {
+    let (short, long) = paper.size_mm();
+    match orientation {
+        Orientation::Landscape => (long, short),
+        Orientation::Portrait => (short, long),
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct PlotParams {
+    /// Layout name; defaults to `Model`. Paper space layouts are plotted
+    /// with what their viewports show.
+    pub layout: Option<String>,
+    /// Comma-separated layer names; all layers when absent.
+    pub layers: Option<String>,
+    /// The region to plot, in the layout's units; the layout's limits, or
+    /// its extents, by default.
+    pub min_x: Option<f64>,
+    pub min_y: Option<f64>,
+    pub max_x: Option<f64>,
+    pub max_y: Option<f64>,
+    #[serde(default)]
+    pub paper: PaperSize,
+    #[serde(default)]
+    pub orientation: Orientation,
+    /// `1:100` for 1 mm on paper per 100 drawing units, or `fit` (the
+    /// default) to fill the sheet.
+    pub scale: Option<String>,
+}
+
+/// Set on plots with text the PDF font cannot show, to the number of
+/// characters drawn as `?`.
+const REPLACED_CHARS_HEADER: HeaderName = HeaderName::from_static("x-replaced-characters");
+
+/// Plots a layout, or a window of it, onto a sheet of standard paper as a
+/// PDF, to scale or fitted. Drawing units are taken as millimetres.
+pub async fn plot_pdf(
+    State(state): State<AppState>,
+    Path((project_id, file_id)): Path<(u64, u64)>,
+    Query(params): Query<PlotParams>,
+) -> Result<Response> {
+    let file = find_project_file(&state.db, project_id, file_id).await?;
+    let scale = plot_scale(params.scale.as_deref())?;
+    let window = window(params.min_x, params.min_y, params.max_x, params.max_y)?;
+    let layout = params
+        .layout
+        .clone()
+        .unwrap_or_else(|| MODEL_LAYOUT.to_string());
+    let layers = select_layers(&state, file_id, params.layers.as_deref()).await?;
+
+    let entities =
+        PersistService::get_entities_in_layout(&state.db, file_id, &layout, window.as_ref())
+            .await?;
+    let mut viewports = Vec::new();
+    let mut sheet = window;
+    if layout != MODEL_LAYOUT {
+        let layout = PersistService::get_layout(&state.db, file_id, &layout).await?;
+        viewports = PersistService::get_viewports_by_layout(&state.db, file_id, &layout.name)
+            .await?
+            .iter()
+            .filter_map(SheetViewport::from_entity)
+            .collect();
+        if sheet.is_none() && layout.min_x < layout.max_x && layout.min_y < layout.max_y {
+            sheet = Some(BoundingBox::new(
+                layout.min_x,
+                layout.min_y,
+                layout.max_x,
+                layout.max_y,
+            ));
+        }
+    }
+    let sheet = sheet.unwrap_or_else(|| {
+        let mut extents = render::extents(&layers, &entities);
+        for viewport in &viewports {
+            extents.merge(&viewport.paper);
+        }
+        extents
+    });
+
+    let (width, height) = paper_size(params.paper, params.orientation);
+    let printable = BoundingBox::new(
+        PLOT_MARGIN,
+        PLOT_MARGIN,
+        width - PLOT_MARGIN,
+        height - PLOT_MARGIN,
+    );
+    let placement = Placement {
+        origin: center(&sheet),
+        center: center(&printable),
+        scale: scale.unwrap_or_else(|| {
+            ((printable.max_x - printable.min_x) / (sheet.max_x - sheet.min_x))
+                .min((printable.max_y - printable.min_y) / (sheet.max_y - sheet.min_y))
+        }),
+        rotation: 0.0,
+    };
+    let on_sheet = |region: &BoundingBox| {
+        let min = placement.map(Point {
+            x: region.min_x,
+            y: region.min_y,
+        });
+        let max = placement.map(Point {
+            x: region.max_x,
+            y: region.max_y,
+        });
+        BoundingBox::new(
+            min.x.max(printable.min_x),
+            min.y.max(printable.min_y),
+            max.x.min(printable.max_x),
+            max.y.min(printable.max_y),
+        )
+    };
+
+    let mut views = vec![PlotView {
+        shapes: render::shapes(&layers, &entities, Rgb::WHITE),
+        placement,
+        clip: on_sheet(&sheet),
+    }];
+    for viewport in viewports {
+        let layers: Vec<Layer> = layers
+            .iter()
+            .filter(|layer| !viewport.frozen_layers.contains(&layer.name))
+            .cloned()
+            .collect();
+        let entities = PersistService::get_entities_in_layout(
+            &state.db,
+            file_id,
+            MODEL_LAYOUT,
+            Some(&viewport.model_bounds),
+        )
+        .await?;
+        views.push(PlotView {
+            shapes: render::shapes(&layers, &entities, Rgb::WHITE),
+            placement: Placement {
+                origin: viewport.model_center,
+                center: placement.map(center(&viewport.paper)),
+                scale: placement.scale * viewport.scale,
+                rotation: viewport.twist_angle,
+            },
+            clip: on_sheet(&viewport.paper),
+        });
+    }
+
+    let plot = tokio::task::spawn_blocking(move || render_pdf(width, height, &views))
+        .await
+        .map_err(|e| AppError::Internal(e.to_string()))?;
+
+    let mut response = (
+        [
+            (header::CONTENT_TYPE, "application/pdf".to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                content_disposition("inline", &format!("{}.pdf", file_stem(&file.name))),
+            ),
+        ],
+        plot.pdf,
+    )
+        .into_response();
+    // Helvetica only covers WinAnsi; other characters are drawn as `?`.
+    if plot.replaced_chars > 0 {
+        tracing::warn!(
+            "Plot of file {} drew {} characters of text as ?",
+            file_id,
+            plot.replaced_chars
+        );
+        response.headers_mut().insert(
+            REPLACED_CHARS_HEADER,
+            HeaderValue::from(plot.replaced_chars),
+        );
+    }
+    Ok(response)
+}
+
+/// Parses a plot scale: `fit` (or none) for `None`, and `a:b` for `a`
+/// mm on paper per `b` drawing units.
+fn plot_scale(scale: Option<&str>) -> Result<Option<f64>> {
+    let Some(scale) = scale.filter(|scale| *scale != "fit") else {
+        return Ok(None);
+    };
+    let ratio = scale.split_once(':').and_then(|(paper, drawing)| {
+        let paper = paper.trim().parse::<f64>().ok()?;
+        let drawing = drawing.trim().parse::<f64>().ok()?;
+        (paper > 0.0 && drawing > 0.0).then_some(paper / drawing)
+    });
+    ratio.map(Some).ok_or_else(|| {
+        AppError::InvalidQuery(format!("scale must be fit or like 1:100, not {}", scale))
+    })
+}
+
+fn window(
+    min_x: Option<f64>,
+    min_y: Option<f64>,
+    max_x: Option<f64>,
+    max_y: Option<f64>,
+) -> Result<Option<BoundingBox>> {
+    match (min_x, min_y, max_x, max_y) {
+        (None, None, None, None) => Ok(None),
+        (Some(min_x), Some(min_y), Some(max_x), Some(max_y)) if min_x < max_x && min_y < max_y => {
+            Ok(Some(BoundingBox::new(min_x, min_y, max_x, max_y)))
+        }
+        (Some(_), Some(_), Some(_), Some(_)) => Err(AppError::InvalidQuery(
+            "min_x and min_y must be less than max_x and max_y".to_string(),
+        )),
+        _ => Err(AppError::InvalidQuery(
+            "min_x, min_y, max_x and max_y must be given together".to_string(),
+        )),
+    }
+}
+
+/// The layers of a file, or those named in a comma-separated list.
+async fn select_layers(state: &AppState, file_id: u64, names: Option<&str>) -> Result<Vec<Layer>> {
+    let mut layers = PersistService::get_layers_by_file(&state.db, file_id).await?;
+    if let Some(names) = names {
+        let names: Vec<&str> = names.split(',').map(str::trim).collect();
+        if let Some(unknown) = names
+            .iter()
+            .find(|name| !layers.iter().any(|layer| layer.name == **name))
+        {
+            return Err(AppError::InvalidQuery(format!("unknown layer {}", unknown)));
+        }
+        layers.retain(|layer| names.contains(&layer.name.as_str()));
+    }
+    Ok(layers)
+}
+
+fn center(bbox: &BoundingBox) -> Point {
+    Point {
+        x: (bbox.min_x + bbox.max_x) / 2.0,
+        y: (bbox.min_y + bbox.max_y) / 2.0,
+    }
+}
+
+fn file_stem(name: &str) -> &str {
+    std::path::Path::new(name)
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .unwrap_or("drawing")
+}
@@ -1,20 +1,36 @@
 use crate::error::{AppError, Result};
+use crate::geojson;
 use crate::models::{File, FileUploadResponse};
 use crate::services::FileStorage;
 use axum::{
-    extract::{Multipart, Path, State},
-    http::StatusCode,
+    body::Bytes,
+    extract::{Multipart, Path, Query, State},
+    http::{header, StatusCode},
+    response::IntoResponse,
     Json,
 };
 use chrono::Utc;
+use dxf_parser::{write_dxf, DxfVersion, EntityHandlerRegistry};
+use serde::Deserialize;
 use sqlx::MySqlPool;
 use std::sync::Arc;
 
+/// Drawings that can be parsed.
+pub const DRAWING_EXTENSIONS: &[&str] = &["dxf", "dwg"];
+
+/// Raster images and underlay documents that drawings reference through
+/// IMAGE and underlay entities. They are stored and served, not parsed.
+pub const REFERENCE_EXTENSIONS: &[&str] = &[
+    "png", "jpg", "jpeg", "tif", "tiff", "bmp", "gif", "pdf", "dwf", "dwfx", "dgn",
+];
+
 #[derive(Clone)]
 pub struct AppState {
     pub db: MySqlPool,
     pub file_storage: Arc<FileStorage>,
     pub max_file_size: usize,
+    /// Handlers the parser reads entities with.
+    pub entity_handlers: Arc<EntityHandlerRegistry>,
 }
 
 pub async fn upload_file(
@@ -61,28 +77,122 @@ pub async fn upload_file(
     let filename =
         filename.ok_or_else(|| AppError::InvalidMultipart("No filename provided".to_string()))?;
 
-    let file_extension = std::path::Path::new(&filename)
-        .extension()
-        .and_then(|ext| ext.to_str())
-        .unwrap_or("")
-        .to_lowercase();
+    let file_extension = file_extension(&filename);
 
-    if !["dxf", "dwg"].contains(&file_extension.as_str()) {
+    if !DRAWING_EXTENSIONS.contains(&file_extension.as_str())
+        && !REFERENCE_EXTENSIONS.contains(&file_extension.as_str())
+    {
         return Err(AppError::InvalidFileType);
     }
 
-    let (storage_path, checksum) = state.file_storage.store_file(&file_data, &filename).await?;
+    let response = store_new_file(&state, project_id, &filename, &file_data).await?;
+
+    Ok((StatusCode::CREATED, Json(response)))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ImportGeoJsonParams {
+    /// The feature property whose values name the layers; `layer` by
+    /// default, which is what the GeoJSON export writes.
+    #[serde(default = "default_layer_property")]
+    pub layer_property: String,
+    /// Name of the new file; `.dxf` is added if missing. Without it the
+    /// file is named `import.dxf`, or `import-2.dxf` and so on if taken.
+    pub name: Option<String>,
+}
+
+fn default_layer_property() -> String {
+    "layer".to_string()
+}
+
+/// Turns a GeoJSON FeatureCollection, sent as the request body, into a new
+/// DXF file of the project, with a layer for each value of
+/// `layer_property`. The file is parsed like an uploaded one.
+pub async fn import_geojson(
+    State(state): State<AppState>,
+    Path(project_id): Path<u64>,
+    Query(params): Query<ImportGeoJsonParams>,
+    body: Bytes,
+) -> Result<(StatusCode, Json<FileUploadResponse>)> {
+    let _project = sqlx::query_as::<_, crate::models::Project>(
+        "SELECT id, name, description, created_at, updated_at FROM projects WHERE id = ?",
+    )
+    .bind(project_id)
+    .fetch_optional(&state.db)
+    .await?
+    .ok_or(AppError::ProjectNotFound)?;
+
+    if body.len() > state.max_file_size {
+        return Err(AppError::FileTooLarge);
+    }
+
+    let filename = match params.name {
+        Some(name) => {
+            let filename = if file_extension(&name) == "dxf" {
+                name
+            } else {
+                format!("{}.dxf", name)
+            };
+            if file_name_taken(&state.db, project_id, &filename).await? {
+                return Err(AppError::FileNameTaken);
+            }
+            filename
+        }
+        None => {
+            let mut filename = "import.dxf".to_string();
+            let mut number = 1;
+            while file_name_taken(&state.db, project_id, &filename).await? {
+                number += 1;
+                filename = format!("import-{}.dxf", number);
+            }
+            filename
+        }
+    };
+
+    let collection: serde_json::Value =
+        serde_json::from_slice(&body).map_err(|e| AppError::InvalidGeoJson(e.to_string()))?;
+    let drawing = geojson::import_feature_collection(&collection, &params.layer_property)?;
+    let dxf = tokio::task::spawn_blocking(move || write_dxf(&drawing, DxfVersion::R2018))
+        .await
+        .map_err(|e| AppError::Internal(format!("DXF writer task failed: {}", e)))?;
+
+    let response = store_new_file(&state, project_id, &filename, dxf.as_bytes()).await?;
+
+    Ok((StatusCode::CREATED, Json(response)))
+}
+
+/// Whether a project already has a file of a name, compared as the
+/// database's unique key compares them.
+async fn file_name_taken(db: &MySqlPool, project_id: u64, name: &str) -> Result<bool> {
+    let (count,) =
+        sqlx::query_as::<_, (i64,)>("SELECT COUNT(*) FROM files WHERE project_id = ? AND name = ?")
+            .bind(project_id)
+            .bind(name)
+            .fetch_one(db)
+            .await?;
+    Ok(count > 0)
+}
+
+/// Stores the bytes of a new file of a project and records it, awaiting
+/// parsing.
+async fn store_new_file(
+    state: &AppState,
+    project_id: u64,
+    filename: &str,
+    file_data: &[u8],
+) -> Result<FileUploadResponse> {
+    let (storage_path, checksum) = state.file_storage.store_file(file_data, filename).await?;
 
-    let encoding = state.file_storage.detect_encoding(&file_data);
+    let encoding = state.file_storage.detect_encoding(file_data);
     let file_size = file_data.len() as u64;
 
     let file_record = sqlx::query_as::<_, File>(
         "INSERT INTO files (project_id, name, size, storage_path, checksum, encoding, created_at, updated_at) 
          VALUES (?, ?, ?, ?, ?, ?, ?, ?) 
-         RETURNING id, project_id, name, size, storage_path, checksum, encoding, parse_status, parse_error, created_at, updated_at",
+         RETURNING id, project_id, name, size, storage_path, checksum, encoding, parse_status, parse_error, linetype_scale, created_at, updated_at",
     )
     .bind(project_id)
-    .bind(&filename)
+    .bind(filename)
     .bind(file_size)
     .bind(storage_path.to_str().unwrap())
     .bind(&checksum)
@@ -100,7 +210,7 @@ pub async fn upload_file(
                  VALUES (?, ?, ?, ?, ?, ?)",
             )
             .bind(project_id)
-            .bind(&filename)
+            .bind(filename)
             .bind(file_size)
             .bind(storage_path.to_str().unwrap())
             .bind(&checksum)
@@ -109,13 +219,13 @@ pub async fn upload_file(
             .await?;
 
             sqlx::query_as::<_, File>(
-                "SELECT id, project_id, name, size, storage_path, checksum, encoding, parse_status, parse_error, created_at, updated_at 
+                "SELECT id, project_id, name, size, storage_path, checksum, encoding, parse_status, parse_error, linetype_scale, created_at, updated_at 
                  FROM files 
                  WHERE project_id = ? AND name = ? 
                  ORDER BY id DESC LIMIT 1",
             )
             .bind(project_id)
-            .bind(&filename)
+            .bind(filename)
             .fetch_one(&state.db)
             .await?
         }
@@ -132,5 +242,126 @@ pub async fn upload_file(
         created_at: file_record.created_at,
     };
 
-    Ok((StatusCode::CREATED, Json(response)))
+    Ok(response)
+}
+
+/// Serves the stored bytes of a file, e.g. a raster image referenced by a
+/// drawing.
+pub async fn get_file_content(
+    State(state): State<AppState>,
+    Path((project_id, file_id)): Path<(u64, u64)>,
+) -> Result<impl IntoResponse> {
+    let file = find_project_file(&state.db, project_id, file_id).await?;
+
+    let storage_path = file
+        .storage_path
+        .ok_or_else(|| AppError::Internal("File storage path not found".to_string()))?;
+    let data = tokio::fs::read(state.file_storage.root_path().join(storage_path)).await?;
+
+    Ok((
+        [(
+            header::CONTENT_TYPE,
+            content_type(&file_extension(&file.name)),
+        )],
+        data,
+    ))
+}
+
+/// Lowercased extension of a file name, or an empty string.
+pub(crate) fn file_extension(filename: &str) -> String {
+    std::path::Path::new(filename)
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .unwrap_or("")
+        .to_lowercase()
+}
+
+pub(crate) fn content_type(extension: &str) -> &'static str {
+    match extension {
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "tif" | "tiff" => "image/tiff",
+        "bmp" => "image/bmp",
+        "gif" => "image/gif",
+        "pdf" => "application/pdf",
+        "dwf" => "model/vnd.dwf",
+        "dxf" => "image/vnd.dxf",
+        "dwg" => "image/vnd.dwg",
+        _ => "application/octet-stream",
+    }
+}
+
+/// A `Content-Disposition` value (`inline` or `attachment`) naming a file.
+/// The quoted `filename` is an ASCII fallback without quotes or
+/// backslashes; `filename*` has the name percent-encoded as UTF-8
+/// (RFC 6266).
+pub(crate) fn content_disposition(disposition: &str, file_name: &str) -> String {
+    let fallback: String = file_name
+        .chars()
+        .map(|ch| match ch {
+            '"' | '\\' => '_',
+            ' '..='~' => ch,
+            _ => '_',
+        })
+        .collect();
+    let mut encoded = String::new();
+    for byte in file_name.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                encoded.push(byte as char)
+            }
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    format!(
+        "{}; filename=\"{}\"; filename*=UTF-8''{}",
+        disposition, fallback, encoded
+    )
+}
+
+pub(crate) async fn find_project_file(
+    db: &MySqlPool,
+    project_id: u64,
+    file_id: u64,
+) -> Result<File> {
+    let _project = sqlx::query_as::<_, crate::models::Project>(
+        "SELECT id, name, description, created_at, updated_at FROM projects WHERE id = ?",
+    )
+    .bind(project_id)
+    .fetch_optional(db)
+    .await?
+    .ok_or(AppError::ProjectNotFound)?;
+
+    let file = sqlx::query_as::<_, File>(
+        "SELECT id, project_id, name, size, storage_path, checksum, encoding, parse_status, parse_error, linetype_scale, created_at, updated_at 
+         FROM files WHERE id = ? AND project_id = ?",
+    )
+    .bind(file_id)
+    .bind(project_id)
+    .fetch_optional(db)
+    .await?
+    .ok_or(AppError::FileNotFound)?;
+
+    Ok(file)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_content_disposition_escapes_file_names() {
+        assert_eq!(
+            content_disposition("inline", "plan.pdf"),
+            "inline; filename=\"plan.pdf\"; filename*=UTF-8''plan.pdf"
+        );
+        assert_eq!(
+            content_disposition("attachment", "a \"b\"\\c.dxf"),
+            "attachment; filename=\"a _b__c.dxf\"; filename*=UTF-8''a%20%22b%22%5Cc.dxf"
+        );
+        assert_eq!(
+            content_disposition("inline", "平面图.svg"),
+            "inline; filename=\"___.svg\"; filename*=UTF-8''%E5%B9%B3%E9%9D%A2%E5%9B%BE.svg"
+        );
+    }
 }
@@ -0,0 +1,45 @@
+use crate::error::Result;
+use crate::models::{Layout, SheetComposition, SheetViewport};
+use crate::routes::files::find_project_file;
+use crate::routes::AppState;
+use crate::services::PersistService;
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LayoutsResponse {
+    pub file_id: u64,
+    pub layouts: Vec<Layout>,
+}
+
+pub async fn list_layouts(
+    State(state): State<AppState>,
+    Path((project_id, file_id)): Path<(u64, u64)>,
+) -> Result<Json<LayoutsResponse>> {
+    find_project_file(&state.db, project_id, file_id).await?;
+
+    let layouts = PersistService::get_layouts_by_file(&state.db, file_id).await?;
+
+    Ok(Json(LayoutsResponse { file_id, layouts }))
+}
+
+/// Returns the viewports of a layout with the model space region each one
+/// shows, so the sheet can be drawn without a CAD program.
+pub async fn get_sheet(
+    State(state): State<AppState>,
+    Path((project_id, file_id, layout)): Path<(u64, u64, String)>,
+) -> Result<Json<SheetComposition>> {
+    find_project_file(&state.db, project_id, file_id).await?;
+
+    let layout = PersistService::get_layout(&state.db, file_id, &layout).await?;
+    let viewports = PersistService::get_viewports_by_layout(&state.db, file_id, &layout.name)
+        .await?
+        .iter()
+        .filter_map(SheetViewport::from_entity)
+        .collect();
+
+    Ok(Json(SheetComposition { layout, viewports }))
+}
@@ -0,0 +1,34 @@
+use crate::error::Result;
+use crate::models::Linetype;
+use crate::routes::files::find_project_file;
+use crate::routes::AppState;
+use crate::services::PersistService;
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LinetypesResponse {
+    pub file_id: u64,
+    /// `$LTSCALE` from the drawing header; pattern lengths are multiplied by
+    /// this and by any per-entity `line_type_scale` when drawn.
+    pub linetype_scale: f64,
+    pub linetypes: Vec<Linetype>,
+}
+
+pub async fn list_linetypes(
+    State(state): State<AppState>,
+    Path((project_id, file_id)): Path<(u64, u64)>,
+) -> Result<Json<LinetypesResponse>> {
+    let file = find_project_file(&state.db, project_id, file_id).await?;
+
+    let linetypes = PersistService::get_linetypes_by_file(&state.db, file_id).await?;
+
+    Ok(Json(LinetypesResponse {
+        file_id,
+        linetype_scale: file.linetype_scale.unwrap_or(1.0),
+        linetypes,
+    }))
+}
@@ -1,7 +1,25 @@
+pub mod entities;
+pub mod export;
 pub mod files;
 pub mod health;
+pub mod layouts;
+pub mod linetypes;
+pub mod objects;
 pub mod parse;
+pub mod raw_records;
+pub mod references;
+pub mod styles;
+pub mod tables;
 
-pub use files::{upload_file, AppState};
+pub use entities::{get_entity_by_handle, list_entities};
+pub use export::{export_dxf, export_geojson, export_svg, get_thumbnail, plot_pdf};
+pub use files::{get_file_content, import_geojson, upload_file, AppState};
 pub use health::health_check;
-pub use parse::parse_file;
+pub use layouts::{get_sheet, list_layouts};
+pub use linetypes::list_linetypes;
+pub use objects::{get_group_entities, list_groups, list_named_objects};
+pub use parse::{get_parse_status, list_diagnostics, parse_file};
+pub use raw_records::list_raw_records;
+pub use references::list_references;
+pub use styles::{list_dim_styles, list_text_styles};
+pub use tables::{get_table, list_tables};
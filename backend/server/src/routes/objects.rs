@@ -0,0 +1,65 @@
+use crate::error::Result;
+use crate::models::{Entity, EntityGroup, NamedObject};
+use crate::routes::files::find_project_file;
+use crate::routes::AppState;
+use crate::services::PersistService;
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct NamedObjectsResponse {
+    pub file_id: u64,
+    pub objects: Vec<NamedObject>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GroupsResponse {
+    pub file_id: u64,
+    pub groups: Vec<EntityGroup>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GroupEntitiesResponse {
+    pub group: EntityGroup,
+    pub entities: Vec<Entity>,
+}
+
+pub async fn list_named_objects(
+    State(state): State<AppState>,
+    Path((project_id, file_id)): Path<(u64, u64)>,
+) -> Result<Json<NamedObjectsResponse>> {
+    find_project_file(&state.db, project_id, file_id).await?;
+
+    let objects = PersistService::get_named_objects_by_file(&state.db, file_id).await?;
+
+    Ok(Json(NamedObjectsResponse { file_id, objects }))
+}
+
+pub async fn list_groups(
+    State(state): State<AppState>,
+    Path((project_id, file_id)): Path<(u64, u64)>,
+) -> Result<Json<GroupsResponse>> {
+    find_project_file(&state.db, project_id, file_id).await?;
+
+    let groups = PersistService::get_entity_groups_by_file(&state.db, file_id).await?;
+
+    Ok(Json(GroupsResponse { file_id, groups }))
+}
+
+/// Returns a group with its member entities, for selecting the whole group.
+/// Members that were not parsed are left out.
+pub async fn get_group_entities(
+    State(state): State<AppState>,
+    Path((project_id, file_id, name)): Path<(u64, u64, String)>,
+) -> Result<Json<GroupEntitiesResponse>> {
+    find_project_file(&state.db, project_id, file_id).await?;
+
+    let group = PersistService::get_entity_group(&state.db, file_id, &name).await?;
+    let handles: Vec<String> = serde_json::from_value(group.members.clone()).unwrap_or_default();
+    let entities = PersistService::get_entities_by_handles(&state.db, file_id, &handles).await?;
+
+    Ok(Json(GroupEntitiesResponse { group, entities }))
+}
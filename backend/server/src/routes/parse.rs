@@ -1,14 +1,23 @@
 use crate::error::{AppError, Result};
-use crate::models::File;
+use crate::models::{Diagnostic, ParseJob, UnsupportedItem};
+use crate::routes::export::store_thumbnails;
+use crate::routes::files::{file_extension, find_project_file, DRAWING_EXTENSIONS};
 use crate::routes::AppState;
-use crate::services::{dxf_parser, PersistService};
+use crate::services::PersistService;
 use axum::{
-    extract::{Path, State},
+    extract::{Path, Query, State},
     http::StatusCode,
     Json,
 };
+use dxf_parser::{DxfParser, EntityHandlerRegistry, ParseMode};
 use serde::{Deserialize, Serialize};
+use std::io::BufReader;
 use std::path::PathBuf;
+use std::sync::Arc;
+use tokio::sync::mpsc;
+
+/// Parse events buffered between the parser thread and the database writer.
+const PARSE_EVENT_BUFFER: usize = 1024;
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ParseResponse {
@@ -17,27 +26,43 @@ pub struct ParseResponse {
     pub file_id: u64,
 }
 
+#[derive(Debug, Default, Deserialize)]
+pub struct ParseParams {
+    /// `lenient` (default) skips malformed entities and records diagnostics;
+    /// `strict` fails the parse on the first malformed value.
+    #[serde(default)]
+    pub mode: ParseMode,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ParseStatusResponse {
+    pub file_id: u64,
+    pub parse_status: Option<String>,
+    pub parse_error: Option<String>,
+    /// Progress of the latest parse; `None` if the file was never parsed.
+    pub progress: Option<ParseJob>,
+    /// Share of the file read so far, from 0 to 100.
+    pub percent: Option<f64>,
+    /// Entity types, sections and tables skipped by the last parse.
+    pub unsupported: Vec<UnsupportedItem>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DiagnosticsResponse {
+    pub file_id: u64,
+    pub diagnostics: Vec<Diagnostic>,
+}
+
 pub async fn parse_file(
     State(state): State<AppState>,
     Path((project_id, file_id)): Path<(u64, u64)>,
+    Query(params): Query<ParseParams>,
 ) -> Result<(StatusCode, Json<ParseResponse>)> {
-    let _project = sqlx::query_as::<_, crate::models::Project>(
-        "SELECT id, name, description, created_at, updated_at FROM projects WHERE id = ?",
-    )
-    .bind(project_id)
-    .fetch_optional(&state.db)
-    .await?
-    .ok_or(AppError::ProjectNotFound)?;
-
-    let file = sqlx::query_as::<_, File>(
-        "SELECT id, project_id, name, size, storage_path, checksum, encoding, parse_status, parse_error, created_at, updated_at 
-         FROM files WHERE id = ? AND project_id = ?",
-    )
-    .bind(file_id)
-    .bind(project_id)
-    .fetch_optional(&state.db)
-    .await?
-    .ok_or_else(|| AppError::Internal("File not found".to_string()))?;
+    let file = find_project_file(&state.db, project_id, file_id).await?;
+
+    if !DRAWING_EXTENSIONS.contains(&file_extension(&file.name).as_str()) {
+        return Err(AppError::InvalidFileType);
+    }
 
     let storage_path = file
         .storage_path
@@ -55,11 +80,27 @@ pub async fn parse_file(
         ));
     }
 
+    if let Err(e) =
+        PersistService::start_parse_job(&state.db, file_id, params.mode, file.size).await
+    {
+        let _ = PersistService::mark_parse_failed(&state.db, file_id, &e.to_string()).await;
+        return Err(e);
+    }
+
     let file_storage = state.file_storage.clone();
     let db = state.db.clone();
+    let entity_handlers = state.entity_handlers.clone();
 
     tokio::spawn(async move {
-        let result = parse_file_task(file_storage.as_ref(), &db, file_id, &storage_path).await;
+        let result = parse_file_task(
+            file_storage.as_ref(),
+            &db,
+            file_id,
+            &storage_path,
+            params.mode,
+            entity_handlers,
+        )
+        .await;
 
         if let Err(e) = result {
             tracing::error!("Parse error for file {}: {:?}", file_id, e);
@@ -82,16 +123,87 @@ async fn parse_file_task(
     db: &sqlx::MySqlPool,
     file_id: u64,
     storage_path: &str,
+    mode: ParseMode,
+    entity_handlers: Arc<EntityHandlerRegistry>,
 ) -> Result<()> {
     let full_path = PathBuf::from(&file_storage.root_path()).join(storage_path);
 
-    let content = tokio::fs::read_to_string(&full_path)
-        .await
-        .map_err(|e| AppError::Internal(format!("Failed to read file: {}", e)))?;
+    // Parsing is blocking work; entities are handed to the persist layer
+    // through a bounded channel so a large file is never held in memory.
+    let (tx, mut rx) = mpsc::channel(PARSE_EVENT_BUFFER);
+    let parser = tokio::task::spawn_blocking(move || {
+        let file = std::fs::File::open(&full_path)
+            .map_err(|e| AppError::Internal(format!("Failed to read file: {}", e)));
+        let parser = file.and_then(|file| {
+            DxfParser::with_handlers(BufReader::new(file), mode, entity_handlers)
+                .map_err(AppError::from)
+        });
 
-    let parsed_layers = dxf_parser::parse_dxf(&content)?;
+        match parser {
+            Ok(parser) => {
+                for event in parser {
+                    if tx.blocking_send(event.map_err(AppError::from)).is_err() {
+                        break;
+                    }
+                }
+            }
+            Err(e) => {
+                let _ = tx.blocking_send(Err(e));
+            }
+        }
+    });
+
+    let result = PersistService::persist_parse_events(db, file_id, &mut rx).await;
 
-    PersistService::persist_parsed_data(db, file_id, parsed_layers).await?;
+    // Unblock the parser if persisting stopped early, then wait for it.
+    drop(rx);
+    parser
+        .await
+        .map_err(|e| AppError::Internal(format!("DXF parser task failed: {}", e)))?;
+    result?;
+
+    // A drawing that parsed but cannot be drawn is still parsed; it just
+    // goes without a thumbnail.
+    if let Err(e) = store_thumbnails(file_storage, db, file_id, storage_path).await {
+        tracing::warn!("Thumbnails for file {} failed: {:?}", file_id, e);
+    }
 
     Ok(())
 }
+
+pub async fn get_parse_status(
+    State(state): State<AppState>,
+    Path((project_id, file_id)): Path<(u64, u64)>,
+) -> Result<Json<ParseStatusResponse>> {
+    let file = find_project_file(&state.db, project_id, file_id).await?;
+
+    let progress = PersistService::get_parse_job(&state.db, file_id).await?;
+    let unsupported = PersistService::get_unsupported_items_by_file(&state.db, file_id).await?;
+    let percent = match file.parse_status.as_deref() {
+        Some("parsed") => Some(100.0),
+        _ => progress.as_ref().and_then(ParseJob::percent),
+    };
+
+    Ok(Json(ParseStatusResponse {
+        file_id,
+        parse_status: file.parse_status,
+        parse_error: file.parse_error,
+        progress,
+        percent,
+        unsupported,
+    }))
+}
+
+pub async fn list_diagnostics(
+    State(state): State<AppState>,
+    Path((project_id, file_id)): Path<(u64, u64)>,
+) -> Result<Json<DiagnosticsResponse>> {
+    find_project_file(&state.db, project_id, file_id).await?;
+
+    let diagnostics = PersistService::get_diagnostics_by_file(&state.db, file_id).await?;
+
+    Ok(Json(DiagnosticsResponse {
+        file_id,
+        diagnostics,
+    }))
+}
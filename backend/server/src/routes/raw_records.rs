@@ -0,0 +1,60 @@
+use crate::error::Result;
+use crate::models::{RawRecord, RawSection};
+use crate::routes::entities::{Pagination, DEFAULT_PAGE_SIZE, MAX_PAGE_SIZE};
+use crate::routes::files::find_project_file;
+use crate::routes::AppState;
+use crate::services::PersistService;
+use axum::{
+    extract::{Path, Query, State},
+    Json,
+};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Default, Deserialize)]
+pub struct RawRecordQueryParams {
+    /// `entities`, `objects` or `blocks`; all of them by default.
+    pub section: Option<RawSection>,
+    pub page: Option<u64>,
+    pub page_size: Option<u64>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RawRecordsResponse {
+    pub data: Vec<RawRecord>,
+    pub pagination: Pagination,
+}
+
+/// Lists the entities, objects and blocks the parser kept verbatim, in file order,
+/// for writing the drawing back out.
+pub async fn list_raw_records(
+    State(state): State<AppState>,
+    Path((project_id, file_id)): Path<(u64, u64)>,
+    Query(params): Query<RawRecordQueryParams>,
+) -> Result<Json<RawRecordsResponse>> {
+    find_project_file(&state.db, project_id, file_id).await?;
+
+    let page = params.page.unwrap_or(1).max(1);
+    let page_size = params
+        .page_size
+        .unwrap_or(DEFAULT_PAGE_SIZE)
+        .clamp(1, MAX_PAGE_SIZE);
+
+    let (data, total) = PersistService::query_raw_records(
+        &state.db,
+        file_id,
+        params.section,
+        page_size,
+        (page - 1) * page_size,
+    )
+    .await?;
+
+    Ok(Json(RawRecordsResponse {
+        data,
+        pagination: Pagination {
+            page,
+            page_size,
+            total,
+            total_pages: total.div_ceil(page_size),
+        },
+    }))
+}
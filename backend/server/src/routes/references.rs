@@ -0,0 +1,64 @@
+use crate::error::Result;
+use crate::models::ExternalReference;
+use crate::routes::files::find_project_file;
+use crate::routes::AppState;
+use crate::services::PersistService;
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+use serde::{Deserialize, Serialize};
+
+/// An image or underlay definition, with the project file its path resolves
+/// to. `resolved_file_id` is `None` when no file of that name was uploaded.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ResolvedReference {
+    #[serde(flatten)]
+    pub reference: ExternalReference,
+    pub resolved_file_id: Option<u64>,
+    pub content_url: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ReferencesResponse {
+    pub file_id: u64,
+    pub references: Vec<ResolvedReference>,
+}
+
+/// Lists the images and underlays referenced by a drawing. Paths saved by
+/// CAD applications are absolute on the author's machine, so they are
+/// matched by file name (case-insensitively) against the other files of the
+/// project; the newest upload wins.
+pub async fn list_references(
+    State(state): State<AppState>,
+    Path((project_id, file_id)): Path<(u64, u64)>,
+) -> Result<Json<ReferencesResponse>> {
+    find_project_file(&state.db, project_id, file_id).await?;
+
+    let references = PersistService::get_external_references_by_file(&state.db, file_id).await?;
+    let files = PersistService::get_files_by_project(&state.db, project_id).await?;
+
+    let references = references
+        .into_iter()
+        .map(|reference| {
+            let resolved_file_id = files
+                .iter()
+                .find(|file| {
+                    file.id != file_id && file.name.eq_ignore_ascii_case(reference.file_name())
+                })
+                .map(|file| file.id);
+
+            ResolvedReference {
+                content_url: resolved_file_id
+                    .map(|id| format!("/api/projects/{}/files/{}/content", project_id, id)),
+                resolved_file_id,
+                reference,
+            }
+        })
+        .collect();
+
+    Ok(Json(ReferencesResponse {
+        file_id,
+        references,
+    }))
+}
@@ -0,0 +1,31 @@
+use crate::error::Result;
+use crate::models::{DimStyle, TextStyle};
+use crate::routes::files::find_project_file;
+use crate::routes::AppState;
+use crate::services::PersistService;
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+
+pub async fn list_text_styles(
+    State(state): State<AppState>,
+    Path((project_id, file_id)): Path<(u64, u64)>,
+) -> Result<Json<Vec<TextStyle>>> {
+    find_project_file(&state.db, project_id, file_id).await?;
+
+    let text_styles = PersistService::get_text_styles_by_file(&state.db, file_id).await?;
+
+    Ok(Json(text_styles))
+}
+
+pub async fn list_dim_styles(
+    State(state): State<AppState>,
+    Path((project_id, file_id)): Path<(u64, u64)>,
+) -> Result<Json<Vec<DimStyle>>> {
+    find_project_file(&state.db, project_id, file_id).await?;
+
+    let dim_styles = PersistService::get_dim_styles_by_file(&state.db, file_id).await?;
+
+    Ok(Json(dim_styles))
+}
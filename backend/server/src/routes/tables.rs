@@ -0,0 +1,81 @@
+use crate::error::{AppError, Result};
+use crate::models::TableGrid;
+use crate::routes::files::{content_disposition, find_project_file};
+use crate::routes::AppState;
+use crate::services::PersistService;
+use axum::{
+    extract::{Path, Query, State},
+    http::header,
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TableFormat {
+    #[default]
+    Json,
+    Csv,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct TableParams {
+    #[serde(default)]
+    pub format: TableFormat,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TablesResponse {
+    pub file_id: u64,
+    pub tables: Vec<TableGrid>,
+}
+
+pub async fn list_tables(
+    State(state): State<AppState>,
+    Path((project_id, file_id)): Path<(u64, u64)>,
+) -> Result<Json<TablesResponse>> {
+    find_project_file(&state.db, project_id, file_id).await?;
+
+    let tables = PersistService::get_tables_by_file(&state.db, file_id)
+        .await?
+        .iter()
+        .filter_map(TableGrid::from_entity)
+        .collect();
+
+    Ok(Json(TablesResponse { file_id, tables }))
+}
+
+/// Returns one table by its entity handle, as JSON rows or as CSV
+/// (`?format=csv`).
+pub async fn get_table(
+    State(state): State<AppState>,
+    Path((project_id, file_id, handle)): Path<(u64, u64, String)>,
+    Query(params): Query<TableParams>,
+) -> Result<Response> {
+    find_project_file(&state.db, project_id, file_id).await?;
+
+    let entity = match PersistService::get_entity_by_handle(&state.db, file_id, &handle).await {
+        Err(AppError::EntityNotFound) => return Err(AppError::TableNotFound),
+        result => result?,
+    };
+    let table = TableGrid::from_entity(&entity).ok_or(AppError::TableNotFound)?;
+
+    Ok(match params.format {
+        TableFormat::Json => Json(table).into_response(),
+        TableFormat::Csv => {
+            let file_name = format!("table-{}.csv", handle.to_ascii_uppercase());
+            (
+                [
+                    (header::CONTENT_TYPE, "text/csv; charset=utf-8".to_string()),
+                    (
+                        header::CONTENT_DISPOSITION,
+                        content_disposition("attachment", &file_name),
+                    ),
+                ],
+                table.to_csv(),
+            )
+                .into_response()
+        }
+    })
+}
@@ -50,6 +50,38 @@ impl FileStorage {
         Ok((relative_path, checksum))
     }
 
+    /// Stores a file derived from a stored one, such as a thumbnail, in
+    /// the same directory under `name`, replacing an earlier version.
+    pub async fn store_derived(&self, storage_path: &str, name: &str, data: &[u8]) -> Result<()> {
+        let file_path = self.derived_path(storage_path, name);
+        let mut file = fs::File::create(&file_path).await?;
+        file.write_all(data).await?;
+        file.flush().await?;
+        Ok(())
+    }
+
+    /// A file stored by `store_derived`, or `None` if there is none.
+    pub async fn read_derived(&self, storage_path: &str, name: &str) -> Result<Option<Vec<u8>>> {
+        match fs::read(self.derived_path(storage_path, name)).await {
+            Ok(data) => Ok(Some(data)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Removes a file stored by `store_derived`, if there is one.
+    pub async fn remove_derived(&self, storage_path: &str, name: &str) -> Result<()> {
+        match fs::remove_file(self.derived_path(storage_path, name)).await {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+            _ => Ok(()),
+        }
+    }
+
+    fn derived_path(&self, storage_path: &str, name: &str) -> PathBuf {
+        let file_path = self.root_path.join(storage_path);
+        file_path.parent().unwrap_or(&self.root_path).join(name)
+    }
+
     fn calculate_checksum(&self, data: &[u8]) -> String {
         let mut hasher = Sha256::new();
         hasher.update(data);
@@ -85,6 +117,57 @@ mod tests {
         assert_eq!(checksum.len(), 64);
     }
 
+    #[tokio::test]
+    async fn test_store_derived_file_next_to_original() {
+        let temp_dir = tempdir().unwrap();
+        let storage = FileStorage::new(temp_dir.path().to_path_buf());
+
+        let (path, _) = storage.store_file(b"0\nEOF\n", "test.dxf").await.unwrap();
+        let path = path.to_str().unwrap();
+        assert_eq!(
+            storage
+                .read_derived(path, "thumbnail-256.png")
+                .await
+                .unwrap(),
+            None
+        );
+
+        storage
+            .store_derived(path, "thumbnail-256.png", b"png")
+            .await
+            .unwrap();
+
+        assert!(temp_dir
+            .path()
+            .join(path)
+            .with_file_name("thumbnail-256.png")
+            .exists());
+        assert_eq!(
+            storage
+                .read_derived(path, "thumbnail-256.png")
+                .await
+                .unwrap(),
+            Some(b"png".to_vec())
+        );
+
+        storage
+            .remove_derived(path, "thumbnail-256.png")
+            .await
+            .unwrap();
+        assert_eq!(
+            storage
+                .read_derived(path, "thumbnail-256.png")
+                .await
+                .unwrap(),
+            None
+        );
+        // Removing a file that is not there is not an error.
+        storage
+            .remove_derived(path, "thumbnail-256.png")
+            .await
+            .unwrap();
+    }
+
     #[tokio::test]
     async fn test_detect_encoding() {
         let storage = FileStorage::new(PathBuf::from("test"));
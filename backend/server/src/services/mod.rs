@@ -1,4 +1,3 @@
-pub mod dxf_parser;
 pub mod file_storage;
 pub mod persist;
 
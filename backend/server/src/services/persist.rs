@@ -1,16 +1,61 @@
 use crate::error::{AppError, Result};
-use crate::models::{BoundingBox, CreateEntityInput, CreateLayerInput, Entity, Layer};
-use crate::services::dxf_parser::ParsedLayer;
-use sqlx::{MySqlConnection, MySqlPool};
+use crate::models::{
+    BoundingBox, CreateDiagnosticInput, CreateDimStyleInput, CreateEntityGroupInput,
+    CreateEntityInput, CreateExternalReferenceInput, CreateLayerInput, CreateLayoutInput,
+    CreateLinetypeInput, CreateNamedObjectInput, CreateRawRecordInput, CreateTextStyleInput,
+    CreateUnsupportedItemInput, Diagnostic, DimStyle, ElevationRange, Entity, EntityFilter,
+    EntityGroup, ExternalReference, File, Layer, Layout, Linetype, NamedObject, ParseJob,
+    RawRecord, RawSection, TextStyle, UnsupportedItem, MODEL_LAYOUT,
+};
+use dxf_parser::{DrawingTables, ParseEvent, ParseMode, ParseProgress, ParsedDrawing, ParsedLayer};
+use sqlx::{MySql, MySqlConnection, MySqlPool, QueryBuilder};
+use std::collections::hash_map::Entry;
 use std::collections::HashMap;
+use tokio::sync::mpsc;
+
+/// Number of entities written per multi-row INSERT.
+pub const ENTITY_CHUNK_SIZE: usize = 500;
 
 pub struct PersistService;
 
+/// Extent of a layer accumulated while its entities stream in.
+struct LayerExtent {
+    layer_id: u64,
+    bbox: Option<BoundingBox>,
+    z_range: Option<ElevationRange>,
+}
+
+impl LayerExtent {
+    fn include(&mut self, entity: &CreateEntityInput) {
+        let entity_bbox = BoundingBox::new(entity.min_x, entity.min_y, entity.max_x, entity.max_y);
+        if let Some(bbox) = self.bbox.as_mut() {
+            bbox.merge(&entity_bbox);
+        } else {
+            self.bbox = Some(entity_bbox);
+        }
+
+        let entity_z_range = ElevationRange {
+            min_z: entity.min_z,
+            max_z: entity.max_z,
+        };
+        if let Some(z_range) = self.z_range.as_mut() {
+            z_range.merge(&entity_z_range);
+        } else {
+            self.z_range = Some(entity_z_range);
+        }
+    }
+}
+
 impl PersistService {
-    pub async fn persist_parsed_data(
+    /// Writes a parsed drawing as it streams in from the parser. Entities
+    /// are inserted in chunks of [`ENTITY_CHUNK_SIZE`]; only the current
+    /// chunk and one bounding box per layer are held in memory. Everything is
+    /// written in one transaction, so an error in the stream leaves the
+    /// previously parsed data in place.
+    pub async fn persist_parse_events(
         pool: &MySqlPool,
         file_id: u64,
-        layers: HashMap<String, ParsedLayer>,
+        events: &mut mpsc::Receiver<Result<ParseEvent>>,
     ) -> Result<()> {
         let mut tx = pool.begin().await?;
 
@@ -26,34 +71,194 @@ impl PersistService {
             .execute(&mut *tx)
             .await?;
 
-        for (_, parsed_layer) in layers {
-            let layer_id = Self::create_layer(&mut tx, file_id, &parsed_layer.input).await?;
+        sqlx::query("DELETE FROM layouts WHERE file_id = ?")
+            .bind(file_id)
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query("DELETE FROM named_objects WHERE file_id = ?")
+            .bind(file_id)
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query("DELETE FROM entity_groups WHERE file_id = ?")
+            .bind(file_id)
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query("DELETE FROM external_references WHERE file_id = ?")
+            .bind(file_id)
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query("DELETE FROM raw_records WHERE file_id = ?")
+            .bind(file_id)
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query("DELETE FROM linetypes WHERE file_id = ?")
+            .bind(file_id)
+            .execute(&mut *tx)
+            .await?;
 
-            let mut layer_bbox: Option<BoundingBox> = None;
+        sqlx::query("DELETE FROM text_styles WHERE file_id = ?")
+            .bind(file_id)
+            .execute(&mut *tx)
+            .await?;
 
-            for entity_input in parsed_layer.entities {
-                Self::create_entity(&mut tx, layer_id, &entity_input).await?;
+        sqlx::query("DELETE FROM dim_styles WHERE file_id = ?")
+            .bind(file_id)
+            .execute(&mut *tx)
+            .await?;
 
-                let entity_bbox = BoundingBox::new(
-                    entity_input.min_x,
-                    entity_input.min_y,
-                    entity_input.max_x,
-                    entity_input.max_y,
-                );
+        sqlx::query("DELETE FROM parse_diagnostics WHERE file_id = ?")
+            .bind(file_id)
+            .execute(&mut *tx)
+            .await?;
 
-                if let Some(ref mut bbox) = layer_bbox {
-                    bbox.merge(&entity_bbox);
-                } else {
-                    layer_bbox = Some(entity_bbox);
+        sqlx::query("DELETE FROM unsupported_items WHERE file_id = ?")
+            .bind(file_id)
+            .execute(&mut *tx)
+            .await?;
+
+        let mut layers: HashMap<String, LayerExtent> = HashMap::new();
+        let mut chunk: Vec<(u64, CreateEntityInput)> = Vec::with_capacity(ENTITY_CHUNK_SIZE);
+        let mut tables: Option<DrawingTables> = None;
+
+        while let Some(event) = events.recv().await {
+            match event? {
+                ParseEvent::Layer(input) => {
+                    // Layer names are unique regardless of case, as in the
+                    // layers table's collation.
+                    if let Entry::Vacant(entry) = layers.entry(input.name.to_lowercase()) {
+                        let layer_id = Self::create_layer(&mut tx, file_id, &input).await?;
+                        entry.insert(LayerExtent {
+                            layer_id,
+                            bbox: None,
+                            z_range: None,
+                        });
+                    }
+                }
+                ParseEvent::Entity { layer, entity } => {
+                    let Some(extent) = layers.get_mut(&layer.to_lowercase()) else {
+                        return Err(AppError::Internal(format!(
+                            "entity references unknown layer {}",
+                            layer
+                        )));
+                    };
+
+                    // Layer extents cover model space only, so that title
+                    // blocks on paper do not stretch them.
+                    if entity.layout == MODEL_LAYOUT {
+                        extent.include(&entity);
+                    }
+
+                    chunk.push((extent.layer_id, entity));
+                    if chunk.len() >= ENTITY_CHUNK_SIZE {
+                        Self::create_entities(&mut tx, &chunk).await?;
+                        chunk.clear();
+                    }
+                }
+                ParseEvent::Diagnostic(diagnostic) => {
+                    Self::create_diagnostic(&mut tx, file_id, &diagnostic).await?;
+                }
+                ParseEvent::Progress(progress) => {
+                    // Written through the pool so that it is visible before
+                    // the transaction commits.
+                    Self::update_parse_progress(pool, file_id, &progress).await?;
                 }
+                ParseEvent::Unsupported(items) => {
+                    for item in &items {
+                        Self::create_unsupported_item(&mut tx, file_id, item).await?;
+                    }
+                }
+                ParseEvent::RawRecord(record) => {
+                    Self::create_raw_record(&mut tx, file_id, &record).await?;
+                }
+                ParseEvent::Tables(drawing_tables) => tables = Some(drawing_tables),
             }
+        }
+
+        let tables = tables.ok_or_else(|| {
+            AppError::Internal("DXF parser stopped before the end of the drawing".to_string())
+        })?;
+
+        Self::create_entities(&mut tx, &chunk).await?;
+
+        for layout in &tables.layouts {
+            Self::create_layout(&mut tx, file_id, layout).await?;
+        }
 
-            if let Some(bbox) = layer_bbox {
-                Self::update_layer_bbox(&mut tx, layer_id, &bbox).await?;
+        for named_object in &tables.named_objects {
+            Self::create_named_object(&mut tx, file_id, named_object).await?;
+        }
+
+        for group in &tables.groups {
+            Self::create_entity_group(&mut tx, file_id, group).await?;
+        }
+
+        for reference in &tables.references {
+            Self::create_external_reference(&mut tx, file_id, reference).await?;
+        }
+
+        for (block, layout) in tables.layout_renames() {
+            sqlx::query(
+                "UPDATE entities e JOIN layers l ON l.id = e.layer_id 
+                 SET e.layout = ? WHERE l.file_id = ? AND e.layout = ?",
+            )
+            .bind(layout)
+            .bind(file_id)
+            .bind(block)
+            .execute(&mut *tx)
+            .await?;
+
+            sqlx::query("UPDATE raw_records SET layout = ? WHERE file_id = ? AND layout = ?")
+                .bind(layout)
+                .bind(file_id)
+                .bind(block)
+                .execute(&mut *tx)
+                .await?;
+        }
+
+        for (handle, draw_order) in &tables.draw_order {
+            sqlx::query(
+                "UPDATE entities e JOIN layers l ON l.id = e.layer_id 
+                 SET e.draw_order = ? WHERE l.file_id = ? AND e.handle = ?",
+            )
+            .bind(draw_order)
+            .bind(file_id)
+            .bind(handle)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        for extent in layers.values() {
+            if let (Some(bbox), Some(z_range)) = (&extent.bbox, &extent.z_range) {
+                Self::update_layer_bbox(&mut tx, extent.layer_id, bbox, z_range).await?;
             }
         }
 
-        sqlx::query("UPDATE files SET parse_status = 'parsed', parse_error = NULL WHERE id = ?")
+        for linetype in &tables.linetypes {
+            Self::create_linetype(&mut tx, file_id, linetype).await?;
+        }
+
+        for text_style in &tables.text_styles {
+            Self::create_text_style(&mut tx, file_id, text_style).await?;
+        }
+
+        for dim_style in &tables.dim_styles {
+            Self::create_dim_style(&mut tx, file_id, dim_style).await?;
+        }
+
+        sqlx::query(
+            "UPDATE files SET parse_status = 'parsed', parse_error = NULL, linetype_scale = ? WHERE id = ?",
+        )
+        .bind(tables.linetype_scale)
+        .bind(file_id)
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query("UPDATE parse_jobs SET finished_at = CURRENT_TIMESTAMP(6) WHERE file_id = ?")
             .bind(file_id)
             .execute(&mut *tx)
             .await?;
@@ -70,6 +275,11 @@ impl PersistService {
             .execute(pool)
             .await?;
 
+        sqlx::query("UPDATE parse_jobs SET finished_at = CURRENT_TIMESTAMP(6) WHERE file_id = ?")
+            .bind(file_id)
+            .execute(pool)
+            .await?;
+
         Ok(())
     }
 
@@ -84,6 +294,60 @@ impl PersistService {
         Ok(result.rows_affected() > 0)
     }
 
+    /// Resets the progress row of a file at the start of a parse.
+    pub async fn start_parse_job(
+        pool: &MySqlPool,
+        file_id: u64,
+        mode: ParseMode,
+        bytes_total: Option<u64>,
+    ) -> Result<()> {
+        sqlx::query(
+            "INSERT INTO parse_jobs (file_id, mode, bytes_total, bytes_read, entities_parsed, section, started_at, finished_at) 
+             VALUES (?, ?, ?, 0, 0, NULL, CURRENT_TIMESTAMP(6), NULL) 
+             ON DUPLICATE KEY UPDATE mode = VALUES(mode), bytes_total = VALUES(bytes_total), 
+                bytes_read = 0, entities_parsed = 0, section = NULL, 
+                started_at = CURRENT_TIMESTAMP(6), finished_at = NULL",
+        )
+        .bind(file_id)
+        .bind(mode.as_str())
+        .bind(bytes_total)
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn update_parse_progress(
+        pool: &MySqlPool,
+        file_id: u64,
+        progress: &ParseProgress,
+    ) -> Result<()> {
+        sqlx::query(
+            "UPDATE parse_jobs SET bytes_read = ?, entities_parsed = ?, section = ? WHERE file_id = ?",
+        )
+        .bind(progress.bytes_read)
+        .bind(progress.entities_parsed)
+        .bind(&progress.section)
+        .bind(file_id)
+        .execute(pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn get_parse_job(pool: &MySqlPool, file_id: u64) -> Result<Option<ParseJob>> {
+        let job = sqlx::query_as::<_, ParseJob>(
+            "SELECT file_id, mode, bytes_total, bytes_read, entities_parsed, section, 
+                    started_at, finished_at, updated_at 
+             FROM parse_jobs WHERE file_id = ?",
+        )
+        .bind(file_id)
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(job)
+    }
+
     async fn create_layer(
         conn: &mut MySqlConnection,
         file_id: u64,
@@ -106,21 +370,66 @@ impl PersistService {
         Ok(result.last_insert_id())
     }
 
-    async fn create_entity(
+    async fn create_entities(
         conn: &mut MySqlConnection,
-        layer_id: u64,
-        input: &CreateEntityInput,
-    ) -> Result<u64> {
-        let data_json = serde_json::to_string(&input.data)
-            .map_err(|e| AppError::Internal(format!("JSON serialization error: {}", e)))?;
+        entities: &[(u64, CreateEntityInput)],
+    ) -> Result<()> {
+        if entities.is_empty() {
+            return Ok(());
+        }
 
-        let result = sqlx::query(
-            "INSERT INTO entities (layer_id, entity_type, data, min_x, min_y, max_x, max_y) 
-             VALUES (?, ?, ?, ?, ?, ?, ?)",
+        let mut rows = Vec::with_capacity(entities.len());
+        for (layer_id, input) in entities {
+            let data_json = serde_json::to_string(&input.data)
+                .map_err(|e| AppError::Internal(format!("JSON serialization error: {}", e)))?;
+            let xdata_json = input
+                .xdata
+                .as_ref()
+                .map(serde_json::to_string)
+                .transpose()
+                .map_err(|e| AppError::Internal(format!("JSON serialization error: {}", e)))?;
+            rows.push((*layer_id, input, data_json, xdata_json));
+        }
+
+        let mut query = QueryBuilder::<MySql>::new(
+            "INSERT INTO entities (layer_id, layout, handle, owner_handle, extension_dictionary, \
+             draw_order, entity_type, data, xdata, min_x, min_y, max_x, max_y, min_z, max_z) ",
+        );
+        query.push_values(rows, |mut row, (layer_id, input, data_json, xdata_json)| {
+            row.push_bind(layer_id)
+                .push_bind(&input.layout)
+                .push_bind(&input.handle)
+                .push_bind(&input.owner_handle)
+                .push_bind(&input.extension_dictionary)
+                .push_bind(input.draw_order)
+                .push_bind(input.entity_type())
+                .push_bind(data_json)
+                .push_bind(xdata_json)
+                .push_bind(input.min_x)
+                .push_bind(input.min_y)
+                .push_bind(input.max_x)
+                .push_bind(input.max_y)
+                .push_bind(input.min_z)
+                .push_bind(input.max_z);
+        });
+        query.build().execute(&mut *conn).await?;
+
+        Ok(())
+    }
+
+    async fn create_layout(
+        conn: &mut MySqlConnection,
+        file_id: u64,
+        input: &CreateLayoutInput,
+    ) -> Result<()> {
+        sqlx::query(
+            "INSERT INTO layouts (file_id, name, block_name, tab_order, min_x, min_y, max_x, max_y) 
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
         )
-        .bind(layer_id)
-        .bind(&input.entity_type)
-        .bind(data_json)
+        .bind(file_id)
+        .bind(&input.name)
+        .bind(&input.block_name)
+        .bind(input.tab_order)
         .bind(input.min_x)
         .bind(input.min_y)
         .bind(input.max_x)
@@ -128,6 +437,161 @@ impl PersistService {
         .execute(&mut *conn)
         .await?;
 
+        Ok(())
+    }
+
+    async fn create_named_object(
+        conn: &mut MySqlConnection,
+        file_id: u64,
+        input: &CreateNamedObjectInput,
+    ) -> Result<()> {
+        sqlx::query(
+            "INSERT INTO named_objects (file_id, path, handle, object_type) VALUES (?, ?, ?, ?)",
+        )
+        .bind(file_id)
+        .bind(&input.path)
+        .bind(&input.handle)
+        .bind(&input.object_type)
+        .execute(&mut *conn)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn create_entity_group(
+        conn: &mut MySqlConnection,
+        file_id: u64,
+        input: &CreateEntityGroupInput,
+    ) -> Result<()> {
+        let members_json = serde_json::to_string(&input.members)
+            .map_err(|e| AppError::Internal(format!("JSON serialization error: {}", e)))?;
+
+        sqlx::query(
+            "INSERT INTO entity_groups (file_id, name, handle, description, is_selectable, members) 
+             VALUES (?, ?, ?, ?, ?, ?)",
+        )
+        .bind(file_id)
+        .bind(&input.name)
+        .bind(&input.handle)
+        .bind(&input.description)
+        .bind(input.is_selectable)
+        .bind(members_json)
+        .execute(&mut *conn)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn create_raw_record(
+        conn: &mut MySqlConnection,
+        file_id: u64,
+        input: &CreateRawRecordInput,
+    ) -> Result<()> {
+        sqlx::query(
+            "INSERT INTO raw_records (file_id, section, record_type, handle, owner_handle, layout, position, content) 
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(file_id)
+        .bind(input.section.as_str())
+        .bind(&input.record_type)
+        .bind(&input.handle)
+        .bind(&input.owner_handle)
+        .bind(&input.layout)
+        .bind(input.position)
+        .bind(&input.content)
+        .execute(&mut *conn)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn create_external_reference(
+        conn: &mut MySqlConnection,
+        file_id: u64,
+        input: &CreateExternalReferenceInput,
+    ) -> Result<()> {
+        sqlx::query(
+            "INSERT INTO external_references (file_id, handle, kind, path, name, width_px, height_px) 
+             VALUES (?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(file_id)
+        .bind(&input.handle)
+        .bind(input.kind.as_str())
+        .bind(&input.path)
+        .bind(&input.name)
+        .bind(input.width_px)
+        .bind(input.height_px)
+        .execute(&mut *conn)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn create_linetype(
+        conn: &mut MySqlConnection,
+        file_id: u64,
+        input: &CreateLinetypeInput,
+    ) -> Result<u64> {
+        let elements_json = serde_json::to_string(&input.elements)
+            .map_err(|e| AppError::Internal(format!("JSON serialization error: {}", e)))?;
+
+        let result = sqlx::query(
+            "INSERT INTO linetypes (file_id, name, description, pattern_length, elements) 
+             VALUES (?, ?, ?, ?, ?)",
+        )
+        .bind(file_id)
+        .bind(&input.name)
+        .bind(&input.description)
+        .bind(input.pattern_length)
+        .bind(elements_json)
+        .execute(&mut *conn)
+        .await?;
+
+        Ok(result.last_insert_id())
+    }
+
+    async fn create_text_style(
+        conn: &mut MySqlConnection,
+        file_id: u64,
+        input: &CreateTextStyleInput,
+    ) -> Result<u64> {
+        let result = sqlx::query(
+            "INSERT INTO text_styles (file_id, name, font_file, bigfont_file, fixed_height, width_factor, oblique_angle, flags) 
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(file_id)
+        .bind(&input.name)
+        .bind(&input.font_file)
+        .bind(&input.bigfont_file)
+        .bind(input.fixed_height)
+        .bind(input.width_factor)
+        .bind(input.oblique_angle)
+        .bind(input.flags)
+        .execute(&mut *conn)
+        .await?;
+
+        Ok(result.last_insert_id())
+    }
+
+    async fn create_dim_style(
+        conn: &mut MySqlConnection,
+        file_id: u64,
+        input: &CreateDimStyleInput,
+    ) -> Result<u64> {
+        let properties_json = serde_json::to_string(&input.properties)
+            .map_err(|e| AppError::Internal(format!("JSON serialization error: {}", e)))?;
+
+        let result = sqlx::query(
+            "INSERT INTO dim_styles (file_id, name, text_style, properties) 
+             VALUES (?, ?, ?, ?)",
+        )
+        .bind(file_id)
+        .bind(&input.name)
+        .bind(&input.text_style)
+        .bind(properties_json)
+        .execute(&mut *conn)
+        .await?;
+
         Ok(result.last_insert_id())
     }
 
@@ -135,15 +599,60 @@ impl PersistService {
         conn: &mut MySqlConnection,
         layer_id: u64,
         bbox: &BoundingBox,
+        z_range: &ElevationRange,
     ) -> Result<()> {
-        sqlx::query("UPDATE layers SET min_x = ?, min_y = ?, max_x = ?, max_y = ? WHERE id = ?")
-            .bind(bbox.min_x)
-            .bind(bbox.min_y)
-            .bind(bbox.max_x)
-            .bind(bbox.max_y)
-            .bind(layer_id)
-            .execute(&mut *conn)
-            .await?;
+        sqlx::query(
+            "UPDATE layers SET min_x = ?, min_y = ?, max_x = ?, max_y = ?, min_z = ?, max_z = ? WHERE id = ?",
+        )
+        .bind(bbox.min_x)
+        .bind(bbox.min_y)
+        .bind(bbox.max_x)
+        .bind(bbox.max_y)
+        .bind(z_range.min_z)
+        .bind(z_range.max_z)
+        .bind(layer_id)
+        .execute(&mut *conn)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn create_diagnostic(
+        conn: &mut MySqlConnection,
+        file_id: u64,
+        input: &CreateDiagnosticInput,
+    ) -> Result<()> {
+        sqlx::query(
+            "INSERT INTO parse_diagnostics (file_id, line, group_code, entity_type, entity_handle, severity, message) 
+             VALUES (?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(file_id)
+        .bind(input.line)
+        .bind(input.group_code)
+        .bind(&input.entity_type)
+        .bind(&input.entity_handle)
+        .bind(input.severity.as_str())
+        .bind(&input.message)
+        .execute(&mut *conn)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn create_unsupported_item(
+        conn: &mut MySqlConnection,
+        file_id: u64,
+        input: &CreateUnsupportedItemInput,
+    ) -> Result<()> {
+        sqlx::query(
+            "INSERT INTO unsupported_items (file_id, kind, name, count) VALUES (?, ?, ?, ?)",
+        )
+        .bind(file_id)
+        .bind(input.kind.as_str())
+        .bind(&input.name)
+        .bind(input.count)
+        .execute(&mut *conn)
+        .await?;
 
         Ok(())
     }
@@ -151,7 +660,7 @@ impl PersistService {
     pub async fn get_layers_by_file(pool: &MySqlPool, file_id: u64) -> Result<Vec<Layer>> {
         let layers = sqlx::query_as::<_, Layer>(
             "SELECT id, file_id, name, is_locked, is_visible, color, line_type, line_weight, 
-                    min_x, min_y, max_x, max_y, created_at, updated_at 
+                    min_x, min_y, max_x, max_y, min_z, max_z, created_at, updated_at 
              FROM layers WHERE file_id = ? ORDER BY name",
         )
         .bind(file_id)
@@ -163,16 +672,541 @@ impl PersistService {
 
     pub async fn get_entities_by_layer(pool: &MySqlPool, layer_id: u64) -> Result<Vec<Entity>> {
         let entities = sqlx::query_as::<_, Entity>(
-            "SELECT id, layer_id, entity_type, data, min_x, min_y, max_x, max_y, 
+            "SELECT id, layer_id, layout, handle, owner_handle, extension_dictionary, draw_order, 
+                    entity_type, data, xdata, min_x, min_y, max_x, max_y, min_z, max_z, 
                     created_at, updated_at 
-             FROM entities WHERE layer_id = ? ORDER BY id",
+             FROM entities WHERE layer_id = ? ORDER BY draw_order, id",
+        )
+        .bind(layer_id)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(entities)
+    }
+
+    /// All entities of a file, by draw order.
+    pub async fn get_entities_by_file(pool: &MySqlPool, file_id: u64) -> Result<Vec<Entity>> {
+        let entities = sqlx::query_as::<_, Entity>(
+            "SELECT e.id, e.layer_id, e.layout, e.handle, e.owner_handle, e.extension_dictionary, 
+                    e.draw_order, e.entity_type, e.data, e.xdata, e.min_x, e.min_y, e.max_x, e.max_y, 
+                    e.min_z, e.max_z, e.created_at, e.updated_at 
+             FROM entities e JOIN layers l ON l.id = e.layer_id 
+             WHERE l.file_id = ? ORDER BY e.draw_order, e.id",
+        )
+        .bind(file_id)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(entities)
+    }
+
+    /// The entities of a file in one layout, by draw order, optionally only
+    /// those overlapping a window.
+    pub async fn get_entities_in_layout(
+        pool: &MySqlPool,
+        file_id: u64,
+        layout: &str,
+        window: Option<&BoundingBox>,
+    ) -> Result<Vec<Entity>> {
+        let mut query = QueryBuilder::<MySql>::new(
+            "SELECT e.id, e.layer_id, e.layout, e.handle, e.owner_handle, e.extension_dictionary, 
+                    e.draw_order, e.entity_type, e.data, e.xdata, e.min_x, e.min_y, e.max_x, e.max_y, 
+                    e.min_z, e.max_z, e.created_at, e.updated_at 
+             FROM entities e JOIN layers l ON l.id = e.layer_id 
+             WHERE l.file_id = ",
+        );
+        query
+            .push_bind(file_id)
+            .push(" AND e.layout = ")
+            .push_bind(layout.to_string());
+        if let Some(window) = window {
+            query
+                .push(" AND e.max_x >= ")
+                .push_bind(window.min_x)
+                .push(" AND e.min_x <= ")
+                .push_bind(window.max_x)
+                .push(" AND e.max_y >= ")
+                .push_bind(window.min_y)
+                .push(" AND e.min_y <= ")
+                .push_bind(window.max_y);
+        }
+        query.push(" ORDER BY e.draw_order, e.id");
+
+        let entities = query.build_query_as::<Entity>().fetch_all(pool).await?;
+
+        Ok(entities)
+    }
+
+    /// Up to `limit` entities of a layout in draw order, starting after the
+    /// one with the given draw order and ID; for reading a layout a page at
+    /// a time.
+    pub async fn get_entities_in_layout_page(
+        pool: &MySqlPool,
+        file_id: u64,
+        layout: &str,
+        after: Option<(u64, u64)>,
+        limit: u64,
+    ) -> Result<Vec<Entity>> {
+        let mut query = QueryBuilder::<MySql>::new(
+            "SELECT e.id, e.layer_id, e.layout, e.handle, e.owner_handle, e.extension_dictionary, 
+                    e.draw_order, e.entity_type, e.data, e.xdata, e.min_x, e.min_y, e.max_x, e.max_y, 
+                    e.min_z, e.max_z, e.created_at, e.updated_at 
+             FROM entities e JOIN layers l ON l.id = e.layer_id 
+             WHERE l.file_id = ",
+        );
+        query
+            .push_bind(file_id)
+            .push(" AND e.layout = ")
+            .push_bind(layout.to_string());
+        if let Some((draw_order, id)) = after {
+            query
+                .push(" AND (e.draw_order > ")
+                .push_bind(draw_order)
+                .push(" OR (e.draw_order = ")
+                .push_bind(draw_order)
+                .push(" AND e.id > ")
+                .push_bind(id)
+                .push("))");
+        }
+        query
+            .push(" ORDER BY e.draw_order, e.id LIMIT ")
+            .push_bind(limit);
+
+        let entities = query.build_query_as::<Entity>().fetch_all(pool).await?;
+
+        Ok(entities)
+    }
+
+    /// The extents of a layout's entities, or `None` when it has none.
+    pub async fn get_layout_extents(
+        pool: &MySqlPool,
+        file_id: u64,
+        layout: &str,
+    ) -> Result<Option<BoundingBox>> {
+        let (min_x, min_y, max_x, max_y): (Option<f64>, Option<f64>, Option<f64>, Option<f64>) =
+            sqlx::query_as(
+                "SELECT MIN(e.min_x), MIN(e.min_y), MAX(e.max_x), MAX(e.max_y) 
+                 FROM entities e JOIN layers l ON l.id = e.layer_id 
+                 WHERE l.file_id = ? AND e.layout = ?",
+            )
+            .bind(file_id)
+            .bind(layout)
+            .fetch_one(pool)
+            .await?;
+
+        Ok(match (min_x, min_y, max_x, max_y) {
+            (Some(min_x), Some(min_y), Some(max_x), Some(max_y)) => {
+                Some(BoundingBox::new(min_x, min_y, max_x, max_y))
+            }
+            _ => None,
+        })
+    }
+
+    /// Reads a parsed file back into the form the parser produced it in,
+    /// e.g. to write it out as DXF again. Diagnostics and unsupported items
+    /// are not included.
+    pub async fn load_drawing(pool: &MySqlPool, file: &File) -> Result<ParsedDrawing> {
+        let mut layers: HashMap<u64, ParsedLayer> = Self::get_layers_by_file(pool, file.id)
+            .await?
+            .into_iter()
+            .map(|layer| {
+                let layer_id = layer.id;
+                let layer = ParsedLayer {
+                    input: layer.into(),
+                    entities: Vec::new(),
+                };
+                (layer_id, layer)
+            })
+            .collect();
+        for entity in Self::get_entities_by_file(pool, file.id).await? {
+            if let Some(layer) = layers.get_mut(&entity.layer_id) {
+                layer.entities.push(entity.into());
+            }
+        }
+        let (raw_records, _) = Self::query_raw_records(pool, file.id, None, u64::MAX, 0).await?;
+
+        Ok(ParsedDrawing {
+            layers: layers
+                .into_values()
+                .map(|layer| (layer.input.name.clone(), layer))
+                .collect(),
+            linetypes: into_inputs(Self::get_linetypes_by_file(pool, file.id).await?),
+            text_styles: into_inputs(Self::get_text_styles_by_file(pool, file.id).await?),
+            dim_styles: into_inputs(Self::get_dim_styles_by_file(pool, file.id).await?),
+            layouts: into_inputs(Self::get_layouts_by_file(pool, file.id).await?),
+            named_objects: into_inputs(Self::get_named_objects_by_file(pool, file.id).await?),
+            groups: into_inputs(Self::get_entity_groups_by_file(pool, file.id).await?),
+            references: into_inputs(Self::get_external_references_by_file(pool, file.id).await?),
+            raw_records: into_inputs(raw_records),
+            linetype_scale: file.linetype_scale,
+            diagnostics: Vec::new(),
+            unsupported: Vec::new(),
+        })
+    }
+
+    pub async fn get_layer(pool: &MySqlPool, layer_id: u64) -> Result<Layer> {
+        sqlx::query_as::<_, Layer>(
+            "SELECT id, file_id, name, is_locked, is_visible, color, line_type, line_weight, 
+                    min_x, min_y, max_x, max_y, min_z, max_z, created_at, updated_at 
+             FROM layers WHERE id = ?",
         )
         .bind(layer_id)
+        .fetch_optional(pool)
+        .await?
+        .ok_or(AppError::LayerNotFound)
+    }
+
+    pub async fn get_entity_by_handle(
+        pool: &MySqlPool,
+        file_id: u64,
+        handle: &str,
+    ) -> Result<Entity> {
+        sqlx::query_as::<_, Entity>(
+            "SELECT e.id, e.layer_id, e.layout, e.handle, e.owner_handle, e.extension_dictionary, 
+                    e.draw_order, e.entity_type, e.data, e.xdata, e.min_x, e.min_y, e.max_x, e.max_y, 
+                    e.min_z, e.max_z, e.created_at, e.updated_at 
+             FROM entities e JOIN layers l ON l.id = e.layer_id 
+             WHERE l.file_id = ? AND e.handle = ?",
+        )
+        .bind(file_id)
+        .bind(handle.to_ascii_uppercase())
+        .fetch_optional(pool)
+        .await?
+        .ok_or(AppError::EntityNotFound)
+    }
+
+    /// Returns one page of a layer's entities in one layout and the total
+    /// number matching `filter`.
+    pub async fn query_entities(
+        pool: &MySqlPool,
+        layer_id: u64,
+        filter: &EntityFilter,
+        limit: u64,
+        offset: u64,
+    ) -> Result<(Vec<Entity>, u64)> {
+        fn push_filters(query: &mut QueryBuilder<'_, MySql>, layer_id: u64, filter: &EntityFilter) {
+            query
+                .push(" WHERE layer_id = ")
+                .push_bind(layer_id)
+                .push(" AND layout = ")
+                .push_bind(filter.layout.clone());
+            if let Some(bbox) = &filter.bbox {
+                query
+                    .push(" AND max_x >= ")
+                    .push_bind(bbox.min_x)
+                    .push(" AND min_x <= ")
+                    .push_bind(bbox.max_x)
+                    .push(" AND max_y >= ")
+                    .push_bind(bbox.min_y)
+                    .push(" AND min_y <= ")
+                    .push_bind(bbox.max_y);
+            }
+            if let Some(elevation) = &filter.elevation {
+                query
+                    .push(" AND max_z >= ")
+                    .push_bind(elevation.min_z)
+                    .push(" AND min_z <= ")
+                    .push_bind(elevation.max_z);
+            }
+        }
+
+        let mut count = QueryBuilder::<MySql>::new("SELECT COUNT(*) FROM entities");
+        push_filters(&mut count, layer_id, filter);
+        let (total,): (i64,) = count.build_query_as().fetch_one(pool).await?;
+
+        let mut query = QueryBuilder::<MySql>::new(
+            "SELECT id, layer_id, layout, handle, owner_handle, extension_dictionary, draw_order, 
+                    entity_type, data, xdata, min_x, min_y, max_x, max_y, min_z, max_z, 
+                    created_at, updated_at 
+             FROM entities",
+        );
+        push_filters(&mut query, layer_id, filter);
+        query
+            .push(" ORDER BY draw_order, id LIMIT ")
+            .push_bind(limit)
+            .push(" OFFSET ")
+            .push_bind(offset);
+        let entities = query.build_query_as::<Entity>().fetch_all(pool).await?;
+
+        Ok((entities, total as u64))
+    }
+
+    pub async fn get_layouts_by_file(pool: &MySqlPool, file_id: u64) -> Result<Vec<Layout>> {
+        let layouts = sqlx::query_as::<_, Layout>(
+            "SELECT id, file_id, name, block_name, tab_order, min_x, min_y, max_x, max_y, created_at 
+             FROM layouts WHERE file_id = ? ORDER BY tab_order, name",
+        )
+        .bind(file_id)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(layouts)
+    }
+
+    pub async fn get_layout(pool: &MySqlPool, file_id: u64, name: &str) -> Result<Layout> {
+        sqlx::query_as::<_, Layout>(
+            "SELECT id, file_id, name, block_name, tab_order, min_x, min_y, max_x, max_y, created_at 
+             FROM layouts WHERE file_id = ? AND name = ?",
+        )
+        .bind(file_id)
+        .bind(name)
+        .fetch_optional(pool)
+        .await?
+        .ok_or(AppError::LayoutNotFound)
+    }
+
+    pub async fn get_viewports_by_layout(
+        pool: &MySqlPool,
+        file_id: u64,
+        layout: &str,
+    ) -> Result<Vec<Entity>> {
+        let viewports = sqlx::query_as::<_, Entity>(
+            "SELECT e.id, e.layer_id, e.layout, e.handle, e.owner_handle, e.extension_dictionary, 
+                    e.draw_order, e.entity_type, e.data, e.xdata, e.min_x, e.min_y, e.max_x, e.max_y, 
+                    e.min_z, e.max_z, e.created_at, e.updated_at 
+             FROM entities e JOIN layers l ON l.id = e.layer_id 
+             WHERE l.file_id = ? AND e.layout = ? AND e.entity_type = 'VIEWPORT' 
+             ORDER BY e.draw_order, e.id",
+        )
+        .bind(file_id)
+        .bind(layout)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(viewports)
+    }
+
+    /// TABLE entities of a file, by layout and draw order.
+    pub async fn get_tables_by_file(pool: &MySqlPool, file_id: u64) -> Result<Vec<Entity>> {
+        let tables = sqlx::query_as::<_, Entity>(
+            "SELECT e.id, e.layer_id, e.layout, e.handle, e.owner_handle, e.extension_dictionary, 
+                    e.draw_order, e.entity_type, e.data, e.xdata, e.min_x, e.min_y, e.max_x, e.max_y, 
+                    e.min_z, e.max_z, e.created_at, e.updated_at 
+             FROM entities e JOIN layers l ON l.id = e.layer_id 
+             WHERE l.file_id = ? AND e.entity_type = 'TABLE' 
+             ORDER BY e.layout, e.draw_order, e.id",
+        )
+        .bind(file_id)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(tables)
+    }
+
+    pub async fn get_named_objects_by_file(
+        pool: &MySqlPool,
+        file_id: u64,
+    ) -> Result<Vec<NamedObject>> {
+        let objects = sqlx::query_as::<_, NamedObject>(
+            "SELECT id, file_id, path, handle, object_type, created_at 
+             FROM named_objects WHERE file_id = ? ORDER BY path",
+        )
+        .bind(file_id)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(objects)
+    }
+
+    pub async fn get_entity_groups_by_file(
+        pool: &MySqlPool,
+        file_id: u64,
+    ) -> Result<Vec<EntityGroup>> {
+        let groups = sqlx::query_as::<_, EntityGroup>(
+            "SELECT id, file_id, name, handle, description, is_selectable, members, created_at 
+             FROM entity_groups WHERE file_id = ? ORDER BY name",
+        )
+        .bind(file_id)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(groups)
+    }
+
+    pub async fn get_external_references_by_file(
+        pool: &MySqlPool,
+        file_id: u64,
+    ) -> Result<Vec<ExternalReference>> {
+        let references = sqlx::query_as::<_, ExternalReference>(
+            "SELECT id, file_id, handle, kind, path, name, width_px, height_px, created_at 
+             FROM external_references WHERE file_id = ? ORDER BY handle",
+        )
+        .bind(file_id)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(references)
+    }
+
+    /// Raw records of a file, optionally of one section, in file order.
+    /// Returns one page and the total number of matching records.
+    pub async fn query_raw_records(
+        pool: &MySqlPool,
+        file_id: u64,
+        section: Option<RawSection>,
+        limit: u64,
+        offset: u64,
+    ) -> Result<(Vec<RawRecord>, u64)> {
+        fn push_filters(
+            query: &mut QueryBuilder<'_, MySql>,
+            file_id: u64,
+            section: Option<RawSection>,
+        ) {
+            query.push(" WHERE file_id = ").push_bind(file_id);
+            if let Some(section) = section {
+                query.push(" AND section = ").push_bind(section.as_str());
+            }
+        }
+
+        let mut query = QueryBuilder::<MySql>::new(
+            "SELECT id, file_id, section, record_type, handle, owner_handle, layout, position, 
+                    content, created_at 
+             FROM raw_records",
+        );
+        push_filters(&mut query, file_id, section);
+        query
+            .push(" ORDER BY section, position, id LIMIT ")
+            .push_bind(limit)
+            .push(" OFFSET ")
+            .push_bind(offset);
+        let records = query.build_query_as::<RawRecord>().fetch_all(pool).await?;
+
+        let mut count = QueryBuilder::<MySql>::new("SELECT COUNT(*) FROM raw_records");
+        push_filters(&mut count, file_id, section);
+        let (total,): (i64,) = count.build_query_as().fetch_one(pool).await?;
+
+        Ok((records, total as u64))
+    }
+
+    /// Files of a project, newest first, for resolving referenced paths.
+    pub async fn get_files_by_project(pool: &MySqlPool, project_id: u64) -> Result<Vec<File>> {
+        let files = sqlx::query_as::<_, File>(
+            "SELECT id, project_id, name, size, storage_path, checksum, encoding, parse_status, parse_error, linetype_scale, created_at, updated_at 
+             FROM files WHERE project_id = ? ORDER BY id DESC",
+        )
+        .bind(project_id)
         .fetch_all(pool)
         .await?;
 
+        Ok(files)
+    }
+
+    pub async fn get_entity_group(
+        pool: &MySqlPool,
+        file_id: u64,
+        name: &str,
+    ) -> Result<EntityGroup> {
+        sqlx::query_as::<_, EntityGroup>(
+            "SELECT id, file_id, name, handle, description, is_selectable, members, created_at 
+             FROM entity_groups WHERE file_id = ? AND name = ?",
+        )
+        .bind(file_id)
+        .bind(name)
+        .fetch_optional(pool)
+        .await?
+        .ok_or(AppError::GroupNotFound)
+    }
+
+    /// Returns the entities of a file with the given handles, in file order.
+    pub async fn get_entities_by_handles(
+        pool: &MySqlPool,
+        file_id: u64,
+        handles: &[String],
+    ) -> Result<Vec<Entity>> {
+        if handles.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut query = QueryBuilder::<MySql>::new(
+            "SELECT e.id, e.layer_id, e.layout, e.handle, e.owner_handle, e.extension_dictionary, 
+                    e.draw_order, e.entity_type, e.data, e.xdata, e.min_x, e.min_y, e.max_x, e.max_y, 
+                    e.min_z, e.max_z, e.created_at, e.updated_at 
+             FROM entities e JOIN layers l ON l.id = e.layer_id 
+             WHERE l.file_id = ",
+        );
+        query.push_bind(file_id).push(" AND e.handle IN (");
+        let mut separated = query.separated(", ");
+        for handle in handles {
+            separated.push_bind(handle);
+        }
+        query.push(") ORDER BY e.draw_order, e.id");
+
+        let entities = query.build_query_as::<Entity>().fetch_all(pool).await?;
+
         Ok(entities)
     }
+
+    pub async fn get_linetypes_by_file(pool: &MySqlPool, file_id: u64) -> Result<Vec<Linetype>> {
+        let linetypes = sqlx::query_as::<_, Linetype>(
+            "SELECT id, file_id, name, description, pattern_length, elements, created_at, updated_at 
+             FROM linetypes WHERE file_id = ? ORDER BY name",
+        )
+        .bind(file_id)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(linetypes)
+    }
+
+    pub async fn get_text_styles_by_file(pool: &MySqlPool, file_id: u64) -> Result<Vec<TextStyle>> {
+        let text_styles = sqlx::query_as::<_, TextStyle>(
+            "SELECT id, file_id, name, font_file, bigfont_file, fixed_height, width_factor, 
+                    oblique_angle, flags, created_at, updated_at 
+             FROM text_styles WHERE file_id = ? ORDER BY name",
+        )
+        .bind(file_id)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(text_styles)
+    }
+
+    pub async fn get_dim_styles_by_file(pool: &MySqlPool, file_id: u64) -> Result<Vec<DimStyle>> {
+        let dim_styles = sqlx::query_as::<_, DimStyle>(
+            "SELECT id, file_id, name, text_style, properties, created_at, updated_at 
+             FROM dim_styles WHERE file_id = ? ORDER BY name",
+        )
+        .bind(file_id)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(dim_styles)
+    }
+
+    pub async fn get_unsupported_items_by_file(
+        pool: &MySqlPool,
+        file_id: u64,
+    ) -> Result<Vec<UnsupportedItem>> {
+        let items = sqlx::query_as::<_, UnsupportedItem>(
+            "SELECT id, file_id, kind, name, count, created_at 
+             FROM unsupported_items WHERE file_id = ? ORDER BY kind, count DESC, name",
+        )
+        .bind(file_id)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(items)
+    }
+
+    pub async fn get_diagnostics_by_file(
+        pool: &MySqlPool,
+        file_id: u64,
+    ) -> Result<Vec<Diagnostic>> {
+        let diagnostics = sqlx::query_as::<_, Diagnostic>(
+            "SELECT id, file_id, line, group_code, entity_type, entity_handle, severity, message, 
+                    created_at 
+             FROM parse_diagnostics WHERE file_id = ? ORDER BY line, id",
+        )
+        .bind(file_id)
+        .fetch_all(pool)
+        .await?;
+
+        Ok(diagnostics)
+    }
+}
+
+fn into_inputs<T, U: From<T>>(rows: Vec<T>) -> Vec<U> {
+    rows.into_iter().map(U::from).collect()
 }
 
 #[cfg(test)]
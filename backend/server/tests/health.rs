@@ -1,7 +1,6 @@
 use serde_json::Value;
 use server::{config::Config, create_app};
 use sqlx::mysql::MySqlPoolOptions;
-use std::path::PathBuf;
 use tempfile::TempDir;
 use tokio::net::TcpListener;
 
@@ -54,7 +53,7 @@ async fn test_health_endpoint_returns_200() {
 
     let client = reqwest::Client::new();
     let response = client
-        .get(&format!("{}/health", server_url))
+        .get(format!("{}/health", server_url))
         .send()
         .await
         .expect("Failed to send request");
@@ -77,7 +76,7 @@ async fn test_health_endpoint_checks_db_connection() {
 
     let client = reqwest::Client::new();
     let response = client
-        .get(&format!("{}/health", server_url))
+        .get(format!("{}/health", server_url))
         .send()
         .await
         .expect("Failed to send request");
@@ -1,10 +1,19 @@
 use reqwest::multipart;
-use serde_json::Value;
+use serde_json::{json, Value};
 use server::{config::Config, create_app};
 use sqlx::mysql::MySqlPoolOptions;
 use tempfile::TempDir;
 use tokio::net::TcpListener;
 
+type LayerBboxRow = (
+    u64,
+    String,
+    Option<f64>,
+    Option<f64>,
+    Option<f64>,
+    Option<f64>,
+);
+
 async fn setup_test_server() -> (String, TempDir, sqlx::MySqlPool) {
     dotenvy::dotenv().ok();
 
@@ -44,6 +53,7 @@ async fn setup_test_server() -> (String, TempDir, sqlx::MySqlPool) {
             encoding VARCHAR(50) NULL,
             parse_status ENUM('uploaded', 'parsing', 'parsed', 'failed') NOT NULL DEFAULT 'uploaded',
             parse_error TEXT NULL,
+            linetype_scale DOUBLE NULL,
             created_at DATETIME(6) NOT NULL DEFAULT CURRENT_TIMESTAMP(6),
             updated_at DATETIME(6) NOT NULL DEFAULT CURRENT_TIMESTAMP(6) ON UPDATE CURRENT_TIMESTAMP(6),
             CONSTRAINT fk_files_project FOREIGN KEY (project_id) REFERENCES projects(id) ON DELETE CASCADE,
@@ -69,6 +79,8 @@ async fn setup_test_server() -> (String, TempDir, sqlx::MySqlPool) {
             min_y DOUBLE NULL,
             max_x DOUBLE NULL,
             max_y DOUBLE NULL,
+            min_z DOUBLE NULL,
+            max_z DOUBLE NULL,
             created_at DATETIME(6) NOT NULL DEFAULT CURRENT_TIMESTAMP(6),
             updated_at DATETIME(6) NOT NULL DEFAULT CURRENT_TIMESTAMP(6) ON UPDATE CURRENT_TIMESTAMP(6),
             CONSTRAINT fk_layers_file FOREIGN KEY (file_id) REFERENCES files(id) ON DELETE CASCADE,
@@ -84,12 +96,20 @@ async fn setup_test_server() -> (String, TempDir, sqlx::MySqlPool) {
         "CREATE TABLE IF NOT EXISTS entities (
             id BIGINT UNSIGNED AUTO_INCREMENT PRIMARY KEY,
             layer_id BIGINT UNSIGNED NOT NULL,
-            entity_type ENUM('LINE', 'POLYLINE', 'ARC', 'CIRCLE', 'TEXT', 'INSERT') NOT NULL,
+            layout VARCHAR(255) NOT NULL DEFAULT 'Model',
+            handle VARCHAR(16) NULL,
+            owner_handle VARCHAR(16) NULL,
+            extension_dictionary VARCHAR(16) NULL,
+            draw_order BIGINT UNSIGNED NOT NULL DEFAULT 0,
+            entity_type VARCHAR(32) NOT NULL,
             data JSON NOT NULL,
+            xdata JSON NULL,
             min_x DOUBLE NOT NULL,
             min_y DOUBLE NOT NULL,
             max_x DOUBLE NOT NULL,
             max_y DOUBLE NOT NULL,
+            min_z DOUBLE NOT NULL DEFAULT 0,
+            max_z DOUBLE NOT NULL DEFAULT 0,
             created_at DATETIME(6) NOT NULL DEFAULT CURRENT_TIMESTAMP(6),
             updated_at DATETIME(6) NOT NULL DEFAULT CURRENT_TIMESTAMP(6) ON UPDATE CURRENT_TIMESTAMP(6),
             CONSTRAINT fk_entities_layer FOREIGN KEY (layer_id) REFERENCES layers(id) ON DELETE CASCADE,
@@ -97,13 +117,223 @@ async fn setup_test_server() -> (String, TempDir, sqlx::MySqlPool) {
             INDEX idx_entities_min_x (min_x),
             INDEX idx_entities_min_y (min_y),
             INDEX idx_entities_max_x (max_x),
-            INDEX idx_entities_max_y (max_y)
+            INDEX idx_entities_max_y (max_y),
+            INDEX idx_entities_min_z (min_z),
+            INDEX idx_entities_max_z (max_z),
+            INDEX idx_entities_handle (handle),
+            INDEX idx_entities_layer_layout (layer_id, layout),
+            INDEX idx_entities_layer_layout_draw_order (layer_id, layout, draw_order)
         ) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci"
     )
     .execute(&pool)
     .await
     .expect("Failed to create entities table");
 
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS linetypes (
+            id BIGINT UNSIGNED AUTO_INCREMENT PRIMARY KEY,
+            file_id BIGINT UNSIGNED NOT NULL,
+            name VARCHAR(255) NOT NULL,
+            description VARCHAR(255) NULL,
+            pattern_length DOUBLE NOT NULL DEFAULT 0,
+            elements JSON NOT NULL,
+            created_at DATETIME(6) NOT NULL DEFAULT CURRENT_TIMESTAMP(6),
+            updated_at DATETIME(6) NOT NULL DEFAULT CURRENT_TIMESTAMP(6) ON UPDATE CURRENT_TIMESTAMP(6),
+            CONSTRAINT fk_linetypes_file FOREIGN KEY (file_id) REFERENCES files(id) ON DELETE CASCADE,
+            UNIQUE KEY uk_linetypes_file_name (file_id, name),
+            INDEX idx_linetypes_file_id (file_id)
+        ) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci"
+    )
+    .execute(&pool)
+    .await
+    .expect("Failed to create linetypes table");
+
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS text_styles (
+            id BIGINT UNSIGNED AUTO_INCREMENT PRIMARY KEY,
+            file_id BIGINT UNSIGNED NOT NULL,
+            name VARCHAR(255) NOT NULL,
+            font_file VARCHAR(255) NULL,
+            bigfont_file VARCHAR(255) NULL,
+            fixed_height DOUBLE NOT NULL DEFAULT 0,
+            width_factor DOUBLE NOT NULL DEFAULT 1,
+            oblique_angle DOUBLE NOT NULL DEFAULT 0,
+            flags INT NOT NULL DEFAULT 0,
+            created_at DATETIME(6) NOT NULL DEFAULT CURRENT_TIMESTAMP(6),
+            updated_at DATETIME(6) NOT NULL DEFAULT CURRENT_TIMESTAMP(6) ON UPDATE CURRENT_TIMESTAMP(6),
+            CONSTRAINT fk_text_styles_file FOREIGN KEY (file_id) REFERENCES files(id) ON DELETE CASCADE,
+            UNIQUE KEY uk_text_styles_file_name (file_id, name),
+            INDEX idx_text_styles_file_id (file_id)
+        ) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci"
+    )
+    .execute(&pool)
+    .await
+    .expect("Failed to create text_styles table");
+
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS dim_styles (
+            id BIGINT UNSIGNED AUTO_INCREMENT PRIMARY KEY,
+            file_id BIGINT UNSIGNED NOT NULL,
+            name VARCHAR(255) NOT NULL,
+            text_style VARCHAR(255) NULL,
+            properties JSON NOT NULL,
+            created_at DATETIME(6) NOT NULL DEFAULT CURRENT_TIMESTAMP(6),
+            updated_at DATETIME(6) NOT NULL DEFAULT CURRENT_TIMESTAMP(6) ON UPDATE CURRENT_TIMESTAMP(6),
+            CONSTRAINT fk_dim_styles_file FOREIGN KEY (file_id) REFERENCES files(id) ON DELETE CASCADE,
+            UNIQUE KEY uk_dim_styles_file_name (file_id, name),
+            INDEX idx_dim_styles_file_id (file_id)
+        ) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci"
+    )
+    .execute(&pool)
+    .await
+    .expect("Failed to create dim_styles table");
+
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS parse_diagnostics (
+            id BIGINT UNSIGNED AUTO_INCREMENT PRIMARY KEY,
+            file_id BIGINT UNSIGNED NOT NULL,
+            line BIGINT UNSIGNED NOT NULL,
+            group_code INT NULL,
+            entity_type VARCHAR(32) NULL,
+            entity_handle VARCHAR(32) NULL,
+            severity ENUM('error', 'warning') NOT NULL,
+            message TEXT NOT NULL,
+            created_at DATETIME(6) NOT NULL DEFAULT CURRENT_TIMESTAMP(6),
+            CONSTRAINT fk_parse_diagnostics_file FOREIGN KEY (file_id) REFERENCES files(id) ON DELETE CASCADE,
+            INDEX idx_parse_diagnostics_file_line (file_id, line)
+        ) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci"
+    )
+    .execute(&pool)
+    .await
+    .expect("Failed to create parse_diagnostics table");
+
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS parse_jobs (
+            file_id BIGINT UNSIGNED PRIMARY KEY,
+            mode VARCHAR(16) NOT NULL,
+            bytes_total BIGINT UNSIGNED NULL,
+            bytes_read BIGINT UNSIGNED NOT NULL DEFAULT 0,
+            entities_parsed BIGINT UNSIGNED NOT NULL DEFAULT 0,
+            section VARCHAR(32) NULL,
+            started_at DATETIME(6) NOT NULL DEFAULT CURRENT_TIMESTAMP(6),
+            finished_at DATETIME(6) NULL,
+            updated_at DATETIME(6) NOT NULL DEFAULT CURRENT_TIMESTAMP(6) ON UPDATE CURRENT_TIMESTAMP(6),
+            CONSTRAINT fk_parse_jobs_file FOREIGN KEY (file_id) REFERENCES files(id) ON DELETE CASCADE
+        ) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci"
+    )
+    .execute(&pool)
+    .await
+    .expect("Failed to create parse_jobs table");
+
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS unsupported_items (
+            id BIGINT UNSIGNED AUTO_INCREMENT PRIMARY KEY,
+            file_id BIGINT UNSIGNED NOT NULL,
+            kind ENUM('entity', 'section', 'table') NOT NULL,
+            name VARCHAR(255) NOT NULL,
+            count BIGINT UNSIGNED NOT NULL,
+            created_at DATETIME(6) NOT NULL DEFAULT CURRENT_TIMESTAMP(6),
+            CONSTRAINT fk_unsupported_items_file FOREIGN KEY (file_id) REFERENCES files(id) ON DELETE CASCADE,
+            UNIQUE KEY uk_unsupported_items_file_kind_name (file_id, kind, name)
+        ) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci"
+    )
+    .execute(&pool)
+    .await
+    .expect("Failed to create unsupported_items table");
+
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS layouts (
+            id BIGINT UNSIGNED AUTO_INCREMENT PRIMARY KEY,
+            file_id BIGINT UNSIGNED NOT NULL,
+            name VARCHAR(255) NOT NULL,
+            block_name VARCHAR(255) NULL,
+            tab_order INT NOT NULL DEFAULT 0,
+            min_x DOUBLE NOT NULL DEFAULT 0,
+            min_y DOUBLE NOT NULL DEFAULT 0,
+            max_x DOUBLE NOT NULL DEFAULT 0,
+            max_y DOUBLE NOT NULL DEFAULT 0,
+            created_at DATETIME(6) NOT NULL DEFAULT CURRENT_TIMESTAMP(6),
+            CONSTRAINT fk_layouts_file FOREIGN KEY (file_id) REFERENCES files(id) ON DELETE CASCADE,
+            UNIQUE KEY uk_layouts_file_name (file_id, name)
+        ) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci",
+    )
+    .execute(&pool)
+    .await
+    .expect("Failed to create layouts table");
+
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS named_objects (
+            id BIGINT UNSIGNED AUTO_INCREMENT PRIMARY KEY,
+            file_id BIGINT UNSIGNED NOT NULL,
+            path VARCHAR(1024) NOT NULL,
+            handle VARCHAR(16) NOT NULL,
+            object_type VARCHAR(64) NOT NULL,
+            created_at DATETIME(6) NOT NULL DEFAULT CURRENT_TIMESTAMP(6),
+            CONSTRAINT fk_named_objects_file FOREIGN KEY (file_id) REFERENCES files(id) ON DELETE CASCADE,
+            INDEX idx_named_objects_file_id (file_id)
+        ) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci"
+    )
+    .execute(&pool)
+    .await
+    .expect("Failed to create named_objects table");
+
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS entity_groups (
+            id BIGINT UNSIGNED AUTO_INCREMENT PRIMARY KEY,
+            file_id BIGINT UNSIGNED NOT NULL,
+            name VARCHAR(255) NOT NULL,
+            handle VARCHAR(16) NOT NULL,
+            description TEXT NULL,
+            is_selectable TINYINT(1) NOT NULL DEFAULT 1,
+            members JSON NOT NULL,
+            created_at DATETIME(6) NOT NULL DEFAULT CURRENT_TIMESTAMP(6),
+            CONSTRAINT fk_entity_groups_file FOREIGN KEY (file_id) REFERENCES files(id) ON DELETE CASCADE,
+            UNIQUE KEY uk_entity_groups_file_name (file_id, name)
+        ) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci"
+    )
+    .execute(&pool)
+    .await
+    .expect("Failed to create entity_groups table");
+
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS external_references (
+            id BIGINT UNSIGNED AUTO_INCREMENT PRIMARY KEY,
+            file_id BIGINT UNSIGNED NOT NULL,
+            handle VARCHAR(16) NOT NULL,
+            kind VARCHAR(16) NOT NULL,
+            path VARCHAR(1024) NOT NULL,
+            name VARCHAR(255) NULL,
+            width_px DOUBLE NULL,
+            height_px DOUBLE NULL,
+            created_at DATETIME(6) NOT NULL DEFAULT CURRENT_TIMESTAMP(6),
+            CONSTRAINT fk_external_references_file FOREIGN KEY (file_id) REFERENCES files(id) ON DELETE CASCADE,
+            UNIQUE KEY uk_external_references_file_handle (file_id, handle)
+        ) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci"
+    )
+    .execute(&pool)
+    .await
+    .expect("Failed to create external_references table");
+
+    sqlx::query(
+        "CREATE TABLE IF NOT EXISTS raw_records (
+            id BIGINT UNSIGNED AUTO_INCREMENT PRIMARY KEY,
+            file_id BIGINT UNSIGNED NOT NULL,
+            section VARCHAR(16) NOT NULL,
+            record_type VARCHAR(255) NOT NULL,
+            handle VARCHAR(16) NULL,
+            owner_handle VARCHAR(16) NULL,
+            layout VARCHAR(255) NULL,
+            position BIGINT UNSIGNED NOT NULL DEFAULT 0,
+            content LONGTEXT NOT NULL,
+            created_at DATETIME(6) NOT NULL DEFAULT CURRENT_TIMESTAMP(6),
+            CONSTRAINT fk_raw_records_file FOREIGN KEY (file_id) REFERENCES files(id) ON DELETE CASCADE,
+            INDEX idx_raw_records_file_section (file_id, section, position)
+        ) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci"
+    )
+    .execute(&pool)
+    .await
+    .expect("Failed to create raw_records table");
+
     sqlx::query("INSERT IGNORE INTO projects (id, name, description) VALUES (1, 'Test Project', 'A test project')")
         .execute(&pool)
         .await
@@ -136,6 +366,51 @@ async fn setup_test_server() -> (String, TempDir, sqlx::MySqlPool) {
     (server_url, temp_dir, pool)
 }
 
+async fn upload_and_parse(
+    client: &reqwest::Client,
+    server_url: &str,
+    content: &[u8],
+    file_name: &str,
+) -> u64 {
+    let form = multipart::Form::new().part(
+        "file",
+        multipart::Part::bytes(content.to_vec())
+            .file_name(file_name.to_string())
+            .mime_str("application/dxf")
+            .unwrap(),
+    );
+
+    let upload_response = client
+        .post(format!("{}/api/projects/1/files", server_url))
+        .multipart(form)
+        .send()
+        .await
+        .expect("Failed to send upload request");
+
+    assert_eq!(upload_response.status(), 201);
+
+    let upload_json: Value = upload_response
+        .json()
+        .await
+        .expect("Failed to parse upload JSON");
+    let file_id = upload_json["id"].as_u64().unwrap();
+
+    let parse_response = client
+        .post(format!(
+            "{}/api/projects/1/files/{}/parse",
+            server_url, file_id
+        ))
+        .send()
+        .await
+        .expect("Failed to send parse request");
+
+    assert_eq!(parse_response.status(), 200);
+
+    tokio::time::sleep(tokio::time::Duration::from_secs(2)).await;
+
+    file_id
+}
+
 #[tokio::test]
 async fn test_parse_flow_success() {
     let (server_url, _temp_dir, pool) = setup_test_server().await;
@@ -205,14 +480,7 @@ async fn test_parse_flow_success() {
 
     assert!(layer_count.0 > 0, "Expected at least one layer");
 
-    let layers: Vec<(
-        u64,
-        String,
-        Option<f64>,
-        Option<f64>,
-        Option<f64>,
-        Option<f64>,
-    )> = sqlx::query_as(
+    let layers: Vec<LayerBboxRow> = sqlx::query_as(
         "SELECT id, name, min_x, min_y, max_x, max_y FROM layers WHERE file_id = ? ORDER BY name",
     )
     .bind(file_id)
@@ -378,3 +646,1143 @@ async fn test_reparse_file() {
 
     pool.close().await;
 }
+
+#[tokio::test]
+async fn test_list_linetypes() {
+    let (server_url, _temp_dir, pool) = setup_test_server().await;
+
+    let client = reqwest::Client::new();
+    let file_id = upload_and_parse(
+        &client,
+        &server_url,
+        include_bytes!("../../fixtures/sample_tables.dxf"),
+        "linetypes_test.dxf",
+    )
+    .await;
+
+    let response = client
+        .get(format!(
+            "{}/api/projects/1/files/{}/linetypes",
+            server_url, file_id
+        ))
+        .send()
+        .await
+        .expect("Failed to send linetypes request");
+
+    assert_eq!(response.status(), 200);
+
+    let body: Value = response.json().await.expect("Failed to parse JSON");
+    assert_eq!(body["linetype_scale"], 2.0);
+
+    let linetypes = body["linetypes"].as_array().unwrap();
+    let names: Vec<&str> = linetypes
+        .iter()
+        .map(|lt| lt["name"].as_str().unwrap())
+        .collect();
+    assert_eq!(names, vec!["CENTER", "CONTINUOUS", "DASHED"]);
+
+    let dashed = &linetypes[2];
+    assert_eq!(dashed["pattern_length"], 0.75);
+    assert_eq!(dashed["elements"][0]["kind"], "dash");
+    assert_eq!(dashed["elements"][1]["kind"], "gap");
+
+    let missing = client
+        .get(format!(
+            "{}/api/projects/1/files/999999/linetypes",
+            server_url
+        ))
+        .send()
+        .await
+        .expect("Failed to send linetypes request");
+
+    assert_eq!(missing.status(), 404);
+
+    pool.close().await;
+}
+
+#[tokio::test]
+async fn test_list_text_and_dim_styles() {
+    let (server_url, _temp_dir, pool) = setup_test_server().await;
+
+    let client = reqwest::Client::new();
+    let file_id = upload_and_parse(
+        &client,
+        &server_url,
+        include_bytes!("../../fixtures/sample_tables.dxf"),
+        "styles_test.dxf",
+    )
+    .await;
+
+    let text_styles: Value = client
+        .get(format!(
+            "{}/api/projects/1/files/{}/text-styles",
+            server_url, file_id
+        ))
+        .send()
+        .await
+        .expect("Failed to send text styles request")
+        .json()
+        .await
+        .expect("Failed to parse JSON");
+
+    let text_styles = text_styles.as_array().unwrap();
+    assert_eq!(text_styles.len(), 2);
+    assert_eq!(text_styles[1]["name"], "TITLE");
+    assert_eq!(text_styles[1]["fixed_height"], 5.0);
+
+    let dim_styles: Value = client
+        .get(format!(
+            "{}/api/projects/1/files/{}/dim-styles",
+            server_url, file_id
+        ))
+        .send()
+        .await
+        .expect("Failed to send dim styles request")
+        .json()
+        .await
+        .expect("Failed to parse JSON");
+
+    assert_eq!(dim_styles[0]["name"], "ARCH");
+    assert_eq!(dim_styles[0]["text_style"], "TITLE");
+    assert_eq!(dim_styles[0]["properties"]["DIMTXT"], 2.5);
+
+    pool.close().await;
+}
+
+#[tokio::test]
+async fn test_parse_diagnostics_lenient_and_strict() {
+    let (server_url, _temp_dir, pool) = setup_test_server().await;
+
+    let content = b"0\nSECTION\n2\nENTITIES\n0\nLINE\n5\n1A\n8\nWALLS\n10\n0.0\n20\nabc\n11\n10.0\n21\n10.0\n0\nCIRCLE\n5\n1B\n8\nWALLS\n10\n5.0\n20\n5.0\n40\n2.0\n0\nENDSEC\n0\nEOF\n";
+
+    let client = reqwest::Client::new();
+    let file_id = upload_and_parse(&client, &server_url, content, "diagnostics_test.dxf").await;
+
+    let (status,): (String,) = sqlx::query_as("SELECT parse_status FROM files WHERE id = ?")
+        .bind(file_id)
+        .fetch_one(&pool)
+        .await
+        .expect("Failed to fetch file status");
+    assert_eq!(status, "parsed");
+
+    let diagnostics: Value = client
+        .get(format!(
+            "{}/api/projects/1/files/{}/diagnostics",
+            server_url, file_id
+        ))
+        .send()
+        .await
+        .expect("Failed to send diagnostics request")
+        .json()
+        .await
+        .expect("Failed to parse JSON");
+
+    let diagnostics = diagnostics["diagnostics"].as_array().unwrap();
+    let error = diagnostics
+        .iter()
+        .find(|d| d["severity"] == "error")
+        .expect("Expected an error diagnostic");
+    assert_eq!(error["line"], 14);
+    assert_eq!(error["group_code"], 20);
+    assert_eq!(error["entity_handle"], "1A");
+
+    let parse_response = client
+        .post(format!(
+            "{}/api/projects/1/files/{}/parse?mode=strict",
+            server_url, file_id
+        ))
+        .send()
+        .await
+        .expect("Failed to send parse request");
+    assert_eq!(parse_response.status(), 200);
+
+    tokio::time::sleep(tokio::time::Duration::from_secs(2)).await;
+
+    let (status, error): (String, Option<String>) =
+        sqlx::query_as("SELECT parse_status, parse_error FROM files WHERE id = ?")
+            .bind(file_id)
+            .fetch_one(&pool)
+            .await
+            .expect("Failed to fetch file status");
+    assert_eq!(status, "failed");
+    assert!(error.unwrap().contains("line 14"));
+
+    pool.close().await;
+}
+
+#[tokio::test]
+async fn test_parse_status_reports_progress() {
+    let (server_url, _temp_dir, pool) = setup_test_server().await;
+
+    let content = include_bytes!("../../fixtures/sample_tables.dxf");
+    let client = reqwest::Client::new();
+    let file_id = upload_and_parse(&client, &server_url, content, "progress_test.dxf").await;
+
+    let status: Value = client
+        .get(format!(
+            "{}/api/projects/1/files/{}/parse",
+            server_url, file_id
+        ))
+        .send()
+        .await
+        .expect("Failed to send status request")
+        .json()
+        .await
+        .expect("Failed to parse JSON");
+
+    assert_eq!(status["parse_status"], "parsed");
+    assert_eq!(status["percent"], 100.0);
+    assert_eq!(status["progress"]["mode"], "lenient");
+    assert_eq!(status["progress"]["bytes_read"], content.len() as u64);
+    assert_eq!(status["progress"]["entities_parsed"], 7);
+    assert!(status["progress"]["finished_at"].is_string());
+
+    pool.close().await;
+}
+
+#[tokio::test]
+async fn test_parse_status_lists_unsupported_items() {
+    let (server_url, _temp_dir, pool) = setup_test_server().await;
+
+    let content = b"0\nSECTION\n2\nBLOCKS\n0\nENDSEC\n0\nSECTION\n2\nENTITIES\n0\nSOLID\n8\n0\n0\nACAD_PROXY_ENTITY\n8\n0\n0\nACAD_PROXY_ENTITY\n8\n0\n0\nCIRCLE\n8\n0\n10\n0.0\n20\n0.0\n40\n1.0\n0\nENDSEC\n0\nEOF\n";
+    let client = reqwest::Client::new();
+    let file_id = upload_and_parse(&client, &server_url, content, "unsupported_test.dxf").await;
+
+    let status: Value = client
+        .get(format!(
+            "{}/api/projects/1/files/{}/parse",
+            server_url, file_id
+        ))
+        .send()
+        .await
+        .expect("Failed to send status request")
+        .json()
+        .await
+        .expect("Failed to parse JSON");
+
+    let unsupported = status["unsupported"].as_array().unwrap();
+    // Block definitions are kept, so BLOCKS is not an unsupported section.
+    assert_eq!(unsupported.len(), 2);
+    assert_eq!(unsupported[0]["kind"], "entity");
+    assert_eq!(unsupported[0]["name"], "ACAD_PROXY_ENTITY");
+    assert_eq!(unsupported[0]["count"], 2);
+    assert_eq!(unsupported[1]["name"], "SOLID");
+
+    pool.close().await;
+}
+
+#[tokio::test]
+async fn test_list_entities_by_elevation_range() {
+    let (server_url, _temp_dir, pool) = setup_test_server().await;
+
+    let content = b"0\nSECTION\n2\nENTITIES\n\
+0\nCIRCLE\n8\nSPOT\n10\n0.0\n20\n0.0\n30\n10.0\n40\n1.0\n\
+0\nCIRCLE\n8\nSPOT\n10\n5.0\n20\n5.0\n30\n20.0\n40\n1.0\n\
+0\nLINE\n8\nSPOT\n10\n0.0\n20\n0.0\n30\n15.0\n11\n1.0\n21\n1.0\n31\n25.0\n\
+0\nENDSEC\n0\nEOF\n";
+    let client = reqwest::Client::new();
+    let file_id = upload_and_parse(&client, &server_url, content, "elevation_test.dxf").await;
+
+    let layer: (u64, Option<f64>, Option<f64>) =
+        sqlx::query_as("SELECT id, min_z, max_z FROM layers WHERE file_id = ? AND name = 'SPOT'")
+            .bind(file_id)
+            .fetch_one(&pool)
+            .await
+            .expect("Failed to fetch layer");
+    assert_eq!((layer.1, layer.2), (Some(10.0), Some(25.0)));
+
+    let response: Value = client
+        .get(format!(
+            "{}/api/layers/{}/entities?min_z=18&max_z=30",
+            server_url, layer.0
+        ))
+        .send()
+        .await
+        .expect("Failed to send entities request")
+        .json()
+        .await
+        .expect("Failed to parse JSON");
+
+    let data = response["data"].as_array().unwrap();
+    let types: Vec<&str> = data
+        .iter()
+        .map(|entity| entity["entity_type"].as_str().unwrap())
+        .collect();
+    assert_eq!(types, vec!["CIRCLE", "LINE"]);
+    assert_eq!(data[0]["min_z"], 20.0);
+    assert_eq!(response["pagination"]["total"], 2);
+
+    let response = client
+        .get(format!(
+            "{}/api/layers/{}/entities?min_z=30&max_z=10",
+            server_url, layer.0
+        ))
+        .send()
+        .await
+        .expect("Failed to send entities request");
+    assert_eq!(response.status(), reqwest::StatusCode::BAD_REQUEST);
+
+    pool.close().await;
+}
+
+#[tokio::test]
+async fn test_get_entity_by_handle() {
+    let (server_url, _temp_dir, pool) = setup_test_server().await;
+
+    let content = b"0\nSECTION\n2\nENTITIES\n\
+0\nCIRCLE\n5\n2a\n330\n1F\n102\n{ACAD_XDICTIONARY\n360\n2B\n102\n}\n8\nASSETS\n\
+10\n1.0\n20\n2.0\n40\n0.5\n1001\nACME_ASSETS\n1000\nPUMP-0042\n1070\n3\n\
+0\nENDSEC\n0\nEOF\n";
+    let client = reqwest::Client::new();
+    let file_id = upload_and_parse(&client, &server_url, content, "handles_test.dxf").await;
+
+    let entity: Value = client
+        .get(format!(
+            "{}/api/projects/1/files/{}/entities/2A",
+            server_url, file_id
+        ))
+        .send()
+        .await
+        .expect("Failed to send entity request")
+        .json()
+        .await
+        .expect("Failed to parse JSON");
+
+    assert_eq!(entity["entity_type"], "CIRCLE");
+    assert_eq!(entity["handle"], "2A");
+    assert_eq!(entity["owner_handle"], "1F");
+    assert_eq!(entity["extension_dictionary"], "2B");
+    assert_eq!(entity["xdata"]["ACME_ASSETS"][0]["value"], "PUMP-0042");
+    assert_eq!(entity["xdata"]["ACME_ASSETS"][1]["value"], 3);
+
+    let response = client
+        .get(format!(
+            "{}/api/projects/1/files/{}/entities/FFFF",
+            server_url, file_id
+        ))
+        .send()
+        .await
+        .expect("Failed to send entity request");
+    assert_eq!(response.status(), reqwest::StatusCode::NOT_FOUND);
+
+    pool.close().await;
+}
+
+#[tokio::test]
+async fn test_list_entities_by_layout() {
+    let (server_url, _temp_dir, pool) = setup_test_server().await;
+
+    let content = b"0\nSECTION\n2\nTABLES\n0\nTABLE\n2\nBLOCK_RECORD\n\
+0\nBLOCK_RECORD\n5\n1F\n2\n*Model_Space\n0\nBLOCK_RECORD\n5\n1B\n2\n*Paper_Space\n\
+0\nENDTAB\n0\nENDSEC\n\
+0\nSECTION\n2\nENTITIES\n\
+0\nLINE\n330\n1F\n8\nPLAN\n10\n0.0\n20\n0.0\n11\n10.0\n21\n10.0\n\
+0\nLINE\n330\n1B\n67\n1\n8\nPLAN\n10\n0.0\n20\n0.0\n11\n400.0\n21\n280.0\n\
+0\nENDSEC\n\
+0\nSECTION\n2\nOBJECTS\n\
+0\nLAYOUT\n100\nAcDbLayout\n1\nModel\n71\n0\n330\n1F\n\
+0\nLAYOUT\n100\nAcDbLayout\n1\nTitle Sheet\n71\n1\n330\n1B\n\
+0\nENDSEC\n0\nEOF\n";
+    let client = reqwest::Client::new();
+    let file_id = upload_and_parse(&client, &server_url, content, "layouts_test.dxf").await;
+
+    let layouts: Value = client
+        .get(format!(
+            "{}/api/projects/1/files/{}/layouts",
+            server_url, file_id
+        ))
+        .send()
+        .await
+        .expect("Failed to send layouts request")
+        .json()
+        .await
+        .expect("Failed to parse JSON");
+    let names: Vec<&str> = layouts["layouts"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|layout| layout["name"].as_str().unwrap())
+        .collect();
+    assert_eq!(names, vec!["Model", "Title Sheet"]);
+
+    let layer: (u64, Option<f64>) =
+        sqlx::query_as("SELECT id, max_x FROM layers WHERE file_id = ? AND name = 'PLAN'")
+            .bind(file_id)
+            .fetch_one(&pool)
+            .await
+            .expect("Failed to fetch layer");
+    assert_eq!(layer.1, Some(10.0));
+
+    let model: Value = client
+        .get(format!("{}/api/layers/{}/entities", server_url, layer.0))
+        .send()
+        .await
+        .expect("Failed to send entities request")
+        .json()
+        .await
+        .expect("Failed to parse JSON");
+    assert_eq!(model["pagination"]["total"], 1);
+    assert_eq!(model["data"][0]["layout"], "Model");
+
+    let paper: Value = client
+        .get(format!(
+            "{}/api/layers/{}/entities?layout=Title%20Sheet",
+            server_url, layer.0
+        ))
+        .send()
+        .await
+        .expect("Failed to send entities request")
+        .json()
+        .await
+        .expect("Failed to parse JSON");
+    assert_eq!(paper["pagination"]["total"], 1);
+    assert_eq!(paper["data"][0]["max_x"], 400.0);
+
+    pool.close().await;
+}
+
+#[tokio::test]
+async fn test_sheet_composition() {
+    let (server_url, _temp_dir, pool) = setup_test_server().await;
+
+    let content = b"0\nSECTION\n2\nTABLES\n0\nTABLE\n2\nBLOCK_RECORD\n\
+0\nBLOCK_RECORD\n5\n1F\n2\n*Model_Space\n0\nBLOCK_RECORD\n5\n1B\n2\n*Paper_Space\n\
+0\nENDTAB\n0\nENDSEC\n\
+0\nSECTION\n2\nENTITIES\n\
+0\nVIEWPORT\n330\n1B\n8\n0\n10\n148.5\n20\n105.0\n40\n297.0\n41\n210.0\n68\n1\n69\n1\n45\n210.0\n\
+0\nVIEWPORT\n330\n1B\n8\n0\n10\n100.0\n20\n100.0\n40\n100.0\n41\n50.0\n68\n2\n69\n2\n\
+12\n20.0\n22\n10.0\n45\n500.0\n\
+0\nVIEWPORT\n330\n1B\n8\n0\n10\n0.0\n20\n0.0\n40\n10.0\n41\n10.0\n68\n0\n69\n3\n45\n10.0\n\
+0\nENDSEC\n\
+0\nSECTION\n2\nOBJECTS\n\
+0\nLAYOUT\n100\nAcDbLayout\n1\nSheet 1\n71\n1\n11\n297.0\n21\n210.0\n330\n1B\n\
+0\nENDSEC\n0\nEOF\n";
+    let client = reqwest::Client::new();
+    let file_id = upload_and_parse(&client, &server_url, content, "sheet_test.dxf").await;
+
+    let sheet: Value = client
+        .get(format!(
+            "{}/api/projects/1/files/{}/layouts/Sheet%201/sheet",
+            server_url, file_id
+        ))
+        .send()
+        .await
+        .expect("Failed to send sheet request")
+        .json()
+        .await
+        .expect("Failed to parse JSON");
+
+    assert_eq!(sheet["layout"]["name"], "Sheet 1");
+    let viewports = sheet["viewports"].as_array().unwrap();
+    assert_eq!(viewports.len(), 1);
+    assert_eq!(viewports[0]["viewport_id"], 2);
+    assert_eq!(viewports[0]["scale"], 0.1);
+    assert_eq!(viewports[0]["model_center"]["x"], 20.0);
+    assert_eq!(viewports[0]["model_width"], 1000.0);
+    assert_eq!(viewports[0]["paper"]["min_x"], 50.0);
+
+    let response = client
+        .get(format!(
+            "{}/api/projects/1/files/{}/layouts/Missing/sheet",
+            server_url, file_id
+        ))
+        .send()
+        .await
+        .expect("Failed to send sheet request");
+    assert_eq!(response.status(), reqwest::StatusCode::NOT_FOUND);
+
+    pool.close().await;
+}
+
+#[tokio::test]
+async fn test_group_selects_members() {
+    let (server_url, _temp_dir, pool) = setup_test_server().await;
+
+    let content = b"0\nSECTION\n2\nENTITIES\n\
+0\nCIRCLE\n5\n1A0\n8\nDOORS\n10\n0.0\n20\n0.0\n40\n1.0\n\
+0\nCIRCLE\n5\n1A1\n8\nDOORS\n10\n5.0\n20\n0.0\n40\n1.0\n\
+0\nCIRCLE\n5\n1A2\n8\nDOORS\n10\n9.0\n20\n0.0\n40\n1.0\n\
+0\nENDSEC\n\
+0\nSECTION\n2\nOBJECTS\n\
+0\nDICTIONARY\n5\nC\n3\nACAD_GROUP\n350\nD\n\
+0\nDICTIONARY\n5\nD\n330\nC\n3\nENTRANCE\n350\n2A\n\
+0\nGROUP\n5\n2A\n330\nD\n300\nFront doors\n71\n1\n340\n1A0\n340\n1A2\n\
+0\nENDSEC\n0\nEOF\n";
+    let client = reqwest::Client::new();
+    let file_id = upload_and_parse(&client, &server_url, content, "groups_test.dxf").await;
+
+    let groups: Value = client
+        .get(format!(
+            "{}/api/projects/1/files/{}/groups",
+            server_url, file_id
+        ))
+        .send()
+        .await
+        .expect("Failed to send groups request")
+        .json()
+        .await
+        .expect("Failed to parse JSON");
+    assert_eq!(groups["groups"][0]["name"], "ENTRANCE");
+    assert_eq!(groups["groups"][0]["description"], "Front doors");
+
+    let objects: Value = client
+        .get(format!(
+            "{}/api/projects/1/files/{}/objects",
+            server_url, file_id
+        ))
+        .send()
+        .await
+        .expect("Failed to send objects request")
+        .json()
+        .await
+        .expect("Failed to parse JSON");
+    assert_eq!(objects["objects"][1]["path"], "ACAD_GROUP/ENTRANCE");
+
+    let selection: Value = client
+        .get(format!(
+            "{}/api/projects/1/files/{}/groups/ENTRANCE/entities",
+            server_url, file_id
+        ))
+        .send()
+        .await
+        .expect("Failed to send group entities request")
+        .json()
+        .await
+        .expect("Failed to parse JSON");
+    let handles: Vec<&str> = selection["entities"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|entity| entity["handle"].as_str().unwrap())
+        .collect();
+    assert_eq!(handles, vec!["1A0", "1A2"]);
+
+    pool.close().await;
+}
+
+#[tokio::test]
+async fn test_image_references_resolve_to_project_files() {
+    let (server_url, _temp_dir, pool) = setup_test_server().await;
+    let client = reqwest::Client::new();
+
+    let raster = b"\x89PNG\r\n\x1a\nnot really pixels".to_vec();
+    let form = multipart::Form::new().part(
+        "file",
+        multipart::Part::bytes(raster.clone())
+            .file_name("aerial.png")
+            .mime_str("image/png")
+            .unwrap(),
+    );
+    let upload: Value = client
+        .post(format!("{}/api/projects/1/files", server_url))
+        .multipart(form)
+        .send()
+        .await
+        .expect("Failed to send upload request")
+        .json()
+        .await
+        .expect("Failed to parse upload JSON");
+    let raster_id = upload["id"].as_u64().unwrap();
+
+    let parse_raster = client
+        .post(format!(
+            "{}/api/projects/1/files/{}/parse",
+            server_url, raster_id
+        ))
+        .send()
+        .await
+        .expect("Failed to send parse request");
+    assert_eq!(parse_raster.status(), 400);
+
+    let content = b"0\nSECTION\n2\nENTITIES\n\
+0\nIMAGE\n5\n30\n8\nSITE\n10\n0.0\n20\n0.0\n11\n1.0\n21\n0.0\n12\n0.0\n22\n1.0\n\
+13\n64.0\n23\n32.0\n340\n3A\n\
+0\nDGNUNDERLAY\n5\n31\n8\nSITE\n340\n3B\n10\n5.0\n20\n5.0\n\
+0\nENDSEC\n0\nSECTION\n2\nOBJECTS\n\
+0\nIMAGEDEF\n5\n3A\n1\nC:\\Photos\\AERIAL.PNG\n10\n64.0\n20\n32.0\n\
+0\nDGNDEFINITION\n5\n3B\n1\nD:\\Survey\\missing.dgn\n2\nDefault\n\
+0\nENDSEC\n0\nEOF\n";
+    let file_id = upload_and_parse(&client, &server_url, content, "references_test.dxf").await;
+
+    let references: Value = client
+        .get(format!(
+            "{}/api/projects/1/files/{}/references",
+            server_url, file_id
+        ))
+        .send()
+        .await
+        .expect("Failed to send references request")
+        .json()
+        .await
+        .expect("Failed to parse JSON");
+    let references = references["references"].as_array().unwrap();
+    assert_eq!(references.len(), 2);
+    assert_eq!(references[0]["kind"], "image");
+    assert_eq!(references[0]["width_px"], 64.0);
+    assert_eq!(references[0]["resolved_file_id"], raster_id);
+    assert_eq!(references[1]["kind"], "dgn");
+    assert_eq!(references[1]["name"], "Default");
+    assert!(references[1]["resolved_file_id"].is_null());
+
+    let content_response = client
+        .get(format!(
+            "{}{}",
+            server_url,
+            references[0]["content_url"].as_str().unwrap()
+        ))
+        .send()
+        .await
+        .expect("Failed to send content request");
+    assert_eq!(content_response.status(), 200);
+    assert_eq!(content_response.headers()["content-type"], "image/png");
+    assert_eq!(content_response.bytes().await.unwrap().to_vec(), raster);
+
+    pool.close().await;
+}
+
+#[tokio::test]
+async fn test_list_entities_in_draw_order() {
+    let (server_url, _temp_dir, pool) = setup_test_server().await;
+
+    let content = b"0\nSECTION\n2\nENTITIES\n\
+0\nLINE\n5\n10\n8\n0\n10\n0.0\n20\n0.0\n11\n10.0\n21\n5.0\n\
+0\nWIPEOUT\n5\n11\n8\n0\n10\n0.0\n20\n0.0\n11\n10.0\n21\n0.0\n12\n0.0\n22\n5.0\n\
+71\n1\n14\n-0.5\n24\n-0.5\n14\n0.5\n24\n0.5\n\
+0\nENDSEC\n0\nSECTION\n2\nOBJECTS\n\
+0\nSORTENTSTABLE\n5\n40\n330\n1F\n100\nAcDbSortentsTable\n330\n1F\n331\n11\n5\n9\n\
+0\nENDSEC\n0\nEOF\n";
+    let client = reqwest::Client::new();
+    let file_id = upload_and_parse(&client, &server_url, content, "draw_order_test.dxf").await;
+
+    let layer: (u64,) = sqlx::query_as("SELECT id FROM layers WHERE file_id = ? AND name = '0'")
+        .bind(file_id)
+        .fetch_one(&pool)
+        .await
+        .expect("Failed to fetch layer");
+
+    let entities: Value = client
+        .get(format!("{}/api/layers/{}/entities", server_url, layer.0))
+        .send()
+        .await
+        .expect("Failed to send entities request")
+        .json()
+        .await
+        .expect("Failed to parse JSON");
+    let order: Vec<(&str, u64)> = entities["data"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|entity| {
+            (
+                entity["entity_type"].as_str().unwrap(),
+                entity["draw_order"].as_u64().unwrap(),
+            )
+        })
+        .collect();
+    assert_eq!(order, vec![("WIPEOUT", 0), ("LINE", 1)]);
+
+    pool.close().await;
+}
+
+#[tokio::test]
+async fn test_raw_records_keep_unknown_records() {
+    let (server_url, _temp_dir, pool) = setup_test_server().await;
+
+    let content = b"0\nSECTION\n2\nENTITIES\n\
+0\nACAD_PROXY_ENTITY\n5\n11\n330\n1F\n90\n498\n310\n0A0B\n\
+0\nCIRCLE\n5\n12\n8\n0\n10\n0.0\n20\n0.0\n40\n1.0\n\
+0\nENDSEC\n0\nSECTION\n2\nOBJECTS\n\
+0\nDICTIONARY\n5\nC\n3\nACME\n350\n20\n\
+0\nACME_OBJECT\n5\n20\n330\nC\n1\nkeep me\n\
+0\nENDSEC\n0\nEOF\n";
+    let client = reqwest::Client::new();
+    let file_id = upload_and_parse(&client, &server_url, content, "raw_records_test.dxf").await;
+
+    let all: Value = client
+        .get(format!(
+            "{}/api/projects/1/files/{}/raw-records",
+            server_url, file_id
+        ))
+        .send()
+        .await
+        .expect("Failed to send raw records request")
+        .json()
+        .await
+        .expect("Failed to parse JSON");
+    assert_eq!(all["pagination"]["total"], 2);
+
+    let objects: Value = client
+        .get(format!(
+            "{}/api/projects/1/files/{}/raw-records?section=objects",
+            server_url, file_id
+        ))
+        .send()
+        .await
+        .expect("Failed to send raw records request")
+        .json()
+        .await
+        .expect("Failed to parse JSON");
+    let records = objects["data"].as_array().unwrap();
+    assert_eq!(records.len(), 1);
+    assert_eq!(records[0]["record_type"], "ACME_OBJECT");
+    assert_eq!(records[0]["handle"], "20");
+    assert_eq!(
+        records[0]["content"],
+        "0\nACME_OBJECT\n5\n20\n330\nC\n1\nkeep me\n"
+    );
+
+    pool.close().await;
+}
+
+#[tokio::test]
+async fn test_table_as_json_rows_and_csv() {
+    let (server_url, _temp_dir, pool) = setup_test_server().await;
+
+    let content = b"0\nSECTION\n2\nENTITIES\n\
+0\nACAD_TABLE\n5\n50\n8\nSCHEDULE\n2\n*T1\n10\n0.0\n20\n0.0\n11\n1.0\n21\n0.0\n\
+91\n3\n92\n2\n141\n10.0\n141\n8.0\n141\n8.0\n142\n40.0\n142\n25.0\n\
+171\n1\n175\n2\n1\n{\\fArial|b1;Door schedule}\n171\n1\n1\n\n\
+171\n1\n1\nMark\n171\n1\n1\nWidth, mm\n\
+171\n1\n1\nD-01\n171\n1\n1\n900\n\
+0\nENDSEC\n0\nEOF\n";
+    let client = reqwest::Client::new();
+    let file_id = upload_and_parse(&client, &server_url, content, "table_test.dxf").await;
+
+    let tables: Value = client
+        .get(format!(
+            "{}/api/projects/1/files/{}/tables",
+            server_url, file_id
+        ))
+        .send()
+        .await
+        .expect("Failed to send tables request")
+        .json()
+        .await
+        .expect("Failed to parse JSON");
+    let table = &tables["tables"][0];
+    assert_eq!(table["handle"], "50");
+    assert_eq!(
+        table["rows"],
+        serde_json::json!([
+            ["Door schedule", ""],
+            ["Mark", "Width, mm"],
+            ["D-01", "900"]
+        ])
+    );
+    assert_eq!(
+        table["merges"],
+        serde_json::json!([{"row": 0, "column": 0, "row_span": 1, "column_span": 2}])
+    );
+
+    let csv_response = client
+        .get(format!(
+            "{}/api/projects/1/files/{}/tables/50?format=csv",
+            server_url, file_id
+        ))
+        .send()
+        .await
+        .expect("Failed to send table request");
+    assert_eq!(csv_response.status(), 200);
+    assert_eq!(
+        csv_response.headers()["content-type"],
+        "text/csv; charset=utf-8"
+    );
+    assert_eq!(
+        csv_response.text().await.unwrap(),
+        "Door schedule,\r\nMark,\"Width, mm\"\r\nD-01,900\r\n"
+    );
+
+    let missing = client
+        .get(format!(
+            "{}/api/projects/1/files/{}/tables/FFFF",
+            server_url, file_id
+        ))
+        .send()
+        .await
+        .expect("Failed to send table request");
+    assert_eq!(missing.status(), 404);
+
+    pool.close().await;
+}
+
+#[tokio::test]
+async fn test_entity_data_is_typed_and_versioned() {
+    let (server_url, _temp_dir, pool) = setup_test_server().await;
+
+    let content = b"0\nSECTION\n2\nENTITIES\n\
+0\nLINE\n5\n10\n8\n0\n6\nDASHED\n10\n0.0\n20\n0.0\n11\n10.0\n21\n5.0\n\
+0\nENDSEC\n0\nEOF\n";
+    let client = reqwest::Client::new();
+    let file_id = upload_and_parse(&client, &server_url, content, "geometry_test.dxf").await;
+
+    let layer: (u64,) = sqlx::query_as("SELECT id FROM layers WHERE file_id = ? AND name = '0'")
+        .bind(file_id)
+        .fetch_one(&pool)
+        .await
+        .expect("Failed to fetch layer");
+
+    // A row in the schema from before versioning, as in the seed data.
+    sqlx::query(
+        "INSERT INTO entities (layer_id, draw_order, entity_type, data, min_x, min_y, max_x, max_y) \
+         VALUES (?, 1, 'POLYLINE', ?, 0, 0, 100, 100)",
+    )
+    .bind(layer.0)
+    .bind(r#"{"type": "POLYLINE", "points": [{"x": 0, "y": 0}, {"x": 100, "y": 100}]}"#)
+    .execute(&pool)
+    .await
+    .expect("Failed to insert legacy entity");
+
+    let entities: Value = client
+        .get(format!("{}/api/layers/{}/entities", server_url, layer.0))
+        .send()
+        .await
+        .expect("Failed to send entities request")
+        .json()
+        .await
+        .expect("Failed to parse JSON");
+    let data = entities["data"].as_array().unwrap();
+    assert_eq!(data.len(), 2);
+
+    let line = &data[0]["data"];
+    assert_eq!(line["type"], "LINE");
+    assert_eq!(line["version"], 1);
+    assert_eq!(line["end"], json!({"x": 10.0, "y": 5.0, "z": 0.0}));
+    assert_eq!(line["line_type"], "DASHED");
+
+    let polyline = &data[1]["data"];
+    assert_eq!(polyline["version"], 1);
+    assert_eq!(
+        polyline["vertices"][1],
+        json!({"x": 100.0, "y": 100.0, "z": 0.0})
+    );
+    assert!(polyline.get("points").is_none());
+
+    pool.close().await;
+}
+
+#[tokio::test]
+async fn test_export_dxf_round_trips() {
+    let (server_url, _temp_dir, pool) = setup_test_server().await;
+
+    let client = reqwest::Client::new();
+    let file_id = upload_and_parse(
+        &client,
+        &server_url,
+        include_bytes!("../../fixtures/sample_layouts.dxf"),
+        "layouts.dxf",
+    )
+    .await;
+
+    let response = client
+        .get(format!(
+            "{}/api/projects/1/files/{}/export.dxf",
+            server_url, file_id
+        ))
+        .send()
+        .await
+        .expect("Failed to send export request");
+    assert_eq!(response.status(), 200);
+    assert_eq!(response.headers()["content-type"], "image/vnd.dxf");
+    assert_eq!(
+        response.headers()["content-disposition"],
+        "attachment; filename=\"layouts-r2018.dxf\"; filename*=UTF-8''layouts-r2018.dxf"
+    );
+    let exported = response.bytes().await.unwrap();
+
+    let reparsed_id = upload_and_parse(&client, &server_url, &exported, "exported.dxf").await;
+    let layouts_of = |id: u64| {
+        let client = client.clone();
+        let url = format!("{}/api/projects/1/files/{}/layouts", server_url, id);
+        async move {
+            client
+                .get(url)
+                .send()
+                .await
+                .expect("Failed to send layouts request")
+                .json::<Value>()
+                .await
+                .expect("Failed to parse JSON")["layouts"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|layout| layout["name"].clone())
+                .collect::<Vec<_>>()
+        }
+    };
+    assert_eq!(layouts_of(file_id).await, layouts_of(reparsed_id).await);
+
+    let count_entities = |id: u64| {
+        sqlx::query_as::<_, (i64,)>(
+            "SELECT COUNT(*) FROM entities e JOIN layers l ON l.id = e.layer_id WHERE l.file_id = ?",
+        )
+        .bind(id)
+        .fetch_one(&pool)
+    };
+    assert_eq!(
+        count_entities(file_id).await.unwrap(),
+        count_entities(reparsed_id).await.unwrap()
+    );
+
+    let r12 = client
+        .get(format!(
+            "{}/api/projects/1/files/{}/export.dxf?version=r12",
+            server_url, file_id
+        ))
+        .send()
+        .await
+        .expect("Failed to send export request")
+        .text()
+        .await
+        .unwrap();
+    assert!(r12.contains("AC1009"));
+    assert!(!r12.contains("OBJECTS"));
+
+    pool.close().await;
+}
+
+#[tokio::test]
+async fn test_export_svg() {
+    let (server_url, _temp_dir, pool) = setup_test_server().await;
+
+    let client = reqwest::Client::new();
+    let file_id = upload_and_parse(
+        &client,
+        &server_url,
+        include_bytes!("../../fixtures/sample_layouts.dxf"),
+        "layouts.dxf",
+    )
+    .await;
+    let export = |query: &str| {
+        client
+            .get(format!(
+                "{}/api/projects/1/files/{}/export.svg?{}",
+                server_url, file_id, query
+            ))
+            .send()
+    };
+
+    let response = export("width=800")
+        .await
+        .expect("Failed to send export request");
+    assert_eq!(response.status(), 200);
+    assert_eq!(response.headers()["content-type"], "image/svg+xml");
+    let svg = response.text().await.unwrap();
+    assert!(svg.starts_with("<svg"));
+    assert!(svg.contains(r#"width="800""#));
+    assert!(svg.contains("<circle"));
+    assert!(svg.contains("<path"));
+    assert!(svg.contains(&format!(r#"clip-path="url(#file-{}-window)""#, file_id)));
+
+    // The circle is on DOORS.
+    let walls = export("layers=WALLS&background=none")
+        .await
+        .expect("Failed to send export request")
+        .text()
+        .await
+        .unwrap();
+    assert!(walls.contains("<path"));
+    assert!(!walls.contains("<circle"));
+    assert!(!walls.contains("<rect width"));
+
+    let viewport = export("layout=Sheet%202&viewport=2")
+        .await
+        .expect("Failed to send export request");
+    assert_eq!(viewport.status(), 200);
+
+    let missing = export("layout=Sheet%202&viewport=9")
+        .await
+        .expect("Failed to send export request");
+    assert_eq!(missing.status(), 404);
+
+    let invalid = export("background=red")
+        .await
+        .expect("Failed to send export request");
+    assert_eq!(invalid.status(), 400);
+
+    pool.close().await;
+}
+
+#[tokio::test]
+async fn test_plot_pdf() {
+    let (server_url, _temp_dir, pool) = setup_test_server().await;
+
+    let client = reqwest::Client::new();
+    let file_id = upload_and_parse(
+        &client,
+        &server_url,
+        include_bytes!("../../fixtures/sample_layouts.dxf"),
+        "layouts.dxf",
+    )
+    .await;
+    let plot = |query: &str| {
+        client
+            .get(format!(
+                "{}/api/projects/1/files/{}/plot.pdf?{}",
+                server_url, file_id, query
+            ))
+            .send()
+    };
+
+    let response = plot("paper=a3&scale=1:100")
+        .await
+        .expect("Failed to send plot request");
+    assert_eq!(response.status(), 200);
+    assert_eq!(response.headers()["content-type"], "application/pdf");
+    let pdf = response.bytes().await.unwrap();
+    assert!(pdf.starts_with(b"%PDF-"));
+    // A3 landscape, in points.
+    assert!(String::from_utf8_lossy(&pdf).contains("/MediaBox [0 0 1190.5511 841.8898]"));
+
+    let sheet = plot("layout=Sheet%202&paper=ansi-b&orientation=portrait")
+        .await
+        .expect("Failed to send plot request");
+    assert_eq!(sheet.status(), 200);
+
+    let missing = plot("layout=Missing")
+        .await
+        .expect("Failed to send plot request");
+    assert_eq!(missing.status(), 404);
+
+    for query in ["scale=100", "paper=b5", "layers=NOPE"] {
+        let invalid = plot(query).await.expect("Failed to send plot request");
+        assert_eq!(invalid.status(), 400, "{query}");
+    }
+
+    pool.close().await;
+}
+
+#[tokio::test]
+async fn test_thumbnails_are_made_when_parsing() {
+    let (server_url, _temp_dir, pool) = setup_test_server().await;
+
+    let client = reqwest::Client::new();
+    let file_id = upload_and_parse(
+        &client,
+        &server_url,
+        include_bytes!("../../fixtures/sample_layouts.dxf"),
+        "layouts.dxf",
+    )
+    .await;
+    let thumbnail = |query: &str| {
+        client
+            .get(format!(
+                "{}/api/projects/1/files/{}/thumbnail?{}",
+                server_url, file_id, query
+            ))
+            .send()
+    };
+
+    for (query, size) in [("", 256u32), ("size=1024", 1024)] {
+        let response = thumbnail(query)
+            .await
+            .expect("Failed to send thumbnail request");
+        assert_eq!(response.status(), 200, "{query}");
+        assert_eq!(response.headers()["content-type"], "image/png");
+        let png = response.bytes().await.unwrap();
+        assert!(png.starts_with(b"\x89PNG\r\n\x1a\n"));
+        // The IHDR chunk holds the width and height.
+        assert_eq!(u32::from_be_bytes(png[16..20].try_into().unwrap()), size);
+        assert_eq!(u32::from_be_bytes(png[20..24].try_into().unwrap()), size);
+    }
+
+    let invalid = thumbnail("size=512")
+        .await
+        .expect("Failed to send thumbnail request");
+    assert_eq!(invalid.status(), 400);
+
+    pool.close().await;
+}
+
+#[tokio::test]
+async fn test_geojson_export_and_import() {
+    let (server_url, _temp_dir, pool) = setup_test_server().await;
+
+    let client = reqwest::Client::new();
+    let file_id = upload_and_parse(
+        &client,
+        &server_url,
+        include_bytes!("../../fixtures/sample_layouts.dxf"),
+        "layouts.dxf",
+    )
+    .await;
+
+    let response = client
+        .get(format!(
+            "{}/api/projects/1/files/{}/export.geojson",
+            server_url, file_id
+        ))
+        .send()
+        .await
+        .expect("Failed to send export request");
+    assert_eq!(response.status(), 200);
+    assert_eq!(response.headers()["content-type"], "application/geo+json");
+    let collection: Value = response.json().await.unwrap();
+    assert_eq!(collection["type"], "FeatureCollection");
+    let features = collection["features"].as_array().unwrap();
+    assert!(!features.is_empty());
+    assert!(features
+        .iter()
+        .all(|feature| feature["properties"]["layer"].is_string()));
+    // The hatch is a Polygon with its round hole.
+    let hatch = features
+        .iter()
+        .find(|feature| feature["properties"]["entity_type"] == "HATCH")
+        .expect("no hatch feature");
+    assert_eq!(hatch["geometry"]["type"], "Polygon");
+    assert_eq!(
+        hatch["geometry"]["coordinates"].as_array().unwrap().len(),
+        2
+    );
+    assert_eq!(hatch["properties"]["pattern"], "ANSI31");
+
+    // The export imports back as a new file with the same layers.
+    let import = client
+        .post(format!(
+            "{}/api/projects/1/files/import.geojson?name=layouts-gis",
+            server_url
+        ))
+        .header("content-type", "application/geo+json")
+        .body(collection.to_string())
+        .send()
+        .await
+        .expect("Failed to send import request");
+    assert_eq!(import.status(), 201);
+    let imported: Value = import.json().await.unwrap();
+    assert_eq!(imported["name"], "layouts-gis.dxf");
+    assert_eq!(imported["status"], "uploaded_awaiting_parse");
+
+    // A name that is taken is refused before anything is stored; without
+    // a name, imports are numbered.
+    let import = |query: &'static str| {
+        client
+            .post(format!(
+                "{}/api/projects/1/files/import.geojson{}",
+                server_url, query
+            ))
+            .body(collection.to_string())
+            .send()
+    };
+    let taken = import("?name=LAYOUTS-GIS.dxf")
+        .await
+        .expect("Failed to send import request");
+    assert_eq!(taken.status(), 409);
+    for expected in ["import.dxf", "import-2.dxf"] {
+        let response = import("").await.expect("Failed to send import request");
+        assert_eq!(response.status(), 201);
+        let imported: Value = response.json().await.unwrap();
+        assert_eq!(imported["name"], expected);
+    }
+
+    let invalid = client
+        .post(format!(
+            "{}/api/projects/1/files/import.geojson",
+            server_url
+        ))
+        .body(json!({"type": "Feature"}).to_string())
+        .send()
+        .await
+        .expect("Failed to send import request");
+    assert_eq!(invalid.status(), 422);
+
+    pool.close().await;
+}
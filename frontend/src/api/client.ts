@@ -3,10 +3,23 @@ import type {
   File,
   FileUploadResponse,
   Layer,
+  LayoutsResponse,
+  SheetComposition,
+  NamedObject,
+  EntityGroup,
+  GroupEntitiesResponse,
+  ExternalReference,
+  RawRecord,
+  RawRecordQueryParams,
+  TableGrid,
   Entity,
   ParseResponse,
+  ParseStatusResponse,
   PaginatedResponse,
   EntityQueryParams,
+  SvgExportOptions,
+  GeoJsonExportOptions,
+  PlotOptions,
   ApiError,
 } from './types';
 
@@ -114,10 +127,191 @@ export class ApiClient {
     });
   }
 
+  async getParseStatus(projectId: number, fileId: number): Promise<ParseStatusResponse> {
+    return this.request<ParseStatusResponse>(
+      `/projects/${projectId}/files/${fileId}/parse`,
+      {},
+      `parse-status-${fileId}`
+    );
+  }
+
   async getLayers(fileId: number): Promise<Layer[]> {
     return this.request<Layer[]>(`/files/${fileId}/layers`, {}, `layers-${fileId}`);
   }
 
+  async getLayouts(projectId: number, fileId: number): Promise<LayoutsResponse> {
+    return this.request<LayoutsResponse>(
+      `/projects/${projectId}/files/${fileId}/layouts`,
+      {},
+      `layouts-${fileId}`
+    );
+  }
+
+  async getSheet(projectId: number, fileId: number, layout: string): Promise<SheetComposition> {
+    return this.request<SheetComposition>(
+      `/projects/${projectId}/files/${fileId}/layouts/${encodeURIComponent(layout)}/sheet`,
+      {},
+      `sheet-${fileId}-${layout}`
+    );
+  }
+
+  async getNamedObjects(projectId: number, fileId: number): Promise<NamedObject[]> {
+    const response = await this.request<{ file_id: number; objects: NamedObject[] }>(
+      `/projects/${projectId}/files/${fileId}/objects`,
+      {},
+      `objects-${fileId}`
+    );
+    return response.objects;
+  }
+
+  async getGroups(projectId: number, fileId: number): Promise<EntityGroup[]> {
+    const response = await this.request<{ file_id: number; groups: EntityGroup[] }>(
+      `/projects/${projectId}/files/${fileId}/groups`,
+      {},
+      `groups-${fileId}`
+    );
+    return response.groups;
+  }
+
+  async getGroupEntities(
+    projectId: number,
+    fileId: number,
+    name: string
+  ): Promise<GroupEntitiesResponse> {
+    return this.request<GroupEntitiesResponse>(
+      `/projects/${projectId}/files/${fileId}/groups/${encodeURIComponent(name)}/entities`
+    );
+  }
+
+  async getReferences(projectId: number, fileId: number): Promise<ExternalReference[]> {
+    const response = await this.request<{ file_id: number; references: ExternalReference[] }>(
+      `/projects/${projectId}/files/${fileId}/references`,
+      {},
+      `references-${fileId}`
+    );
+    return response.references;
+  }
+
+  async getRawRecords(
+    projectId: number,
+    fileId: number,
+    params?: RawRecordQueryParams
+  ): Promise<PaginatedResponse<RawRecord>> {
+    const searchParams = new URLSearchParams();
+
+    if (params?.section !== undefined) {
+      searchParams.append('section', params.section);
+    }
+
+    if (params?.page !== undefined) {
+      searchParams.append('page', params.page.toString());
+    }
+
+    if (params?.page_size !== undefined) {
+      searchParams.append('page_size', params.page_size.toString());
+    }
+
+    const queryString = searchParams.toString();
+    const path = `/projects/${projectId}/files/${fileId}/raw-records${queryString ? `?${queryString}` : ''}`;
+
+    return this.request<PaginatedResponse<RawRecord>>(path);
+  }
+
+  async getTables(projectId: number, fileId: number): Promise<TableGrid[]> {
+    const response = await this.request<{ file_id: number; tables: TableGrid[] }>(
+      `/projects/${projectId}/files/${fileId}/tables`,
+      {},
+      `tables-${fileId}`
+    );
+    return response.tables;
+  }
+
+  async getTable(projectId: number, fileId: number, handle: string): Promise<TableGrid> {
+    return this.request<TableGrid>(
+      `/projects/${projectId}/files/${fileId}/tables/${encodeURIComponent(handle)}`
+    );
+  }
+
+  getTableCsvUrl(projectId: number, fileId: number, handle: string): string {
+    return `${this.baseUrl}/projects/${projectId}/files/${fileId}/tables/${encodeURIComponent(handle)}?format=csv`;
+  }
+
+  getExportDxfUrl(projectId: number, fileId: number, version: 'r2018' | 'r12' = 'r2018'): string {
+    return `${this.baseUrl}/projects/${projectId}/files/${fileId}/export.dxf?version=${version}`;
+  }
+
+  getExportSvgUrl(projectId: number, fileId: number, options: SvgExportOptions = {}): string {
+    return this.fileUrl(projectId, fileId, 'export.svg', { ...options });
+  }
+
+  getExportGeoJsonUrl(
+    projectId: number,
+    fileId: number,
+    options: GeoJsonExportOptions = {}
+  ): string {
+    return this.fileUrl(projectId, fileId, 'export.geojson', { ...options });
+  }
+
+  async importGeoJson(
+    projectId: number,
+    collection: Blob | string,
+    options: { layerProperty?: string; name?: string } = {}
+  ): Promise<FileUploadResponse> {
+    const params = new URLSearchParams();
+    if (options.layerProperty) {
+      params.set('layer_property', options.layerProperty);
+    }
+    if (options.name) {
+      params.set('name', options.name);
+    }
+    const query = params.toString();
+    const response = await fetch(
+      `${this.baseUrl}/projects/${projectId}/files/import.geojson${query ? `?${query}` : ''}`,
+      {
+        method: 'POST',
+        headers: { 'Content-Type': 'application/geo+json' },
+        body: collection,
+      }
+    );
+
+    if (!response.ok) {
+      const error: ApiError = await response.json().catch(() => ({
+        error: response.statusText,
+      }));
+      throw new Error(error.error || '导入失败');
+    }
+
+    return await response.json();
+  }
+
+  getPlotPdfUrl(projectId: number, fileId: number, options: PlotOptions = {}): string {
+    return this.fileUrl(projectId, fileId, 'plot.pdf', { ...options });
+  }
+
+  getThumbnailUrl(projectId: number, fileId: number, size: 256 | 1024 = 256): string {
+    return this.fileUrl(projectId, fileId, 'thumbnail', { size });
+  }
+
+  private fileUrl(
+    projectId: number,
+    fileId: number,
+    path: string,
+    options: Record<string, string | number | string[] | undefined>
+  ): string {
+    const params = new URLSearchParams();
+    for (const [key, value] of Object.entries(options)) {
+      if (value !== undefined) {
+        params.set(key, Array.isArray(value) ? value.join(',') : String(value));
+      }
+    }
+    const query = params.toString();
+    return `${this.baseUrl}/projects/${projectId}/files/${fileId}/${path}${query ? `?${query}` : ''}`;
+  }
+
+  getFileContentUrl(projectId: number, fileId: number): string {
+    return `${this.baseUrl}/projects/${projectId}/files/${fileId}/content`;
+  }
+
   async getLayer(layerId: number): Promise<Layer> {
     return this.request<Layer>(`/layers/${layerId}`);
   }
@@ -127,6 +321,10 @@ export class ApiClient {
     params?: EntityQueryParams
   ): Promise<PaginatedResponse<Entity>> {
     const searchParams = new URLSearchParams();
+
+    if (params?.layout !== undefined) {
+      searchParams.append('layout', params.layout);
+    }
     
     if (params?.bbox) {
       searchParams.append('min_x', params.bbox.min_x.toString());
@@ -135,6 +333,14 @@ export class ApiClient {
       searchParams.append('max_y', params.bbox.max_y.toString());
     }
     
+    if (params?.elevation?.min_z !== undefined) {
+      searchParams.append('min_z', params.elevation.min_z.toString());
+    }
+
+    if (params?.elevation?.max_z !== undefined) {
+      searchParams.append('max_z', params.elevation.max_z.toString());
+    }
+    
     if (params?.page !== undefined) {
       searchParams.append('page', params.page.toString());
     }
@@ -146,11 +352,20 @@ export class ApiClient {
     const queryString = searchParams.toString();
     const path = `/layers/${layerId}/entities${queryString ? `?${queryString}` : ''}`;
     
-    const abortKey = `entities-${layerId}-${params?.bbox ? JSON.stringify(params.bbox) : 'all'}`;
+    const filterKey = params?.layout || params?.bbox || params?.elevation
+      ? JSON.stringify({ layout: params.layout, bbox: params.bbox, elevation: params.elevation })
+      : 'all';
+    const abortKey = `entities-${layerId}-${filterKey}`;
     
     return this.request<PaginatedResponse<Entity>>(path, {}, abortKey);
   }
 
+  async getEntityByHandle(projectId: number, fileId: number, handle: string): Promise<Entity> {
+    return this.request<Entity>(
+      `/projects/${projectId}/files/${fileId}/entities/${encodeURIComponent(handle)}`
+    );
+  }
+
   abortAllRequests(): void {
     this.abortControllers.forEach((controller) => controller.abort());
     this.abortControllers.clear();
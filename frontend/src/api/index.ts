@@ -4,10 +4,37 @@ export type {
   File,
   FileUploadResponse,
   Layer,
+  Layout,
+  LayoutsResponse,
+  SheetComposition,
+  SheetViewport,
+  NamedObject,
+  EntityGroup,
+  GroupEntitiesResponse,
+  ExternalReference,
+  RawRecord,
+  RawRecordQueryParams,
+  TableGrid,
+  CellMerge,
   Entity,
+  DrawingPreview,
+  Geometry,
+  Point,
+  Point3,
+  EntityProperties,
+  ClipBoundary,
+  TableCell,
+  XDataValue,
   BoundingBox,
+  ElevationRange,
   ParseResponse,
+  ParseJob,
+  ParseStatusResponse,
+  UnsupportedItem,
   PaginatedResponse,
   EntityQueryParams,
+  SvgExportOptions,
+  GeoJsonExportOptions,
+  PlotOptions,
   ApiError,
 } from './types';
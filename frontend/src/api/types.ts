@@ -44,23 +44,331 @@ export interface Layer {
   min_y: number | null;
   max_x: number | null;
   max_y: number | null;
+  min_z: number | null;
+  max_z: number | null;
   created_at: string;
   updated_at: string;
 }
 
+export interface XDataValue {
+  code: number;
+  value: string | number | [number, number, number];
+}
+
+export interface Point {
+  x: number;
+  y: number;
+}
+
+export interface Point3 {
+  x: number;
+  y: number;
+  z: number;
+}
+
+/** Entity-level linetype, color and lineweight overrides and extrusion thickness, when set. */
+export interface EntityProperties {
+  line_type?: string;
+  line_type_scale?: number;
+  thickness?: number;
+  /** AutoCAD Color Index: 0 is BYBLOCK, 256 BYLAYER. */
+  color?: number;
+  /** 0xRRGGBB; takes precedence over `color`. */
+  true_color?: number;
+  /** Hundredths of a millimetre; -1 BYLAYER, -2 BYBLOCK, -3 default. */
+  line_weight?: number;
+}
+
+export interface ClipBoundary {
+  type: 'rectangular' | 'polygonal';
+  vertices: Point[];
+}
+
+/** A closed hatch boundary: a polyline's vertices and bulges. */
+export interface HatchLoop {
+  flags: number;
+  vertices: Point3[];
+  bulges?: number[];
+}
+
+export interface HatchPatternLine {
+  angle: number;
+  base: Point;
+  offset: Point;
+  dashes?: number[];
+}
+
+export interface TableCell {
+  text: string;
+  row_span: number;
+  column_span: number;
+  text_height: number | null;
+  block: string | null;
+}
+
+interface UnderlayGeometry {
+  insertion: Point3;
+  scale: Point3;
+  rotation: number;
+  definition: string | null;
+  flags: number;
+  contrast: number;
+  fade: number;
+  clip_boundary: ClipBoundary | null;
+}
+
+/** Entity data, tagged with the entity type and its schema version. */
+export type Geometry = { version: number } & (
+  | ({ type: 'LINE'; start: Point3; end: Point3 } & EntityProperties)
+  | ({ type: 'POLYLINE'; vertices: Point3[]; bulges?: number[]; closed?: boolean } & EntityProperties)
+  | ({
+      type: 'ARC';
+      center: Point3;
+      radius: number;
+      start_angle: number;
+      end_angle: number;
+    } & EntityProperties)
+  | ({ type: 'CIRCLE'; center: Point3; radius: number } & EntityProperties)
+  | ({
+      type: 'TEXT';
+      position: Point3;
+      text: string;
+      height: number;
+      style: string;
+      width_factor: number;
+      oblique_angle: number;
+      rotation: number;
+    } & EntityProperties)
+  | ({
+      type: 'MTEXT';
+      position: Point3;
+      text: string;
+      height: number;
+      width: number;
+      style: string;
+      width_factor: number;
+      attachment_point: number;
+      rotation: number;
+    } & EntityProperties)
+  | ({
+      type: 'DIMENSION';
+      dimension_type: number;
+      dim_style: string;
+      definition_point?: Point3;
+      text_position?: Point3;
+      point_13?: Point3;
+      point_14?: Point3;
+      point_15?: Point3;
+      point_16?: Point3;
+      block_name?: string;
+      text?: string;
+      measurement?: number;
+      text_height?: number;
+      text_style?: string;
+    } & EntityProperties)
+  | {
+      type: 'VIEWPORT';
+      center: Point3;
+      width: number;
+      height: number;
+      status: number;
+      id: number;
+      view_center: Point;
+      view_target: Point3;
+      view_direction: Point3;
+      view_height: number;
+      twist_angle: number;
+      frozen_layers: string[];
+    }
+  | {
+      type: 'IMAGE';
+      insertion: Point3;
+      u_vector: Point3;
+      v_vector: Point3;
+      width_px: number;
+      height_px: number;
+      definition: string | null;
+      display_flags: number;
+      brightness: number;
+      contrast: number;
+      fade: number;
+      clip_boundary: ClipBoundary | null;
+    }
+  | { type: 'WIPEOUT'; boundary: Point3[] }
+  | ({
+      type: 'HATCH';
+      pattern_name: string;
+      solid?: boolean;
+      style: number;
+      pattern_angle: number;
+      pattern_scale: number;
+      pattern_lines?: HatchPatternLine[];
+      loops: HatchLoop[];
+    } & EntityProperties)
+  | {
+      type: 'TABLE';
+      block_name: string | null;
+      insertion: Point3;
+      direction: Point;
+      rows: number;
+      columns: number;
+      row_heights: number[];
+      column_widths: number[];
+      cells: TableCell[][];
+    }
+  | ({ type: 'PDFUNDERLAY' } & UnderlayGeometry)
+  | ({ type: 'DWFUNDERLAY' } & UnderlayGeometry)
+  | ({ type: 'DGNUNDERLAY' } & UnderlayGeometry)
+  | { type: 'INSERT'; block_name: string; position: Point3; scale: Point3; rotation: number }
+);
+
 export interface Entity {
   id: number;
   layer_id: number;
-  entity_type: 'LINE' | 'POLYLINE' | 'ARC' | 'CIRCLE' | 'TEXT' | 'INSERT';
-  data: Record<string, unknown>;
+  layout: string;
+  handle: string | null;
+  owner_handle: string | null;
+  extension_dictionary: string | null;
+  /** Entities of a layout are drawn from the lowest value up. */
+  draw_order: number;
+  entity_type: Geometry['type'];
+  data: Geometry;
+  xdata: Record<string, XDataValue[]> | null;
   min_x: number;
   min_y: number;
   max_x: number;
   max_y: number;
+  min_z: number;
+  max_z: number;
   created_at: string;
   updated_at: string;
 }
 
+/**
+ * Result of `parseDxf` from the `dxf-parser-wasm` package: a drawing parsed
+ * in the browser, with `file_id` 0 and ids numbered from 1 in file order.
+ */
+export interface DrawingPreview {
+  layers: Layer[];
+  /** Ordered by draw order. */
+  entities: Entity[];
+}
+
+export interface Layout {
+  id: number;
+  file_id: number;
+  name: string;
+  block_name: string | null;
+  tab_order: number;
+  min_x: number;
+  min_y: number;
+  max_x: number;
+  max_y: number;
+  created_at: string;
+}
+
+export interface LayoutsResponse {
+  file_id: number;
+  layouts: Layout[];
+}
+
+export interface SheetViewport {
+  entity_id: number;
+  handle: string | null;
+  viewport_id: number;
+  paper: BoundingBox;
+  model_center: { x: number; y: number };
+  model_width: number;
+  model_height: number;
+  twist_angle: number;
+  model_bounds: BoundingBox;
+  scale: number;
+  plan_view: boolean;
+  frozen_layers: string[];
+}
+
+export interface SheetComposition {
+  layout: Layout;
+  viewports: SheetViewport[];
+}
+
+export interface NamedObject {
+  id: number;
+  file_id: number;
+  path: string;
+  handle: string;
+  object_type: string;
+  created_at: string;
+}
+
+export interface EntityGroup {
+  id: number;
+  file_id: number;
+  name: string;
+  handle: string;
+  description: string | null;
+  is_selectable: boolean;
+  members: string[];
+  created_at: string;
+}
+
+export interface GroupEntitiesResponse {
+  group: EntityGroup;
+  entities: Entity[];
+}
+
+export interface ExternalReference {
+  id: number;
+  file_id: number;
+  handle: string;
+  kind: 'image' | 'pdf' | 'dwf' | 'dgn';
+  path: string;
+  name: string | null;
+  width_px: number | null;
+  height_px: number | null;
+  created_at: string;
+  resolved_file_id: number | null;
+  content_url: string | null;
+}
+
+export interface RawRecord {
+  id: number;
+  file_id: number;
+  section: 'entities' | 'objects' | 'blocks';
+  record_type: string;
+  handle: string | null;
+  owner_handle: string | null;
+  layout: string | null;
+  position: number;
+  /** Group code / value lines in ASCII DXF form, starting with the `0` group. */
+  content: string;
+  created_at: string;
+}
+
+export interface RawRecordQueryParams {
+  section?: 'entities' | 'objects' | 'blocks';
+  page?: number;
+  page_size?: number;
+}
+
+export interface CellMerge {
+  row: number;
+  column: number;
+  row_span: number;
+  column_span: number;
+}
+
+export interface TableGrid {
+  entity_id: number;
+  handle: string | null;
+  layout: string;
+  row_heights: number[];
+  column_widths: number[];
+  /** Plain cell text by row; cells covered by a merge are empty. */
+  rows: string[][];
+  merges: CellMerge[];
+}
+
 export interface BoundingBox {
   min_x: number;
   min_y: number;
@@ -68,12 +376,47 @@ export interface BoundingBox {
   max_y: number;
 }
 
+export interface ElevationRange {
+  min_z?: number;
+  max_z?: number;
+}
+
 export interface ParseResponse {
   status: string;
   message: string;
   file_id: number;
 }
 
+export interface ParseJob {
+  file_id: number;
+  mode: 'lenient' | 'strict';
+  bytes_total: number | null;
+  bytes_read: number;
+  entities_parsed: number;
+  section: string | null;
+  started_at: string;
+  finished_at: string | null;
+  updated_at: string;
+}
+
+export interface UnsupportedItem {
+  id: number;
+  file_id: number;
+  kind: 'entity' | 'section' | 'table';
+  name: string;
+  count: number;
+  created_at: string;
+}
+
+export interface ParseStatusResponse {
+  file_id: number;
+  parse_status: File['parse_status'];
+  parse_error: string | null;
+  progress: ParseJob | null;
+  percent: number | null;
+  unsupported: UnsupportedItem[];
+}
+
 export interface PaginatedResponse<T> {
   data: T[];
   pagination: {
@@ -86,11 +429,62 @@ export interface PaginatedResponse<T> {
 
 export interface EntityQueryParams {
   layer_id?: number;
+  layout?: string;
   bbox?: BoundingBox;
+  elevation?: ElevationRange;
   page?: number;
   page_size?: number;
 }
 
+export interface SvgExportOptions {
+  layout?: string;
+  layers?: string[];
+  /** ID of a viewport of `layout`. */
+  viewport?: number;
+  min_x?: number;
+  min_y?: number;
+  max_x?: number;
+  max_y?: number;
+  width?: number;
+  height?: number;
+  /** `#rrggbb` or `none`. */
+  background?: string;
+}
+
+export type PaperSize =
+  | 'a0'
+  | 'a1'
+  | 'a2'
+  | 'a3'
+  | 'a4'
+  | 'ansi-a'
+  | 'ansi-b'
+  | 'ansi-c'
+  | 'ansi-d'
+  | 'ansi-e';
+
+export interface GeoJsonExportOptions {
+  layout?: string;
+  layers?: string[];
+  min_x?: number;
+  min_y?: number;
+  max_x?: number;
+  max_y?: number;
+}
+
+export interface PlotOptions {
+  layout?: string;
+  layers?: string[];
+  min_x?: number;
+  min_y?: number;
+  max_x?: number;
+  max_y?: number;
+  paper?: PaperSize;
+  orientation?: 'landscape' | 'portrait';
+  /** `1:100` (mm on paper per drawing unit) or `fit`. */
+  scale?: string;
+}
+
 export interface ApiError {
   error: string;
 }
@@ -0,0 +1,4 @@
+ALTER TABLE files
+DROP COLUMN linetype_scale;
+
+DROP TABLE IF EXISTS linetypes;
@@ -0,0 +1,16 @@
+CREATE TABLE IF NOT EXISTS linetypes (
+    id BIGINT UNSIGNED AUTO_INCREMENT PRIMARY KEY,
+    file_id BIGINT UNSIGNED NOT NULL,
+    name VARCHAR(255) NOT NULL,
+    description VARCHAR(255) NULL,
+    pattern_length DOUBLE NOT NULL DEFAULT 0,
+    elements JSON NOT NULL,
+    created_at DATETIME(6) NOT NULL DEFAULT CURRENT_TIMESTAMP(6),
+    updated_at DATETIME(6) NOT NULL DEFAULT CURRENT_TIMESTAMP(6) ON UPDATE CURRENT_TIMESTAMP(6),
+    CONSTRAINT fk_linetypes_file FOREIGN KEY (file_id) REFERENCES files(id) ON DELETE CASCADE,
+    UNIQUE KEY uk_linetypes_file_name (file_id, name),
+    INDEX idx_linetypes_file_id (file_id)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
+
+ALTER TABLE files
+ADD COLUMN linetype_scale DOUBLE NULL AFTER parse_error;
@@ -0,0 +1,5 @@
+ALTER TABLE entities
+MODIFY COLUMN entity_type ENUM('LINE', 'POLYLINE', 'ARC', 'CIRCLE', 'TEXT', 'INSERT') NOT NULL;
+
+DROP TABLE IF EXISTS dim_styles;
+DROP TABLE IF EXISTS text_styles;
@@ -0,0 +1,32 @@
+CREATE TABLE IF NOT EXISTS text_styles (
+    id BIGINT UNSIGNED AUTO_INCREMENT PRIMARY KEY,
+    file_id BIGINT UNSIGNED NOT NULL,
+    name VARCHAR(255) NOT NULL,
+    font_file VARCHAR(255) NULL,
+    bigfont_file VARCHAR(255) NULL,
+    fixed_height DOUBLE NOT NULL DEFAULT 0,
+    width_factor DOUBLE NOT NULL DEFAULT 1,
+    oblique_angle DOUBLE NOT NULL DEFAULT 0,
+    flags INT NOT NULL DEFAULT 0,
+    created_at DATETIME(6) NOT NULL DEFAULT CURRENT_TIMESTAMP(6),
+    updated_at DATETIME(6) NOT NULL DEFAULT CURRENT_TIMESTAMP(6) ON UPDATE CURRENT_TIMESTAMP(6),
+    CONSTRAINT fk_text_styles_file FOREIGN KEY (file_id) REFERENCES files(id) ON DELETE CASCADE,
+    UNIQUE KEY uk_text_styles_file_name (file_id, name),
+    INDEX idx_text_styles_file_id (file_id)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
+
+CREATE TABLE IF NOT EXISTS dim_styles (
+    id BIGINT UNSIGNED AUTO_INCREMENT PRIMARY KEY,
+    file_id BIGINT UNSIGNED NOT NULL,
+    name VARCHAR(255) NOT NULL,
+    text_style VARCHAR(255) NULL,
+    properties JSON NOT NULL,
+    created_at DATETIME(6) NOT NULL DEFAULT CURRENT_TIMESTAMP(6),
+    updated_at DATETIME(6) NOT NULL DEFAULT CURRENT_TIMESTAMP(6) ON UPDATE CURRENT_TIMESTAMP(6),
+    CONSTRAINT fk_dim_styles_file FOREIGN KEY (file_id) REFERENCES files(id) ON DELETE CASCADE,
+    UNIQUE KEY uk_dim_styles_file_name (file_id, name),
+    INDEX idx_dim_styles_file_id (file_id)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
+
+ALTER TABLE entities
+MODIFY COLUMN entity_type VARCHAR(32) NOT NULL;
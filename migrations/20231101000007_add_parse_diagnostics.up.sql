@@ -0,0 +1,13 @@
+CREATE TABLE IF NOT EXISTS parse_diagnostics (
+    id BIGINT UNSIGNED AUTO_INCREMENT PRIMARY KEY,
+    file_id BIGINT UNSIGNED NOT NULL,
+    line BIGINT UNSIGNED NOT NULL,
+    group_code INT NULL,
+    entity_type VARCHAR(32) NULL,
+    entity_handle VARCHAR(32) NULL,
+    severity ENUM('error', 'warning') NOT NULL,
+    message TEXT NOT NULL,
+    created_at DATETIME(6) NOT NULL DEFAULT CURRENT_TIMESTAMP(6),
+    CONSTRAINT fk_parse_diagnostics_file FOREIGN KEY (file_id) REFERENCES files(id) ON DELETE CASCADE,
+    INDEX idx_parse_diagnostics_file_line (file_id, line)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
@@ -0,0 +1,12 @@
+CREATE TABLE IF NOT EXISTS parse_jobs (
+    file_id BIGINT UNSIGNED PRIMARY KEY,
+    mode VARCHAR(16) NOT NULL,
+    bytes_total BIGINT UNSIGNED NULL,
+    bytes_read BIGINT UNSIGNED NOT NULL DEFAULT 0,
+    entities_parsed BIGINT UNSIGNED NOT NULL DEFAULT 0,
+    section VARCHAR(32) NULL,
+    started_at DATETIME(6) NOT NULL DEFAULT CURRENT_TIMESTAMP(6),
+    finished_at DATETIME(6) NULL,
+    updated_at DATETIME(6) NOT NULL DEFAULT CURRENT_TIMESTAMP(6) ON UPDATE CURRENT_TIMESTAMP(6),
+    CONSTRAINT fk_parse_jobs_file FOREIGN KEY (file_id) REFERENCES files(id) ON DELETE CASCADE
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
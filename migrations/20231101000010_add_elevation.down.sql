@@ -0,0 +1,9 @@
+ALTER TABLE layers
+DROP COLUMN max_z,
+DROP COLUMN min_z;
+
+ALTER TABLE entities
+DROP INDEX idx_entities_max_z,
+DROP INDEX idx_entities_min_z,
+DROP COLUMN max_z,
+DROP COLUMN min_z;
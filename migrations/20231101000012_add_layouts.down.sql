@@ -0,0 +1,5 @@
+ALTER TABLE entities
+DROP INDEX idx_entities_layer_layout,
+DROP COLUMN layout;
+
+DROP TABLE IF EXISTS layouts;
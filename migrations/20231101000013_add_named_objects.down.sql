@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS entity_groups;
+DROP TABLE IF EXISTS named_objects;
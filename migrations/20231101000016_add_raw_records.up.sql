@@ -0,0 +1,14 @@
+CREATE TABLE IF NOT EXISTS raw_records (
+    id BIGINT UNSIGNED AUTO_INCREMENT PRIMARY KEY,
+    file_id BIGINT UNSIGNED NOT NULL,
+    section VARCHAR(16) NOT NULL,
+    record_type VARCHAR(255) NOT NULL,
+    handle VARCHAR(16) NULL,
+    owner_handle VARCHAR(16) NULL,
+    layout VARCHAR(255) NULL,
+    position BIGINT UNSIGNED NOT NULL DEFAULT 0,
+    content LONGTEXT NOT NULL,
+    created_at DATETIME(6) NOT NULL DEFAULT CURRENT_TIMESTAMP(6),
+    CONSTRAINT fk_raw_records_file FOREIGN KEY (file_id) REFERENCES files(id) ON DELETE CASCADE,
+    INDEX idx_raw_records_file_section (file_id, section, position)
+) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4 COLLATE=utf8mb4_unicode_ci;
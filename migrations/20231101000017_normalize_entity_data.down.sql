@@ -0,0 +1,13 @@
+UPDATE entities
+SET data = JSON_REMOVE(JSON_SET(data, '$.points', JSON_EXTRACT(data, '$.vertices')), '$.vertices')
+WHERE JSON_CONTAINS_PATH(data, 'one', '$.seed_key')
+  AND JSON_CONTAINS_PATH(data, 'one', '$.vertices');
+
+UPDATE entities
+SET data = JSON_REMOVE(JSON_SET(data, '$.content', JSON_EXTRACT(data, '$.text')), '$.text')
+WHERE JSON_CONTAINS_PATH(data, 'one', '$.seed_key')
+  AND JSON_CONTAINS_PATH(data, 'one', '$.text');
+
+UPDATE entities
+SET data = JSON_REMOVE(data, '$.type', '$.version')
+WHERE JSON_CONTAINS_PATH(data, 'one', '$.version');